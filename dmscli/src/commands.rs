@@ -8,7 +8,10 @@ pub use list::*;
 mod simulation;
 
 mod convert;
-pub use convert::Convert;
+pub use convert::{write_solution_json, Convert};
+
+mod actions;
+pub use actions::Actions;
 
 /// All CLI commands available in this binary.
 #[derive(clap::Subcommand, Debug)]
@@ -32,12 +35,26 @@ pub enum Command {
     /// Print the list of all possible optimizations.
     ListAllOpt,
 
+    /// Benchmark all possible optimizations on a problem.
+    #[command(alias = "b")]
+    BenchmarkAll(BenchmarkAll),
+
+    /// Compare the exploration time and memory usage of the state indexers on a problem.
+    BenchIndexer(BenchIndexer),
+
     /// Load the solution and exit (check integrity).
     Load(Load),
 
+    /// Solve the problem of a saved solution again with its saved config and optimizations,
+    /// and check that the results are identical.
+    Reproduce(Reproduce),
+
     /// Convert a binary solution file to JSON.
     #[command(alias = "c")]
     Convert(Convert),
+
+    /// Compare the actions of two action sets in a given state.
+    Actions(Actions),
 }
 
 #[derive(clap::Args, Debug)]
@@ -50,6 +67,16 @@ pub struct Run {
     /// Don't simulate the restoration process.
     #[arg(long, default_value_t = false)]
     no_sim: bool,
+    /// Directory to save the results JSON file and the solutions to.
+    #[arg(long, default_value = RESULTS_DIR)]
+    output_dir: PathBuf,
+    /// File name template of the solutions without extension, relative to the solutions
+    /// directory. May contain `/` for subdirectories.
+    ///
+    /// Placeholders: {experiment}, {problem}, {indexer}, {actions}, {transitions},
+    /// {synthesizer}, {index} and {timestamp}.
+    #[arg(long, default_value = "{index}")]
+    name_template: String,
 }
 
 #[derive(clap::Args, Debug)]
@@ -65,9 +92,54 @@ pub struct Solve {
     /// Action applier class.
     #[arg(short, long, default_value = "NaiveActionApplier")]
     transition: String,
+    /// Policy synthesizer class.
+    /// Defaults to `NaivePolicySynthesizer` for `NaiveActionApplier`,
+    /// `NaiveTimedPolicySynthesizer` otherwise.
+    #[arg(long)]
+    synthesizer: Option<String>,
     /// Print the results as JSON (Hint: redirect stdout)
     #[arg(short, long, default_value_t = false)]
     json: bool,
+    /// Save the solution as JSON to the given path.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Pretty-print the JSON solution with one line per state.
+    #[arg(long, default_value_t = false, requires = "output")]
+    pretty: bool,
+    /// Round the probabilities and costs in the JSON solution to this many significant digits.
+    #[arg(long, requires = "output", value_parser = clap::value_parser!(u32).range(1..))]
+    digits: Option<u32>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct BenchmarkAll {
+    /// Path to the JSON file containing the problem.
+    path: PathBuf,
+    /// Number of optimization combinations to benchmark in parallel, 0 for one per CPU.
+    ///
+    /// Each combination is solved in a single thread. Timings of parallel runs are wall-clock
+    /// times under contention, use 1 (default) for serial timing. Values other than 1 require the
+    /// `parallel` feature.
+    #[arg(short, long, default_value_t = 1)]
+    jobs: usize,
+    /// Print the results as JSON (Hint: redirect stdout)
+    #[arg(long, default_value_t = false)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct BenchIndexer {
+    /// Path to the JSON file containing the problem.
+    path: PathBuf,
+    /// Action set class.
+    #[arg(short, long, default_value = "NaiveActions")]
+    action: String,
+    /// Action applier class.
+    #[arg(short, long, default_value = "NaiveActionApplier")]
+    transition: String,
+    /// Print the results as JSON (Hint: redirect stdout)
+    #[arg(long, default_value_t = false)]
+    json: bool,
 }
 
 #[derive(clap::Args, Debug)]
@@ -91,6 +163,12 @@ pub struct Load {
     path: PathBuf,
 }
 
+#[derive(clap::Args, Debug)]
+pub struct Reproduce {
+    /// Path to the binary file containing the solution.
+    path: PathBuf,
+}
+
 impl Command {
     pub fn run(self) {
         match self {
@@ -99,8 +177,12 @@ impl Command {
             Command::TravelTimes(args) => args.run(),
             Command::Distances(args) => args.run(),
             Command::ListAllOpt => list_all_opt(),
+            Command::BenchmarkAll(args) => args.run(),
+            Command::BenchIndexer(args) => args.run(),
             Command::Load(args) => args.run(),
+            Command::Reproduce(args) => args.run(),
             Command::Convert(args) => args.run(),
+            Command::Actions(args) => args.run(),
         }
     }
 }
@@ -0,0 +1,66 @@
+use super::*;
+use dmslib::teams::state::State;
+
+#[derive(clap::Args, Debug)]
+pub struct Actions {
+    /// Path to the JSON file containing the problem.
+    path: PathBuf,
+    /// State in compact form, e.g., "[TG U D] [0:0 2:1]" where each team is index:time.
+    state: String,
+    /// First action set class.
+    first: String,
+    /// Second action set class.
+    second: String,
+}
+
+/// Print the given list of actions with a label.
+fn print_actions(label: &str, actions: &[&Vec<teams::TeamAction>]) {
+    println!("{} ({})", label.bold(), actions.len());
+    for action in actions {
+        println!("    {:?}", action);
+    }
+}
+
+impl Actions {
+    pub fn run(self) {
+        let Actions {
+            path,
+            state,
+            first,
+            second,
+        } = self;
+
+        let (name, problem, _config) = super::run::read_and_parse_team_problem(path);
+        eprintln!("{:18}{}", "Problem Name:".bold(), name);
+
+        let state: State = match state.parse() {
+            Ok(state) => state,
+            Err(e) => fatal_error!(1, "Cannot parse the state: {}", e),
+        };
+        let get_actions =
+            |action_set: &str| match teams::actions_in_state(&problem.graph, &state, action_set) {
+                Ok(actions) => actions,
+                Err(e) => fatal_error!(1, "Cannot get the actions of {}: {}", action_set, e),
+            };
+        let first_actions = get_actions(&first);
+        let second_actions = get_actions(&second);
+
+        let common = first_actions
+            .iter()
+            .filter(|action| second_actions.contains(action))
+            .collect::<Vec<_>>();
+        let only_first = first_actions
+            .iter()
+            .filter(|action| !second_actions.contains(action))
+            .collect::<Vec<_>>();
+        let only_second = second_actions
+            .iter()
+            .filter(|action| !first_actions.contains(action))
+            .collect::<Vec<_>>();
+
+        println!("{:18}{}", "State:".bold(), state);
+        print_actions("Common actions", &common);
+        print_actions(&format!("Only in {first}"), &only_first);
+        print_actions(&format!("Only in {second}"), &only_second);
+    }
+}
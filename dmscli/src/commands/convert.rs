@@ -6,6 +6,49 @@ pub struct Convert {
     binary: PathBuf,
     /// Path to the JSON file that will be created.
     json: PathBuf,
+    /// Pretty-print the JSON with one line per state.
+    #[arg(long, default_value_t = false)]
+    pretty: bool,
+    /// Round the probabilities and costs to this many significant digits.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    digits: Option<u32>,
+}
+
+/// Write the solution as JSON to a new file at the given path.
+///
+/// If `digits` is given, the probabilities and costs of the transitions are rounded to that many
+/// significant digits.
+pub fn write_solution_json(
+    solution: &GenericTeamSolution,
+    json_path: &Path,
+    pretty: bool,
+    digits: Option<u32>,
+) {
+    let json = match (pretty, digits) {
+        (true, None) => solution.to_pretty_json(),
+        (false, None) => serde_json::to_string(solution),
+        (true, Some(digits)) => solution.rounded(digits).to_pretty_json(),
+        (false, Some(digits)) => serde_json::to_string(&solution.rounded(digits)),
+    };
+
+    let json = match json {
+        Ok(json) => json,
+        Err(e) => fatal_error!(1, "Error while converting to JSON: {}", e),
+    };
+
+    let mut file = match std::fs::File::options()
+        .read(false)
+        .write(true)
+        .create_new(true)
+        .open(json_path)
+    {
+        Ok(file) => file,
+        Err(e) => fatal_error!(1, "Error while opening the JSON file: {}", e),
+    };
+
+    if let Err(e) = file.write_all(json.as_bytes()) {
+        fatal_error!(1, "Error while writing the JSON file: {}", e);
+    }
 }
 
 impl Convert {
@@ -14,6 +57,8 @@ impl Convert {
         let Convert {
             binary: binary_path,
             json: json_path,
+            pretty,
+            digits,
         } = self;
 
         if json_path.exists() {
@@ -25,31 +70,7 @@ impl Convert {
             Err(e) => fatal_error!(1, "Error while loading the solution: {}", e),
         };
 
-        let json = match save_file.solution {
-            GenericTeamSolution::Timed(solution) => serde_json::to_string(&solution),
-            GenericTeamSolution::Regular(solution) => serde_json::to_string(&solution),
-        };
-
-        let json = match json {
-            Ok(json) => json,
-            Err(e) => fatal_error!(1, "Error while converting to JSON: {}", e),
-        };
-
-        let mut file = match std::fs::File::options()
-            .read(false)
-            .write(true)
-            .create_new(true)
-            .open(&json_path)
-        {
-            Ok(file) => file,
-            Err(e) => fatal_error!(1, "Error while opening the JSON file: {}", e),
-        };
-
-        if let Err(e) = file.write_all(json.as_bytes()) {
-            fatal_error!(1, "Error while writing the JSON file: {}", e);
-        }
-
-        drop(file);
+        write_solution_json(&save_file.solution, &json_path, pretty, digits);
 
         println!(
             "{} Saved the JSON file: {}",
@@ -1,12 +1,14 @@
 use dmslib::{
-    io::Experiment,
+    io::{fs::SolveSettings, Experiment},
     teams::{Config, Problem},
 };
 
 /// Commands related to running experiments and solving problems.
 use super::*;
 
-fn read_and_parse_team_problem<P: AsRef<Path>>(path: P) -> (String, teams::Problem, teams::Config) {
+pub(super) fn read_and_parse_team_problem<P: AsRef<Path>>(
+    path: P,
+) -> (String, teams::Problem, teams::Config) {
     let mut problem = match TeamProblem::read_from_file(path) {
         Ok(x) => x,
         Err(err) => fatal_error!(1, "Cannot read team problem: {}", err),
@@ -31,6 +33,7 @@ fn solve(
         &optimization.indexer,
         &optimization.actions,
         &optimization.transitions,
+        optimization.get_synthesizer(),
     )
 }
 
@@ -47,10 +50,15 @@ fn get_optimization_result(
     }
 }
 
-fn print_optimizations(optimization: &OptimizationInfo) {
+pub(super) fn print_optimizations(optimization: &OptimizationInfo) {
     eprintln!("{:18}{}", "Indexer:".bold(), optimization.indexer);
     eprintln!("{:18}{}", "Actions:".bold(), optimization.actions);
     eprintln!("{:18}{}", "Transitions:".bold(), optimization.transitions);
+    eprintln!(
+        "{:18}{}",
+        "Synthesizer:".bold(),
+        optimization.get_synthesizer()
+    );
 }
 
 fn print_benchmark_result(result: &Result<BenchmarkResult, SolveFailure>) {
@@ -77,10 +85,10 @@ fn run_experiment_task(
     optimization: &OptimizationInfo,
     problem: &Problem,
     config: &Config,
-    solutions_dir: Option<&PathBuf>,
+    solutions: Option<&SolutionOutput>,
     simulate: bool,
     current: usize,
-) -> serde_json::Value {
+) -> (OptimizationBenchmarkResult, serde_json::Value) {
     eprintln!();
     print_optimizations(optimization);
 
@@ -90,7 +98,8 @@ fn run_experiment_task(
     print_benchmark_result(&result.result);
     eprintln!();
 
-    let mut result = match serde_json::to_value(result) {
+    let benchmark_result = result;
+    let mut result = match serde_json::to_value(&benchmark_result) {
         Ok(s) => s,
         Err(e) => fatal_error!(1, "Error while serializing results: {}", e),
     };
@@ -110,10 +119,21 @@ fn run_experiment_task(
             );
         }
         // Save solution
-        if let Some(solutions_dir) = solutions_dir {
-            let mut path = solutions_dir.clone();
-            path.push(format!("{:03}.bin", current));
-            let err = dmslib::io::fs::save_solution(team_problem.clone(), solution, &path);
+        if let Some(solutions) = solutions {
+            let path = match solutions.get_path(team_problem, optimization, current) {
+                Ok(path) => path,
+                Err(e) => fatal_error!(1, "Cannot create solution path: {}", e),
+            };
+            let settings = SolveSettings {
+                config: config.clone(),
+                optimizations: optimization.clone(),
+            };
+            let err = dmslib::io::fs::save_solution(
+                team_problem.clone(),
+                solution,
+                Some(settings),
+                &path,
+            );
             if let Err(e) = err {
                 log::error!("Failed to save solution {}: {}", current, e);
             } else {
@@ -125,13 +145,81 @@ fn run_experiment_task(
         }
     }
 
-    result
+    (benchmark_result, result)
+}
+
+fn print_best_combinations(best: &BestCombinations) {
+    eprintln!("{}", "Best combinations:".green().bold());
+    for (title, optimization) in [("Fastest:", &best.fastest), ("Smallest:", &best.smallest)] {
+        if let Some(optimization) = optimization {
+            eprintln!(
+                "{:18}{}, {}, {}",
+                title.bold(),
+                optimization.indexer,
+                optimization.actions,
+                optimization.transitions
+            );
+        } else {
+            eprintln!("{:18}-", title.bold());
+        }
+    }
+    eprintln!();
+}
+
+/// Determines where to save the solutions of an experiment.
+struct SolutionOutput {
+    /// Directory of the solutions.
+    dir: PathBuf,
+    /// File name template, see [`Run::name_template`].
+    name_template: String,
+    /// File stem of the experiment.
+    experiment: String,
+    /// Start time of the experiment as seconds since the Unix epoch.
+    timestamp: String,
+}
+
+impl SolutionOutput {
+    /// Get the values of the placeholders in the name template.
+    fn get_values(
+        &self,
+        problem: &str,
+        optimization: &OptimizationInfo,
+        index: usize,
+    ) -> Vec<(&'static str, String)> {
+        vec![
+            ("experiment", self.experiment.clone()),
+            ("problem", problem.to_string()),
+            ("indexer", optimization.indexer.clone()),
+            ("actions", optimization.actions.clone()),
+            ("transitions", optimization.transitions.clone()),
+            ("synthesizer", optimization.get_synthesizer().to_string()),
+            ("index", format!("{:03}", index)),
+            ("timestamp", self.timestamp.clone()),
+        ]
+    }
+
+    /// Get an unused path for the solution of the given task, creating its directory if needed.
+    fn get_path(
+        &self,
+        team_problem: &TeamProblem,
+        optimization: &OptimizationInfo,
+        index: usize,
+    ) -> Result<PathBuf, dmslib::DmsError> {
+        let problem = team_problem.name.as_deref().unwrap_or("-");
+        let values = self.get_values(problem, optimization, index);
+        let name = expand_name_template(&self.name_template, &values)?;
+        let path = self.dir.join(name + ".bin");
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(unique_path(path))
+    }
 }
 
 /// Run all tasks in experiment.
 fn run_experiment(
     experiment: Experiment,
-    solutions_dir: Option<PathBuf>,
+    solutions: Option<SolutionOutput>,
     simulate: bool,
 ) -> Vec<serde_json::Value> {
     eprintln!(
@@ -170,6 +258,8 @@ fn run_experiment(
                 Err(err) => fatal_error!(1, "Error while parsing team problem: {}", err),
             };
 
+            let mut benchmark_results: Vec<OptimizationBenchmarkResult> = Vec::new();
+
             for optimization in &optimizations {
                 eprintln!(
                     "{}",
@@ -178,18 +268,24 @@ fn run_experiment(
                         .bold()
                 );
 
-                results.push(run_experiment_task(
+                let (benchmark_result, result) = run_experiment_task(
                     &team_problem,
                     optimization,
                     &problem,
                     &config,
-                    solutions_dir.as_ref(),
+                    solutions.as_ref(),
                     simulate,
                     current,
-                ));
+                );
+                benchmark_results.push(benchmark_result);
+                results.push(result);
 
                 current += 1;
             }
+
+            if benchmark_results.len() > 1 {
+                print_best_combinations(&best_combinations(&benchmark_results));
+            }
         }
     }
 
@@ -202,36 +298,49 @@ impl Run {
             path,
             no_save,
             no_sim,
+            output_dir,
+            name_template,
         } = self;
 
-        let mut results_path = match std::env::current_dir() {
-            Ok(p) => p,
-            Err(e) => fatal_error!(1, "Cannot open current working directory: {}", e),
+        let experiment_name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let timestamp = match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(d) => d.as_secs().to_string(),
+            Err(e) => fatal_error!(1, "Cannot get the current time: {}", e),
         };
 
-        results_path.push(RESULTS_DIR);
-        if let Err(e) = std::fs::create_dir_all(&results_path) {
+        if let Err(e) = std::fs::create_dir_all(&output_dir) {
             fatal_error!(1, "Cannot create results directory: {e}");
         }
-        results_path.push(path.file_name().unwrap());
-        if results_path.exists() {
-            // TODO: overwrite this
-            fatal_error!(
-                1,
-                "Results file is present: {}",
-                results_path.to_string_lossy()
-            );
-        }
-        let results_path = results_path;
+        let results_path = unique_path(output_dir.join(path.file_name().unwrap()));
 
-        let solutions_dir = if no_save {
+        let solutions = if no_save {
             None
         } else {
             let dir = results_path.with_extension("d");
             if let Err(e) = std::fs::create_dir_all(&dir) {
                 fatal_error!(1, "Cannot create solutions directory: {e}");
             }
-            Some(dir)
+            let solutions = SolutionOutput {
+                dir,
+                name_template,
+                experiment: experiment_name,
+                timestamp,
+            };
+            // Check the template before solving.
+            let optimization = OptimizationInfo {
+                indexer: String::new(),
+                actions: String::new(),
+                transitions: String::new(),
+                synthesizer: Some(String::new()),
+            };
+            let values = solutions.get_values("-", &optimization, 0);
+            if let Err(e) = expand_name_template(&solutions.name_template, &values) {
+                fatal_error!(1, "Invalid name template: {}", e);
+            }
+            Some(solutions)
         };
 
         let experiment = match read_experiment_from_file(&path) {
@@ -239,7 +348,7 @@ impl Run {
             Err(err) => fatal_error!(1, "Cannot parse experiment: {}", err),
         };
 
-        let results = run_experiment(experiment, solutions_dir, !no_sim);
+        let results = run_experiment(experiment, solutions, !no_sim);
 
         let serialized = match serde_json::to_string_pretty(&results) {
             Ok(s) => s,
@@ -264,9 +373,17 @@ impl Solve {
             indexer,
             action,
             transition,
+            synthesizer,
             json,
+            output,
+            pretty,
+            digits,
         } = self;
 
+        if output.as_ref().is_some_and(|output| output.exists()) {
+            fatal_error!(1, "Output file already exists!");
+        }
+
         let (name, problem, config) = read_and_parse_team_problem(path);
 
         eprintln!("{:18}{}", "Problem Name:".bold(), name);
@@ -275,6 +392,7 @@ impl Solve {
             indexer,
             actions: action,
             transitions: transition,
+            synthesizer,
         };
 
         print_optimizations(&optimizations);
@@ -283,12 +401,20 @@ impl Solve {
         std::io::stderr().flush().unwrap();
 
         let solution = solve(&problem, &config, &optimizations);
-        // TODO: save solution
 
         let result = get_optimization_result(&solution, optimizations);
 
         print_benchmark_result(&result.result);
 
+        if let (Some(output), Ok(solution)) = (output, &solution) {
+            write_solution_json(solution, &output, pretty, digits);
+            eprintln!(
+                "{} Saved the JSON file: {}",
+                "SUCCESS!".bold().green(),
+                output.display()
+            );
+        }
+
         if json {
             let serialized = match serde_json::to_string_pretty(&result) {
                 Ok(s) => s,
@@ -298,3 +424,113 @@ impl Solve {
         }
     }
 }
+
+impl BenchmarkAll {
+    pub fn run(self) {
+        let BenchmarkAll { path, jobs, json } = self;
+
+        #[cfg(not(feature = "parallel"))]
+        if jobs != 1 {
+            fatal_error!(
+                1,
+                "Parallel benchmarks require the parallel feature, use --jobs 1 or rebuild with --features parallel"
+            );
+        }
+
+        let (name, problem, config) = read_and_parse_team_problem(path);
+
+        eprintln!("{:18}{}", "Problem Name:".bold(), name);
+        eprintln!("{:18}{}", "Parallel jobs:".bold(), jobs);
+        eprint!("{}\r", "Solving...".green().bold());
+        std::io::stderr().flush().unwrap();
+
+        #[cfg(not(feature = "parallel"))]
+        let results = teams::benchmark_all(&problem.graph, problem.initial_teams, &config);
+        #[cfg(feature = "parallel")]
+        let results = if jobs == 1 {
+            teams::benchmark_all(&problem.graph, problem.initial_teams, &config)
+        } else {
+            match teams::benchmark_all_parallel(
+                &problem.graph,
+                problem.initial_teams,
+                &config,
+                jobs,
+            ) {
+                Ok(results) => results,
+                Err(e) => fatal_error!(1, "Cannot run the benchmarks: {}", e),
+            }
+        };
+
+        for result in &results {
+            eprintln!();
+            print_optimizations(&result.optimizations);
+            print_benchmark_result(&result.result);
+        }
+        eprintln!();
+        print_best_combinations(&best_combinations(&results));
+
+        if json {
+            let serialized = match serde_json::to_string_pretty(&results) {
+                Ok(s) => s,
+                Err(e) => fatal_error!(1, "Error while serializing results: {}", e),
+            };
+            println!("{}", serialized);
+        }
+    }
+}
+
+impl BenchIndexer {
+    pub fn run(self) {
+        let BenchIndexer {
+            path,
+            action,
+            transition,
+            json,
+        } = self;
+
+        let (name, problem, config) = read_and_parse_team_problem(path);
+
+        eprintln!("{:18}{}", "Problem Name:".bold(), name);
+        eprintln!("{:18}{}", "Actions:".bold(), action);
+        eprintln!("{:18}{}", "Transitions:".bold(), transition);
+        eprint!("{}\r", "Solving...".green().bold());
+        std::io::stderr().flush().unwrap();
+
+        let results = teams::benchmark_indexers(
+            &problem.graph,
+            problem.initial_teams,
+            &config,
+            &action,
+            &transition,
+        );
+
+        eprintln!(
+            "{:42}{:>12}{:>18}{:>20}",
+            "Indexer".bold(),
+            "States".bold(),
+            "Exploration (s)".bold(),
+            "Max memory (bytes)".bold()
+        );
+        for result in &results {
+            match &result.result {
+                Ok(r) => eprintln!(
+                    "{:42}{:>12}{:>18.4}{:>20}",
+                    result.optimizations.indexer, r.states, r.generation_time, r.max_memory
+                ),
+                Err(e) => eprintln!(
+                    "{:42}{}",
+                    result.optimizations.indexer,
+                    format!("Benchmark failed: {e}").red()
+                ),
+            }
+        }
+
+        if json {
+            let serialized = match serde_json::to_string_pretty(&results) {
+                Ok(s) => s,
+                Err(e) => fatal_error!(1, "Error while serializing results: {}", e),
+            };
+            println!("{}", serialized);
+        }
+    }
+}
@@ -14,7 +14,9 @@ impl Load {
             Err(e) => fatal_error!(1, "Error while loading the solution: {}", e),
         };
 
-        let SaveFile { problem, solution } = save_file;
+        let SaveFile {
+            problem, solution, ..
+        } = save_file;
 
         let start_time = Instant::now();
 
@@ -40,8 +42,7 @@ impl Load {
                         let state = solution.get_state(i);
                         let successor = solution.get_state(transition.successor as usize);
 
-                        state.get_cost();
-                        // assert_eq!(state.get_cost(), transition.cost);
+                        // assert_eq!(state.get_cost(&graph), transition.cost);
                         assert_eq!(state.get_probability(&successor, &pfs), transition.p);
                         // assert_eq!(state.get_time)
 
@@ -58,3 +59,34 @@ impl Load {
         );
     }
 }
+
+impl Reproduce {
+    pub fn run(self) {
+        let Reproduce { path } = self;
+
+        let save_file = match dmslib::io::fs::load_solution(path) {
+            Ok(s) => s,
+            Err(e) => fatal_error!(1, "Error while loading the solution: {}", e),
+        };
+        let Some(settings) = &save_file.settings else {
+            fatal_error!(
+                1,
+                "The save file doesn't contain the config and optimizations."
+            );
+        };
+        super::run::print_optimizations(&settings.optimizations);
+
+        eprint!("{}\r", "Solving...".green().bold());
+        std::io::stderr().flush().unwrap();
+        let solution = match save_file.reproduce() {
+            Ok(solution) => solution,
+            Err(e) => fatal_error!(1, "Error while solving the problem: {}", e),
+        };
+
+        if solution.same_results(&save_file.solution) {
+            println!("{} The results are identical.", "SUCCESS!".bold().green());
+        } else {
+            fatal_error!(1, "The results are different from the saved solution.");
+        }
+    }
+}
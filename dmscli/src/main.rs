@@ -1,10 +1,11 @@
 use std::path::Path;
 use std::{io::Write, path::PathBuf};
 
-use dmslib::io::fs::read_problems_from_file;
+use dmslib::io::fs::{expand_name_template, read_problems_from_file, unique_path};
 use dmslib::io::{
-    read_experiment_from_file, BenchmarkResult, ExperimentTask, GenericTeamSolution,
-    OptimizationBenchmarkResult, OptimizationInfo, TeamProblem,
+    best_combinations, read_experiment_from_file, BenchmarkResult, BestCombinations,
+    ExperimentTask, GenericTeamSolution, OptimizationBenchmarkResult, OptimizationInfo,
+    TeamProblem,
 };
 use dmslib::teams;
 use dmslib::SolveFailure;
@@ -34,6 +35,12 @@ macro_rules! fatal_error {
 }
 
 fn main() {
+    // tracing-subscriber also collects the log records.
+    #[cfg(feature = "tracing")]
+    tracing_subscriber::fmt()
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .init();
+    #[cfg(not(feature = "tracing"))]
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     let args = Args::parse();
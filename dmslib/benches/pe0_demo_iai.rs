@@ -15,6 +15,8 @@ fn setup_1_team() -> (Problem, Config) {
             vec![dmslib::io::Team {
                 index: Some(0),
                 latlng: None,
+                name: None,
+                node: None,
             }],
             Some(30),
         )
@@ -30,10 +32,14 @@ fn setup_2_team() -> (Problem, Config) {
                 io::Team {
                     index: Some(1),
                     latlng: None,
+                    name: None,
+                    node: None,
                 },
                 io::Team {
                     index: Some(6),
                     latlng: None,
+                    name: None,
+                    node: None,
                 },
             ],
             Some(30),
@@ -4,16 +4,25 @@
 use crate::policy::*;
 use crate::teams;
 use crate::types::*;
-use crate::SolveFailure;
+use crate::{DmsError, SolveFailure};
 use teams::state::{BusState, TeamState};
 
 use ndarray::{Array1, Array2, ArrayView1};
 use serde::ser::{SerializeMap, SerializeSeq};
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 
+pub mod csv;
 mod experiments;
 pub mod fs;
 pub use experiments::*;
+mod explain;
+pub use explain::*;
+pub mod geojson;
+mod pretty;
+pub use pretty::to_depth_limited_json;
+mod replay;
+pub use replay::*;
 mod simulation;
 pub use simulation::*;
 
@@ -37,15 +46,21 @@ pub struct View {
     lng: f32,
 }
 
+/// Approximate radius of earth in km.
+pub const EARTH_RADIUS: f64 = 6373.0;
+
 impl LatLng {
     /// Given 2 latitude and longitude values, returns the distance in kilometers.
     /// Results in inaccuracies up to 0.5%
     ///
     /// [Source](https://stackoverflow.com/questions/19412462/getting-distance-between-two-points-based-on-latitude-longitude/)
     pub fn distance_to(&self, other: &LatLng) -> f64 {
-        // approximate radius of earth in km
-        const EARTH_RADIUS: f64 = 6373.0;
+        self.distance_to_with_radius(other, EARTH_RADIUS)
+    }
 
+    /// Like [`LatLng::distance_to`], but on a sphere with the given radius. The distance is in
+    /// the unit of the radius, e.g., 3959 gives the distance on earth in miles.
+    pub fn distance_to_with_radius(&self, other: &LatLng, radius: f64) -> f64 {
         let lat1 = self.0.to_radians();
         let lon1 = self.1.to_radians();
         let lat2 = other.0.to_radians();
@@ -54,7 +69,47 @@ impl LatLng {
         let dlat = lat2 - lat1;
         let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
         let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
-        EARTH_RADIUS * c
+        radius * c
+    }
+}
+
+/// Metric for computing the distance between two [`LatLng`] points.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DistanceMetric {
+    /// Great-circle distance in kilometers, computed with the haversine formula.
+    /// See [`LatLng::distance_to`].
+    #[default]
+    Haversine,
+    /// Distance in kilometers on the equirectangular projection.
+    /// Faster than [`DistanceMetric::Haversine`] and accurate for short distances.
+    Equirectangular,
+    /// Euclidean distance that treats the coordinates as points on a plane.
+    Euclidean,
+    /// Manhattan distance that treats the coordinates as points on a plane.
+    Manhattan,
+}
+
+impl DistanceMetric {
+    /// Get the distance between two points according to this metric.
+    pub fn distance(&self, a: &LatLng, b: &LatLng) -> f64 {
+        self.distance_with_radius(a, b, EARTH_RADIUS)
+    }
+
+    /// Get the distance between two points according to this metric, where the spherical
+    /// metrics use the given radius instead of [`EARTH_RADIUS`]. The planar metrics ignore it.
+    pub fn distance_with_radius(&self, a: &LatLng, b: &LatLng, radius: f64) -> f64 {
+        match self {
+            DistanceMetric::Haversine => a.distance_to_with_radius(b, radius),
+            DistanceMetric::Equirectangular => {
+                let lat1 = a.0.to_radians();
+                let lat2 = b.0.to_radians();
+                let x = (b.1 - a.1).to_radians() * ((lat1 + lat2) / 2.0).cos();
+                let y = lat2 - lat1;
+                radius * x.hypot(y)
+            }
+            DistanceMetric::Euclidean => (b.0 - a.0).hypot(b.1 - a.1),
+            DistanceMetric::Manhattan => (b.0 - a.0).abs() + (b.1 - a.1).abs(),
+        }
     }
 }
 
@@ -73,6 +128,15 @@ pub struct ExtBranch {
 pub struct Node {
     pub pf: f64,
     pub latlng: LatLng,
+    /// Optional name of this node, which can be used to refer to it instead of its index.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Optional weight of this node in the cost function, e.g., higher for buses that serve
+    /// critical loads. Defaults to 1 if another node has a weight, see [`teams::Graph::weights`].
+    ///
+    /// The sum of the weights of all nodes must be representable as [`Cost`].
+    #[serde(default)]
+    pub weight: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -81,6 +145,10 @@ pub struct Resource {
     /// "type" is a keyword...
     #[serde(rename = "type")]
     pub kind: Option<String>,
+    /// True if this energy resource is out of service. External branches from this resource
+    /// cannot energize their buses.
+    #[serde(default)]
+    pub outage: bool,
 }
 
 /// JSON representation of a distribution system graph.
@@ -94,6 +162,69 @@ pub struct Graph {
     pub resources: Vec<Resource>,
 }
 
+impl Graph {
+    /// Find the index of the node with the given name.
+    ///
+    /// Returns [`DmsError::Validation`] if there's no such node or the name is ambiguous.
+    pub fn find_node(&self, name: &str) -> Result<usize, DmsError> {
+        let mut indices = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.name.as_deref() == Some(name))
+            .map(|(i, _)| i);
+        match (indices.next(), indices.next()) {
+            (Some(i), None) => Ok(i),
+            (Some(_), Some(_)) => Err(DmsError::Validation(format!(
+                "Node name {name:?} is ambiguous"
+            ))),
+            (None, _) => Err(DmsError::Validation(format!("No node named {name:?}"))),
+        }
+    }
+}
+
+/// Write the canonical representation of the given JSON value to the output, i.e., with sorted
+/// object keys and `-0.0` replaced with `0.0`.
+fn write_canonical_json(value: &serde_json::Value, output: &mut String) {
+    use serde_json::Value;
+    match value {
+        Value::Number(n) => {
+            if n.is_f64() {
+                // Adding 0.0 turns -0.0 into 0.0.
+                let f = n.as_f64().unwrap() + 0.0;
+                output.push_str(&format!("{f:?}"));
+            } else {
+                output.push_str(&n.to_string());
+            }
+        }
+        Value::Array(array) => {
+            output.push('[');
+            for (i, element) in array.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_canonical_json(element, output);
+            }
+            output.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            output.push('{');
+            for (i, (key, element)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                output.push_str(&Value::String(key.clone()).to_string());
+                output.push(':');
+                write_canonical_json(element, output);
+            }
+            output.push('}');
+        }
+        _ => output.push_str(&value.to_string()),
+    }
+}
+
 /// Summarized information about a distribution system [`Graph`].
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GraphEntry {
@@ -107,6 +238,23 @@ pub struct GraphEntry {
 pub struct Team {
     pub index: Option<usize>,
     pub latlng: Option<LatLng>,
+    /// Optional name of the team, e.g., crew name, for labeling the outputs.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Optional name of the node where this team starts, resolved to its index in
+    /// [`TeamProblem::prepare`]. Ignored if `index` is given.
+    #[serde(default)]
+    pub node: Option<String>,
+}
+
+/// A possible start configuration of the teams with its probability, see
+/// [`TeamProblem::start_distribution`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WeightedStart {
+    /// Relative weight of this configuration, normalized by the sum of all weights.
+    pub weight: f64,
+    /// Start of each team in [`TeamProblem::teams`], in the same order.
+    pub teams: Vec<Team>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -117,24 +265,58 @@ pub enum TimeFunc {
     DirectDistance {
         multiplier: Option<f64>,
         divider: Option<f64>,
+        /// Distance metric, [`DistanceMetric::Haversine`] by default.
+        #[serde(default)]
+        metric: DistanceMetric,
+        /// Radius of the sphere for the spherical metrics, [`EARTH_RADIUS`] in km by default.
+        /// Distances are in the unit of the radius.
+        #[serde(default)]
+        radius: Option<f64>,
     },
     /// Use a constant value to build travel time matrix (except for diagonal entries).
     Constant { constant: Time },
 }
 
 impl TimeFunc {
+    /// Get a new time function with all travel times multiplied by the given scale factor.
+    ///
+    /// For [`TimeFunc::DirectDistance`], the scaling happens before rounding up.
+    /// Hence, it allows representing travel times in smaller units (e.g., tenths of a minute)
+    /// for better precision.
+    pub fn scaled(self, scale: f64) -> TimeFunc {
+        match self {
+            TimeFunc::DirectDistance {
+                multiplier,
+                divider,
+                metric,
+                radius,
+            } => TimeFunc::DirectDistance {
+                multiplier: Some(multiplier.unwrap_or(1.0) * scale),
+                divider,
+                metric,
+                radius,
+            },
+            TimeFunc::Constant { constant } => TimeFunc::Constant {
+                constant: ((constant as f64) * scale).round() as Time,
+            },
+        }
+    }
+
     /// Get distance between two points according to this function.
     pub fn get_distance(&self, a: &LatLng, b: &LatLng) -> Time {
         match self {
             TimeFunc::DirectDistance {
                 multiplier,
                 divider,
+                metric,
+                radius,
             } => {
                 let mut mul = multiplier.unwrap_or(1.0);
                 if let Some(divider) = divider {
                     mul /= divider;
                 }
-                (a.distance_to(b) * mul).ceil() as Time
+                let radius = radius.unwrap_or(EARTH_RADIUS);
+                (metric.distance_with_radius(a, b, radius) * mul).ceil() as Time
             }
             TimeFunc::Constant { constant } => *constant,
         }
@@ -149,22 +331,25 @@ impl TimeFunc {
             TimeFunc::DirectDistance {
                 multiplier,
                 divider,
+                metric,
+                radius,
             } => {
                 let mut mul = multiplier.unwrap_or(1.0);
                 if let Some(divider) = divider {
                     mul /= divider;
                 }
+                let radius = radius.unwrap_or(EARTH_RADIUS);
                 for (i1, l1) in locations.iter().enumerate() {
                     for (i2, l2) in locations.iter().enumerate().skip(i1 + 1) {
-                        let time = (l1.distance_to(l2) * mul).ceil() as Time;
+                        let time =
+                            (metric.distance_with_radius(l1, l2, radius) * mul).ceil() as Time;
                         travel_times[(i1, i2)] = time;
                         travel_times[(i2, i1)] = time;
                     }
                 }
             }
             TimeFunc::Constant { constant } => {
-                let mut travel_times = Array2::<Time>::from_elem((lnodes, lnodes), *constant);
-
+                travel_times.fill(*constant);
                 for i in 0..lnodes {
                     travel_times[(i, i)] = 0;
                 }
@@ -180,10 +365,58 @@ impl Default for TimeFunc {
         Self::DirectDistance {
             multiplier: None,
             divider: None,
+            metric: DistanceMetric::default(),
+            radius: None,
         }
     }
 }
 
+/// Get the initial states of the given teams.
+///
+/// Teams are located at the node with the given index or name, or at the given location, which
+/// is added to the locations of the nodes.
+fn resolve_teams(
+    teams: &[Team],
+    graph: &Graph,
+    locations: &mut Vec<LatLng>,
+) -> Result<Vec<TeamState>, SolveFailure> {
+    for (i, team) in teams.iter().enumerate() {
+        if team.index.is_none() && team.node.is_none() && team.latlng.is_none() {
+            return Err(SolveFailure::BadInput(format!(
+                "Team {i} has neither index, node nor latlng!"
+            )));
+        }
+    }
+    let team_indices: Vec<Option<usize>> = teams
+        .iter()
+        .enumerate()
+        .map(|(i, team)| match (&team.index, &team.node) {
+            (Some(index), _) => Ok(Some(*index)),
+            (None, Some(node)) => graph.find_node(node).map(Some).map_err(|e| {
+                let team_name = team.name.clone().unwrap_or_else(|| i.to_string());
+                SolveFailure::BadInput(format!("Cannot resolve the node of team {team_name}: {e}"))
+            }),
+            (None, None) => Ok(None),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(teams
+        .iter()
+        .zip(team_indices)
+        .map(|(t, index)| {
+            let index = if let Some(i) = index {
+                i.try_into().expect("Bus index overflow")
+            } else {
+                let i = locations.len();
+                // We did error checking above
+                locations.push(t.latlng.as_ref().unwrap().clone());
+                i.try_into().expect("Bus index overflow")
+            };
+            TeamState { time: 0, index }
+        })
+        .collect())
+}
+
 /// Represents a field teams restoration problem.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TeamProblem {
@@ -191,17 +424,72 @@ pub struct TeamProblem {
     pub graph: Graph,
     pub teams: Vec<Team>,
     /// Optimization horizon for policy synthesis.
-    /// Use `None` to automatically determine it based on transitions.
-    pub horizon: Option<usize>,
+    /// Use `null` ([`HorizonSpec::Auto`]) to automatically determine it based on transitions.
+    #[serde(default)]
+    pub horizon: HorizonSpec,
     /// Probability of Failure override.
     /// If set, P_f values of all buses will be set to this.
     pub pfo: Option<f64>,
     /// Travel time function.
     #[serde(default, rename = "timeFunction")]
     pub time_func: TimeFunc,
+    /// If true, duplicate branches in the graph result in [`SolveFailure::BadInput`].
+    /// Otherwise, they are ignored with a warning.
+    #[serde(default, rename = "strictBranches")]
+    pub strict_branches: bool,
+    /// Optional repair time for each bus, which is added to the travel time of a team that is
+    /// dispatched to that bus.
+    #[serde(default, rename = "repairTimes")]
+    pub repair_times: Option<Vec<Time>>,
+    /// Optional fixed-point scale factor for time.
+    /// If set, travel times and repair times are multiplied by this factor, e.g., 10 for
+    /// representing them in tenths of the original unit.
+    ///
+    /// All times and values in the solution are reported in the scaled unit.
+    #[serde(default, rename = "timeScale")]
+    pub time_scale: Option<f64>,
+    /// Locations of depots which teams can move to without any energization effect.
+    /// Teams can only move to depots if this is not empty.
+    #[serde(default)]
+    pub depots: Vec<LatLng>,
+    /// Optional deadline for each bus, `null` for the buses without a deadline.
+    /// Each bus contributes an additional cost of 1 for every time unit it remains unenergized
+    /// past its deadline.
+    #[serde(default)]
+    pub deadlines: Option<Vec<Option<Time>>>,
+    /// Optional distribution over the start configurations of the teams if their exact
+    /// locations are uncertain. The starts given in [`TeamProblem::teams`] are ignored in this
+    /// case, and the teams only determine the team names.
+    ///
+    /// See [`teams::solve_start_distribution`].
+    #[serde(default, rename = "startDistribution")]
+    pub start_distribution: Option<Vec<WeightedStart>>,
 }
 
 impl TeamProblem {
+    /// Get the names of the teams and the nodes in this problem, `None` for the unnamed ones.
+    pub fn get_names(&self) -> (Vec<Option<String>>, Vec<Option<String>>) {
+        (
+            self.teams.iter().map(|team| team.name.clone()).collect(),
+            self.graph
+                .nodes
+                .iter()
+                .map(|node| node.name.clone())
+                .collect(),
+        )
+    }
+
+    /// Get a hash of this problem that is stable across processes.
+    ///
+    /// The problem is converted to a canonical JSON representation with sorted object keys and
+    /// normalized floats before hashing, so equal problems have equal hashes.
+    pub fn canonical_hash(&self) -> u64 {
+        let value = serde_json::to_value(self).expect("Cannot convert the problem to JSON");
+        let mut canonical = String::new();
+        write_canonical_json(&value, &mut canonical);
+        crate::utils::fnv1a_hash(canonical.as_bytes())
+    }
+
     /// Get the distance matrix for the system components + any additional starting positions for
     /// the teams.
     pub fn get_distances(&self) -> Result<Array2<f64>, String> {
@@ -215,8 +503,8 @@ impl TeamProblem {
         for (i, team) in self.teams.iter().enumerate() {
             if let Some(latlng) = &team.latlng {
                 locations.push(latlng.clone());
-            } else if team.index.is_none() {
-                return Err(format!("Team {i} has neither index nor latlng!"));
+            } else if team.index.is_none() && team.node.is_none() {
+                return Err(format!("Team {i} has neither index, node nor latlng!"));
             }
         }
 
@@ -237,6 +525,7 @@ impl TeamProblem {
     /// Prepare this problem before solving.
     /// - Add nodes for initial team positions.
     /// - Compute travel times matrix.
+    /// - Check feasibility, see [`teams::Problem::check_feasibility`].
     /// - ...and so on.
     pub fn prepare(self) -> Result<(teams::Problem, teams::Config), SolveFailure> {
         let TeamProblem {
@@ -246,6 +535,12 @@ impl TeamProblem {
             horizon,
             pfo,
             time_func,
+            strict_branches,
+            repair_times,
+            time_scale,
+            depots,
+            deadlines,
+            start_distribution,
         } = self;
 
         let mut locations: Vec<LatLng> =
@@ -261,10 +556,22 @@ impl TeamProblem {
                 .collect()
         };
 
-        for (i, team) in teams.iter().enumerate() {
-            if team.index.is_none() && team.latlng.is_none() {
+        if let Some(repair_times) = &repair_times {
+            if repair_times.len() != graph.nodes.len() {
+                return Err(SolveFailure::BadInput(format!(
+                    "Number of repair times ({}) does not match the number of buses ({})!",
+                    repair_times.len(),
+                    graph.nodes.len()
+                )));
+            }
+        }
+
+        if let Some(deadlines) = &deadlines {
+            if deadlines.len() != graph.nodes.len() {
                 return Err(SolveFailure::BadInput(format!(
-                    "Team {i} has neither index nor latlng!"
+                    "Number of deadlines ({}) does not match the number of buses ({})!",
+                    deadlines.len(),
+                    graph.nodes.len()
                 )));
             }
         }
@@ -277,21 +584,77 @@ impl TeamProblem {
             }
         }
 
-        let initial_teams: Vec<TeamState> = teams
+        let start_distribution = match start_distribution {
+            Some(starts) => {
+                let total_weight: f64 = starts.iter().map(|start| start.weight).sum();
+                if starts.is_empty() || total_weight.is_nan() || total_weight <= 0.0 {
+                    return Err(SolveFailure::BadInput(
+                        "Start distribution must have a positive total weight!".to_string(),
+                    ));
+                }
+                let starts = starts
+                    .iter()
+                    .enumerate()
+                    .map(|(i, start)| {
+                        if start.weight.is_nan() || start.weight < 0.0 {
+                            return Err(SolveFailure::BadInput(format!(
+                                "Weight of start configuration {i} must be non-negative!"
+                            )));
+                        }
+                        if start.teams.len() != teams.len() {
+                            return Err(SolveFailure::BadInput(format!(
+                                "Start configuration {i} has {} teams instead of {}!",
+                                start.teams.len(),
+                                teams.len()
+                            )));
+                        }
+                        let initial_teams = resolve_teams(&start.teams, &graph, &mut locations)?;
+                        Ok((start.weight / total_weight, initial_teams))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Some(starts)
+            }
+            None => None,
+        };
+        let initial_teams = match &start_distribution {
+            // The most likely configuration, the first one if tied.
+            Some(starts) => starts
+                .iter()
+                .reduce(|best, start| if start.0 > best.0 { start } else { best })
+                .map(|(_, teams)| teams.clone())
+                .unwrap(),
+            None => resolve_teams(&teams, &graph, &mut locations)?,
+        };
+
+        let depots: Vec<BusIndex> = depots
             .into_iter()
-            .map(|t| {
-                let index = if let Some(i) = t.index {
-                    i.try_into().expect("Bus index overflow")
-                } else {
-                    let i = locations.len();
-                    // We did error checking above
-                    locations.push(t.latlng.as_ref().unwrap().clone());
-                    i.try_into().expect("Bus index overflow")
-                };
-                TeamState { time: 0, index }
+            .map(|latlng| {
+                let i = locations.len();
+                locations.push(latlng);
+                i.try_into().expect("Bus index overflow in depots")
             })
             .collect();
 
+        let (time_func, repair_times, deadlines) = if let Some(scale) = time_scale {
+            if scale.is_nan() || scale <= 0.0 {
+                return Err(SolveFailure::BadInput(format!(
+                    "Time scale must be positive, got {scale}!"
+                )));
+            }
+            let scale_time = |t: Time| ((t as f64) * scale).round() as Time;
+            let repair_times =
+                repair_times.map(|repair_times| repair_times.into_iter().map(scale_time).collect());
+            let deadlines = deadlines.map(|deadlines| {
+                deadlines
+                    .into_iter()
+                    .map(|deadline| deadline.map(scale_time))
+                    .collect()
+            });
+            (time_func.scaled(scale), repair_times, deadlines)
+        } else {
+            (time_func, repair_times, deadlines)
+        };
+
         let travel_times = time_func.get_travel_times(&locations);
 
         let mut branches = vec![Vec::<BusIndex>::new(); graph.nodes.len()];
@@ -299,16 +662,69 @@ impl TeamProblem {
         for branch in graph.branches.iter() {
             let a = branch.nodes.0;
             let b = branch.nodes.1;
-            // TODO: throw error on duplicate branch?
-            branches[a].push(b.try_into().expect("Bus index overflow in branch"));
-            branches[b].push(a.try_into().expect("Bus index overflow in branch"));
+            let a_index: BusIndex = a.try_into().expect("Bus index overflow in branch");
+            let b_index: BusIndex = b.try_into().expect("Bus index overflow in branch");
+            if branches[a].contains(&b_index) {
+                if strict_branches {
+                    return Err(SolveFailure::BadInput(format!(
+                        "Duplicate branch between buses {a} and {b}!"
+                    )));
+                }
+                log::warn!("Ignoring duplicate branch between buses {a} and {b}");
+                continue;
+            }
+            branches[a].push(b_index);
+            branches[b].push(a_index);
         }
 
         let mut connected: Vec<bool> = vec![false; graph.nodes.len()];
+        let mut bus_sources: Vec<Vec<usize>> = vec![Vec::new(); graph.nodes.len()];
 
         for x in graph.external.iter() {
+            if x.node >= graph.nodes.len() {
+                return Err(SolveFailure::BadInput(format!(
+                    "External branch from source {} to nonexistent bus {}!",
+                    x.source, x.node
+                )));
+            }
+            if x.source >= graph.resources.len() {
+                return Err(SolveFailure::BadInput(format!(
+                    "External branch to bus {} from nonexistent source {}!",
+                    x.node, x.source
+                )));
+            }
             connected[x.node] = true;
+            bus_sources[x.node].push(x.source);
         }
+        let source_outages: Vec<bool> = graph
+            .resources
+            .iter()
+            .map(|resource| resource.outage)
+            .collect();
+        let weights: Option<Vec<Cost>> = if graph.nodes.iter().any(|node| node.weight.is_some()) {
+            let weights: Vec<u32> = graph
+                .nodes
+                .iter()
+                .map(|node| node.weight.unwrap_or(1))
+                .collect();
+            // The cost of a state is at most the sum of all weights.
+            let total: u64 = weights.iter().map(|&weight| u64::from(weight)).sum();
+            if num_traits::cast::<u64, Cost>(total).is_none() {
+                return Err(SolveFailure::BadInput(format!(
+                    "Sum of the bus weights ({total}) is too large for the cost type!"
+                )));
+            }
+            Some(
+                weights
+                    .into_iter()
+                    .map(|weight| {
+                        num_traits::cast(weight).expect("Bus weight must fit in the cost type")
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        };
 
         let mut team_nodes = Array2::<f64>::zeros((locations.len(), 2));
         for (i, location) in locations.into_iter().enumerate() {
@@ -322,13 +738,36 @@ impl TeamProblem {
             connected,
             pfs,
             team_nodes,
+            repair_times: repair_times.map(Array1::from),
+            depots,
+            deadlines,
+            team_bus_allowed: None,
+            team_classes: None,
+            bus_sources: Some(bus_sources),
+            source_outages,
+            weights,
+            components_on_way: None,
+        };
+
+        let violations = graph.check_triangle_inequality();
+        if let Some(&(i, k, j)) = violations.first() {
+            log::warn!(
+                "Travel times violate the triangle inequality in {} cases, e.g., {i} -> {k} -> {j} \
+                is shorter than {i} -> {j}. Filtering the buses on the way may eliminate optimal \
+                actions.",
+                violations.len()
+            );
+        }
+
+        let problem = teams::Problem {
+            graph,
+            initial_teams,
+            start_distribution,
         };
+        problem.check_feasibility()?;
 
         Ok((
-            teams::Problem {
-                graph,
-                initial_teams,
-            },
+            problem,
             teams::Config {
                 horizon,
                 ..Default::default()
@@ -339,20 +778,57 @@ impl TeamProblem {
     /// Solve this field teams restoration problem without any optimizations and return a
     /// [`TeamSolution`] on success.
     pub fn solve_naive(self) -> Result<TeamSolution<RegularTransition>, SolveFailure> {
+        let (team_names, node_names) = self.get_names();
         let (problem, config) = self.prepare()?;
         let solution = teams::solve_naive(&problem.graph, problem.initial_teams, &config)?;
-        Ok(solution.into_io(problem.graph))
+        Ok(solution
+            .into_io(problem.graph)
+            .with_names(team_names, node_names))
+    }
+
+    /// Re-solve this problem without any optimizations from the given bus states with a new set
+    /// of teams, e.g., when reinforcement crews arrive or a crew leaves during an ongoing
+    /// restoration.
+    ///
+    /// Since the number of teams may change, the MDP is rebuilt from scratch starting from the
+    /// given bus states and the positions of the given teams.
+    pub fn resolve_with_teams(
+        mut self,
+        bus_states: Vec<BusState>,
+        teams: Vec<Team>,
+    ) -> Result<TeamSolution<RegularTransition>, SolveFailure> {
+        self.teams = teams;
+        let (team_names, node_names) = self.get_names();
+        let (problem, config) = self.prepare()?;
+        let start_state = teams::state::State {
+            buses: bus_states,
+            teams: problem.initial_teams,
+        };
+        let solution = teams::solve_naive_from_state(&problem.graph, start_state, &config)?;
+        Ok(solution
+            .into_io(problem.graph)
+            .with_names(team_names, node_names))
+    }
+
+    /// Count the states of this field teams restoration problem without solving it.
+    ///
+    /// The result is equal to the number of states in the solution of [`TeamProblem::solve_naive`].
+    pub fn count_states(self) -> Result<usize, SolveFailure> {
+        let (problem, config) = self.prepare()?;
+        teams::count_states(&problem.graph, problem.initial_teams, &config)
     }
 
     /// Solve the field-teams restoration problem with [`RegularTransition`]s (classic MDP
-    /// transitions without time) and the given action set class.
+    /// transitions without time) and the given action set and policy synthesizer classes.
     ///
     /// Returns a [`TeamSolution`] on success.
     pub fn solve_custom_regular(
         self,
         indexer: &str,
         action_set: &str,
+        synthesizer: &str,
     ) -> Result<TeamSolution<RegularTransition>, SolveFailure> {
+        let (team_names, node_names) = self.get_names();
         let (problem, config) = self.prepare()?;
         let solution = teams::solve_custom_regular(
             &problem.graph,
@@ -360,13 +836,17 @@ impl TeamProblem {
             &config,
             indexer,
             action_set,
+            synthesizer,
         )?;
-        Ok(solution.into_io(problem.graph))
+        Ok(solution
+            .into_io(problem.graph)
+            .with_names(team_names, node_names))
     }
 
     /// Solve the field-teams restoration problem with [`TimedTransition`]s and the given:
     /// - action applier class (variations of `TimedActionApplier<T>` where `T` determines time)
     /// - action set class
+    /// - policy synthesizer class
     ///
     /// Returns a [`TeamSolution`] on success.
     pub fn solve_custom_timed(
@@ -374,7 +854,9 @@ impl TeamProblem {
         indexer: &str,
         action_set: &str,
         action_applier: &str,
+        synthesizer: &str,
     ) -> Result<TeamSolution<TimedTransition>, SolveFailure> {
+        let (team_names, node_names) = self.get_names();
         let (problem, config) = self.prepare()?;
         let solution = teams::solve_custom_timed(
             &problem.graph,
@@ -383,13 +865,17 @@ impl TeamProblem {
             indexer,
             action_set,
             action_applier,
+            synthesizer,
         )?;
-        Ok(solution.into_io(problem.graph))
+        Ok(solution
+            .into_io(problem.graph)
+            .with_names(team_names, node_names))
     }
 
     /// Solve the field-teams restoration problem with the given:
     /// - action applier class
     /// - action set class
+    /// - policy synthesizer class
     ///
     /// Returns a [`BenchmarkResult`] on success.
     pub fn benchmark_custom(
@@ -397,6 +883,7 @@ impl TeamProblem {
         indexer: &str,
         action_set: &str,
         action_applier: &str,
+        synthesizer: &str,
     ) -> Result<BenchmarkResult, SolveFailure> {
         let (problem, config) = self.prepare()?;
         let solution = teams::benchmark_custom(
@@ -406,6 +893,7 @@ impl TeamProblem {
             indexer,
             action_set,
             action_applier,
+            synthesizer,
         )?;
         Ok(solution)
     }
@@ -419,6 +907,58 @@ impl TeamProblem {
             &config,
         ))
     }
+
+    /// Run all optimization combination possibilities on this field-teams restoration problem
+    /// with up to `jobs` combinations in parallel.
+    ///
+    /// See [`teams::benchmark_all_parallel`] for the caveats on timing.
+    #[cfg(feature = "parallel")]
+    pub fn benchmark_all_parallel(
+        self,
+        jobs: usize,
+    ) -> Result<Vec<OptimizationBenchmarkResult>, SolveFailure> {
+        let (problem, config) = self.prepare()?;
+        teams::benchmark_all_parallel(&problem.graph, problem.initial_teams, &config, jobs)
+    }
+
+    /// Solve this field-teams restoration problem with the given optimizations on the blocking
+    /// thread pool of the current tokio runtime, e.g., in async services that cannot block.
+    ///
+    /// The solve is not aborted if the returned future is dropped; it runs to completion in the
+    /// background. Panics if called outside a tokio runtime or if the solve panics.
+    #[cfg(feature = "async")]
+    pub fn solve_async(
+        self,
+        optimizations: OptimizationInfo,
+    ) -> impl std::future::Future<Output = Result<GenericTeamSolution, SolveFailure>> {
+        let handle = tokio::task::spawn_blocking(move || {
+            let (team_names, node_names) = self.get_names();
+            let (problem, config) = self.prepare()?;
+            let solution = teams::solve_custom(
+                &problem.graph,
+                problem.initial_teams,
+                &config,
+                &optimizations.indexer,
+                &optimizations.actions,
+                &optimizations.transitions,
+                optimizations.get_synthesizer(),
+            )?;
+            Ok(match solution {
+                GenericTeamSolution::Timed(s) => {
+                    GenericTeamSolution::Timed(s.with_names(team_names, node_names))
+                }
+                GenericTeamSolution::Regular(s) => {
+                    GenericTeamSolution::Regular(s.with_names(team_names, node_names))
+                }
+            })
+        });
+        async move {
+            match handle.await {
+                Ok(result) => result,
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            }
+        }
+    }
 }
 
 /// Parses a field-teams distribution system restoration problem from JSON.
@@ -473,11 +1013,31 @@ pub struct TeamSolution<T: Transition> {
     pub transitions: Vec<Vec<Vec<T>>>,
 
     /// Value function for each action.
+    ///
+    /// Contains only the value of the optimal action in each state if
+    /// [`Config::store_all_action_values`](crate::teams::Config::store_all_action_values) is
+    /// false.
     pub values: Vec<Vec<Value>>,
     /// Index of optimal actions in each state.
     pub policy: Vec<ActionIndex>,
     /// Given or computed Optimization horizon.
     pub horizon: usize,
+    /// Names of the teams, `None` for the unnamed ones.
+    /// Empty if the names are not given.
+    pub team_names: Vec<Option<String>>,
+    /// Names of the nodes, `None` for the unnamed ones.
+    /// Empty if the names are not given.
+    pub node_names: Vec<Option<String>>,
+    /// Quantum of time in which the transition times and the horizon of this solution are
+    /// expressed, whereas [`TeamSolution::travel_times`] are not quantized.
+    /// See [`teams::Config::time_quantum`].
+    pub time_quantum: Time,
+    /// True if the exploration was stopped due to the memory limit, see
+    /// [`teams::Config::allow_partial`].
+    pub partial: bool,
+    /// Team actions that produce the transitions of each state if
+    /// [`teams::Config::store_actions`] is set. See [`teams::Solution::actions`].
+    pub actions: Option<Vec<Vec<Vec<BusIndex>>>>,
 }
 
 /// A timed or regular [`TeamSolution`].
@@ -498,9 +1058,21 @@ impl<T: Transition> TeamSolution<T> {
             transitions: get_transition_count(&self.transitions),
             value: get_min_value(&self.values),
             horizon: self.horizon,
+            action_stats: None,
         }
     }
 
+    /// Set the names of the teams and the nodes for labeling this solution.
+    pub fn with_names(
+        mut self,
+        team_names: Vec<Option<String>>,
+        node_names: Vec<Option<String>>,
+    ) -> Self {
+        self.team_names = team_names;
+        self.node_names = node_names;
+        self
+    }
+
     /// Get the state at given index.
     pub fn get_state(&self, index: usize) -> teams::state::State {
         teams::state::State {
@@ -508,6 +1080,23 @@ impl<T: Transition> TeamSolution<T> {
             teams: self.teams.row(index).to_vec(),
         }
     }
+
+    /// Returns true if the given solution has the same MDP, values and policy, i.e., the
+    /// solutions are identical except for the measured times and memory usage.
+    pub fn same_results(&self, other: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.states == other.states
+            && self.teams == other.teams
+            && self.transitions == other.transitions
+            && self.values == other.values
+            && self.policy == other.policy
+            && self.horizon == other.horizon
+            && self.time_quantum == other.time_quantum
+            && self.partial == other.partial
+            && self.actions == other.actions
+    }
 }
 
 impl GenericTeamSolution {
@@ -518,30 +1107,473 @@ impl GenericTeamSolution {
             GenericTeamSolution::Regular(s) => s.get_benchmark_result(),
         }
     }
+
+    /// See [`TeamSolution::same_results`]. False if the transition types differ.
+    pub fn same_results(&self, other: &Self) -> bool {
+        match (self, other) {
+            (GenericTeamSolution::Timed(a), GenericTeamSolution::Timed(b)) => a.same_results(b),
+            (GenericTeamSolution::Regular(a), GenericTeamSolution::Regular(b)) => a.same_results(b),
+            _ => false,
+        }
+    }
 }
 
+/// Fields are serialized in a fixed order: `timed`, `totalTime`, `generationTime`, `maxMemory`,
+/// `teamNodes`, `travelTimes`, `states`, `teams`, `transitions`, `values`, `policy`, `horizon`,
+/// `teamNames`, `nodeNames`, `timeQuantum`, `partial`, followed by `actions` if present.
+/// `timed` is [`Transition::TIMED`], which tells the kind of a [`GenericTeamSolution`].
+///
+/// See [`TeamSolution::to_pretty_json`] for human-readable output, [`TeamSolution::rounded`]
+/// for rounding the transitions, and [`TeamSolution::without_terminal_loops`] for omitting the
+/// self-transitions of terminal states.
 impl<T: Transition> Serialize for TeamSolution<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(Some(8))?;
+        self.serialize_with(serializer, None, false)
+    }
+}
+
+/// Returns true if the given actions of the state at the given index belong to a terminal state,
+/// i.e., there's a single action with a single self-transition.
+fn is_terminal_loop<T: Transition>(index: usize, actions: &[Vec<T>]) -> bool {
+    match actions {
+        [action] => match action.as_slice() {
+            [t] => t.get_successor() as usize == index,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+impl<T: Transition> TeamSolution<T> {
+    /// Serialize this solution, rounding the probabilities and costs of the transitions to the
+    /// given number of significant digits if any, and omitting the self-transitions of the
+    /// terminal states if `omit_terminal_loops` is set.
+    fn serialize_with<S>(
+        &self,
+        serializer: S,
+        digits: Option<u32>,
+        omit_terminal_loops: bool,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(
+            16 + self.actions.is_some() as usize + omit_terminal_loops as usize,
+        ))?;
+        map.serialize_entry("timed", &T::TIMED)?;
         map.serialize_entry("totalTime", &self.total_time)?;
         map.serialize_entry("generationTime", &self.generation_time)?;
+        map.serialize_entry("maxMemory", &self.max_memory)?;
 
         map.serialize_entry("teamNodes", &Array2Serializer(&self.team_nodes))?;
         map.serialize_entry("travelTimes", &Array2Serializer(&self.travel_times))?;
 
         map.serialize_entry("states", &Array2Serializer(&self.states))?;
         map.serialize_entry("teams", &Array2Serializer(&self.teams))?;
-        map.serialize_entry("transitions", &self.transitions)?;
+        if digits.is_none() && !omit_terminal_loops {
+            map.serialize_entry("transitions", &self.transitions)?;
+        } else {
+            map.serialize_entry(
+                "transitions",
+                &TransitionsSerializer {
+                    transitions: &self.transitions,
+                    digits,
+                    omit_terminal_loops,
+                },
+            )?;
+        }
+
+        if omit_terminal_loops {
+            let terminal_costs: Vec<Cost> = self
+                .transitions
+                .iter()
+                .enumerate()
+                .filter(|(i, state)| is_terminal_loop(*i, state))
+                .map(|(_, state)| state[0][0].get_cost())
+                .collect();
+            map.serialize_entry("terminalCosts", &terminal_costs)?;
+        }
 
         map.serialize_entry("values", &self.values)?;
         map.serialize_entry("policy", &self.policy)?;
+        map.serialize_entry("horizon", &self.horizon)?;
+        map.serialize_entry("teamNames", &self.team_names)?;
+        map.serialize_entry("nodeNames", &self.node_names)?;
+        map.serialize_entry("timeQuantum", &self.time_quantum)?;
+        map.serialize_entry("partial", &self.partial)?;
+        if let Some(actions) = &self.actions {
+            map.serialize_entry("actions", actions)?;
+        }
         map.end()
     }
 }
 
+/// Private helper for [`TeamSolution`] deserialization.
+/// Field names and layout match the custom [`Serialize`] implementation.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TeamSolutionDeserializer<T> {
+    /// See [`Transition::TIMED`].
+    timed: bool,
+    total_time: f64,
+    generation_time: f64,
+    #[serde(default)]
+    max_memory: usize,
+    #[serde(deserialize_with = "deserialize_array2")]
+    team_nodes: Array2<f64>,
+    #[serde(deserialize_with = "deserialize_array2")]
+    travel_times: Array2<Time>,
+    #[serde(deserialize_with = "deserialize_array2")]
+    states: Array2<BusState>,
+    #[serde(deserialize_with = "deserialize_array2")]
+    teams: Array2<TeamState>,
+    transitions: Vec<Vec<Vec<T>>>,
+    values: Vec<Vec<Value>>,
+    policy: Vec<ActionIndex>,
+    #[serde(default)]
+    horizon: usize,
+    #[serde(default)]
+    team_names: Vec<Option<String>>,
+    #[serde(default)]
+    node_names: Vec<Option<String>>,
+    #[serde(default = "default_time_quantum")]
+    time_quantum: Time,
+    #[serde(default)]
+    partial: bool,
+    #[serde(default)]
+    actions: Option<Vec<Vec<Vec<BusIndex>>>>,
+    /// Costs of the omitted self-transitions of the terminal states in the order of the states,
+    /// see [`TeamSolution::without_terminal_loops`].
+    #[serde(default)]
+    terminal_costs: Option<Vec<Cost>>,
+}
+
+/// Solutions without a time quantum are not quantized.
+fn default_time_quantum() -> Time {
+    1
+}
+
+impl<T: Transition> TeamSolutionDeserializer<T> {
+    /// Convert to a [`TeamSolution`], restoring the omitted self-transitions of the terminal
+    /// states.
+    fn into_solution<E: serde::de::Error>(self) -> Result<TeamSolution<T>, E> {
+        let TeamSolutionDeserializer {
+            timed: _,
+            total_time,
+            generation_time,
+            max_memory,
+            team_nodes,
+            travel_times,
+            states,
+            teams,
+            mut transitions,
+            values,
+            policy,
+            horizon,
+            team_names,
+            node_names,
+            time_quantum,
+            partial,
+            actions,
+            terminal_costs,
+        } = self;
+        let mut terminal_costs = terminal_costs.map(Vec::into_iter);
+        for (i, state) in transitions.iter_mut().enumerate() {
+            // Omitted self-transition of a terminal state, see
+            // TeamSolution::without_terminal_loops
+            if state.is_empty() && i < states.nrows() {
+                let cost = match &mut terminal_costs {
+                    Some(costs) => costs
+                        .next()
+                        .ok_or_else(|| E::custom("fewer terminal costs than terminal states"))?,
+                    // Serialized before the terminal costs were added.
+                    None => states
+                        .row(i)
+                        .iter()
+                        .filter(|&&bus| bus != BusState::Energized)
+                        .count() as Cost,
+                };
+                state.push(vec![T::terminal_transition(i as StateIndex, cost)]);
+            }
+        }
+        Ok(TeamSolution {
+            total_time,
+            generation_time,
+            max_memory,
+            team_nodes,
+            travel_times,
+            states,
+            teams,
+            transitions,
+            values,
+            policy,
+            horizon,
+            team_names,
+            node_names,
+            time_quantum,
+            partial,
+            actions,
+        })
+    }
+}
+
+impl<'de, T: Transition + Deserialize<'de>> Deserialize<'de> for TeamSolution<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let solution = TeamSolutionDeserializer::<T>::deserialize(deserializer)?;
+        if solution.timed != T::TIMED {
+            return Err(serde::de::Error::custom(if solution.timed {
+                "expected a regular solution, found a timed one"
+            } else {
+                "expected a timed solution, found a regular one"
+            }));
+        }
+        solution.into_solution()
+    }
+}
+
+impl Serialize for GenericTeamSolution {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            GenericTeamSolution::Timed(s) => s.serialize(serializer),
+            GenericTeamSolution::Regular(s) => s.serialize(serializer),
+        }
+    }
+}
+
+/// Serializes a solution with options for smaller files and stable diffs, see
+/// [`TeamSolution::rounded`] and [`TeamSolution::without_terminal_loops`].
+///
+/// The options can be combined, e.g., `solution.rounded(3).without_terminal_loops()`.
+pub struct SolutionSerializer<'a, S: ?Sized> {
+    solution: &'a S,
+    /// Number of significant digits to round the transitions to, if any.
+    digits: Option<u32>,
+    /// Whether to omit the self-transitions of the terminal states.
+    omit_terminal_loops: bool,
+}
+
+impl<'a, S: ?Sized> SolutionSerializer<'a, S> {
+    /// Create a serializer without any options.
+    fn new(solution: &'a S) -> Self {
+        SolutionSerializer {
+            solution,
+            digits: None,
+            omit_terminal_loops: false,
+        }
+    }
+
+    /// See [`TeamSolution::rounded`].
+    pub fn rounded(self, digits: u32) -> Self {
+        SolutionSerializer {
+            digits: Some(digits),
+            ..self
+        }
+    }
+
+    /// See [`TeamSolution::without_terminal_loops`].
+    pub fn without_terminal_loops(self) -> Self {
+        SolutionSerializer {
+            omit_terminal_loops: true,
+            ..self
+        }
+    }
+}
+
+impl<T: Transition> TeamSolution<T> {
+    /// Get a view of this solution that is serialized with the probabilities and costs of the
+    /// transitions rounded to the given number of significant digits.
+    ///
+    /// See [`RoundedTransition`] for the error bounds. The other fields are not affected.
+    pub fn rounded(&self, digits: u32) -> SolutionSerializer<'_, Self> {
+        SolutionSerializer::new(self).rounded(digits)
+    }
+
+    /// Get a view of this solution that is serialized with an empty list of actions for each
+    /// terminal state instead of its self-transition, for the consumers that expect terminal
+    /// states to have no outgoing transitions. This solution is not modified.
+    ///
+    /// The costs of the omitted self-transitions are serialized as `terminalCosts` in the order
+    /// of the states, since they depend on the weights of the buses ([`teams::Graph::weights`])
+    /// and the objective. When such a solution is deserialized, the self-transitions are restored
+    /// with these costs, or with the number of buses that are not energized if they are missing.
+    pub fn without_terminal_loops(&self) -> SolutionSerializer<'_, Self> {
+        SolutionSerializer::new(self).without_terminal_loops()
+    }
+}
+
+impl GenericTeamSolution {
+    /// See [`TeamSolution::rounded`].
+    pub fn rounded(&self, digits: u32) -> SolutionSerializer<'_, Self> {
+        SolutionSerializer::new(self).rounded(digits)
+    }
+
+    /// See [`TeamSolution::without_terminal_loops`].
+    pub fn without_terminal_loops(&self) -> SolutionSerializer<'_, Self> {
+        SolutionSerializer::new(self).without_terminal_loops()
+    }
+}
+
+impl<'a, T: Transition> Serialize for SolutionSerializer<'a, TeamSolution<T>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.solution
+            .serialize_with(serializer, self.digits, self.omit_terminal_loops)
+    }
+}
+
+impl<'a> Serialize for SolutionSerializer<'a, GenericTeamSolution> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.solution {
+            GenericTeamSolution::Timed(s) => {
+                s.serialize_with(serializer, self.digits, self.omit_terminal_loops)
+            }
+            GenericTeamSolution::Regular(s) => {
+                s.serialize_with(serializer, self.digits, self.omit_terminal_loops)
+            }
+        }
+    }
+}
+
+/// Private helper for serializing the transitions of a solution with the options of
+/// [`SolutionSerializer`].
+struct TransitionsSerializer<'a, T> {
+    transitions: &'a [Vec<Vec<T>>],
+    digits: Option<u32>,
+    omit_terminal_loops: bool,
+}
+
+impl<'a, T: Transition> Serialize for TransitionsSerializer<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.transitions.len()))?;
+        for (i, state) in self.transitions.iter().enumerate() {
+            if self.omit_terminal_loops && is_terminal_loop(i, state) {
+                seq.serialize_element(&[] as &[Vec<T>])?;
+            } else if let Some(digits) = self.digits {
+                let state: Vec<Vec<RoundedTransition<T>>> = state
+                    .iter()
+                    .map(|action| {
+                        action
+                            .iter()
+                            .map(|t| RoundedTransition(t, digits))
+                            .collect()
+                    })
+                    .collect();
+                seq.serialize_element(&state)?;
+            } else {
+                seq.serialize_element(state)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+/// Timed and regular solutions are serialized in the same way, except that the time of each
+/// regular transition is 1. The kind of the solution is given by the `timed` field, since a
+/// timed solution may also consist of transitions that take 1 unit of time.
+impl<'de> Deserialize<'de> for GenericTeamSolution {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let solution = TeamSolutionDeserializer::<TimedTransition>::deserialize(deserializer)?;
+        let timed = solution.timed;
+        let solution = solution.into_solution()?;
+        if timed {
+            return Ok(GenericTeamSolution::Timed(solution));
+        }
+        let TeamSolution {
+            total_time,
+            generation_time,
+            max_memory,
+            team_nodes,
+            travel_times,
+            states,
+            teams,
+            transitions,
+            values,
+            policy,
+            horizon,
+            team_names,
+            node_names,
+            time_quantum,
+            partial,
+            actions,
+        } = solution;
+        let transitions = transitions
+            .into_iter()
+            .map(|state| {
+                state
+                    .into_iter()
+                    .map(|action| {
+                        action
+                            .into_iter()
+                            .map(|t| RegularTransition {
+                                successor: t.successor,
+                                p: t.p,
+                                cost: t.cost,
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(GenericTeamSolution::Regular(TeamSolution {
+            total_time,
+            generation_time,
+            max_memory,
+            team_nodes,
+            travel_times,
+            states,
+            teams,
+            transitions,
+            values,
+            policy,
+            horizon,
+            team_names,
+            node_names,
+            time_quantum,
+            partial,
+            actions,
+        }))
+    }
+}
+
+/// Private helper for 2D array deserialization from list of lists.
+/// All rows must have the same length.
+fn deserialize_array2<'de, D, T>(deserializer: D) -> Result<Array2<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let rows: Vec<Vec<T>> = Vec::deserialize(deserializer)?;
+    let nrows = rows.len();
+    let ncols = rows.first().map_or(0, Vec::len);
+    if rows.iter().any(|row| row.len() != ncols) {
+        return Err(serde::de::Error::custom(
+            "2D array rows have different lengths",
+        ));
+    }
+    let data: Vec<T> = rows.into_iter().flatten().collect();
+    Array2::from_shape_vec((nrows, ncols), data).map_err(serde::de::Error::custom)
+}
+
 /// Private helper for 2D array serialization.
 /// Array is serialized as list of lists.
 struct Array2Serializer<'a, T>(&'a Array2<T>);
@@ -594,4 +1626,8 @@ pub struct BenchmarkResult {
     pub value: Value,
     /// Given or computed Optimization horizon.
     pub horizon: usize,
+    /// Number of actions generated and surviving in each action set by name if it was counted.
+    /// See [`teams::Config::action_stats`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_stats: Option<BTreeMap<String, teams::ActionStats>>,
 }
@@ -0,0 +1,274 @@
+//! Import of distribution system graphs from CSV files.
+//!
+//! Each file starts with a header row, and columns are identified by their names in any order.
+//! Unknown columns are ignored. Fields may be quoted with `"` to contain commas, and `""`
+//! inside a quoted field is an escaped quote.
+use super::*;
+
+/// A CSV file with a header row.
+struct Table<'a> {
+    /// Name of the file for error messages.
+    name: &'a str,
+    /// Column names in the header row.
+    header: Vec<String>,
+    /// Line number and fields of each data row.
+    rows: Vec<(usize, Vec<String>)>,
+}
+
+/// Split a CSV line into its fields.
+fn split_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        if quoted {
+            if c != '"' {
+                field.push(c);
+            } else if chars.peek() == Some(&'"') {
+                chars.next();
+                field.push('"');
+            } else {
+                quoted = false;
+            }
+        } else if c == '"' {
+            quoted = true;
+        } else if c == ',' {
+            fields.push(field.trim().to_string());
+            field.clear();
+        } else {
+            field.push(c);
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field.trim().to_string());
+    Ok(fields)
+}
+
+impl<'a> Table<'a> {
+    /// Parse the given CSV text. Empty lines are skipped.
+    fn parse(name: &'a str, text: &str) -> Result<Table<'a>, String> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty());
+        let (header_line, header) = lines
+            .next()
+            .ok_or_else(|| format!("{name}: missing header row"))?;
+        let header = split_line(header)
+            .map_err(|e| format!("{name} line {header_line}: {e}"))?
+            .into_iter()
+            .map(|column| column.to_lowercase())
+            .collect::<Vec<_>>();
+        let rows = lines
+            .map(|(line_number, line)| {
+                let fields =
+                    split_line(line).map_err(|e| format!("{name} line {line_number}: {e}"))?;
+                if fields.len() > header.len() {
+                    return Err(format!(
+                        "{name} line {line_number}: expected at most {} fields, found {}",
+                        header.len(),
+                        fields.len()
+                    ));
+                }
+                Ok((line_number, fields))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Table { name, header, rows })
+    }
+
+    /// Get the index of the column with the given (lowercase) name if it exists.
+    fn optional_column(&self, column: &str) -> Option<usize> {
+        self.header.iter().position(|c| c == column)
+    }
+
+    /// Get the index of the column with the given (lowercase) name.
+    fn column(&self, column: &str) -> Result<usize, String> {
+        self.optional_column(column)
+            .ok_or_else(|| format!("{}: missing column {column:?}", self.name))
+    }
+
+    /// Get the field at the given column of a row, which is empty if the row is short.
+    fn field(fields: &[String], column: usize) -> &str {
+        fields.get(column).map_or("", String::as_str)
+    }
+
+    /// Parse the field at the given column of a row.
+    fn parse_field<T: std::str::FromStr>(
+        &self,
+        line_number: usize,
+        fields: &[String],
+        column: usize,
+    ) -> Result<T, String> {
+        let field = Self::field(fields, column);
+        field.parse().map_err(|_| {
+            format!(
+                "{} line {line_number}: invalid {} {field:?}",
+                self.name, self.header[column]
+            )
+        })
+    }
+}
+
+/// Find the index of the bus with the given id.
+fn find_bus(
+    bus_ids: &BTreeMap<String, usize>,
+    table: &Table,
+    line_number: usize,
+    id: &str,
+) -> Result<usize, String> {
+    bus_ids
+        .get(id)
+        .copied()
+        .ok_or_else(|| format!("{} line {line_number}: unknown bus {id:?}", table.name))
+}
+
+/// Build a [`Graph`] from CSV files describing its buses, branches, and energy sources.
+///
+/// - `buses_csv` has the columns `id`, `lat`, `lng`, and `pf`, and optionally `name` and
+///   `weight`.
+///   Buses are indexed in the order of rows, and `id` is an arbitrary unique string that other
+///   files use to refer to the bus.
+/// - `branches_csv` has the columns `from` and `to` with the ids of the buses.
+/// - `sources_csv` has the columns `id`, `lat`, `lng`, and `bus`, and optionally `type` and
+///   `outage`. Each row is an external branch from the source to the bus. If a source feeds
+///   multiple buses, it has a row for each, and the coordinates may be left empty after the
+///   first one.
+///
+/// Column names are case-insensitive. Empty `name`, `weight`, and `type` fields are `None`, and
+/// `outage` is `false` unless it's `true` or `1`. The name of the returned graph is empty.
+pub fn parse_graph(
+    buses_csv: &str,
+    branches_csv: &str,
+    sources_csv: &str,
+) -> Result<Graph, String> {
+    let buses = Table::parse("buses", buses_csv)?;
+    let id_column = buses.column("id")?;
+    let lat_column = buses.column("lat")?;
+    let lng_column = buses.column("lng")?;
+    let pf_column = buses.column("pf")?;
+    let name_column = buses.optional_column("name");
+    let weight_column = buses.optional_column("weight");
+    let mut bus_ids = BTreeMap::new();
+    let mut nodes = Vec::new();
+    for (line_number, fields) in &buses.rows {
+        let id = Table::field(fields, id_column);
+        if id.is_empty() {
+            return Err(format!("buses line {line_number}: empty id"));
+        }
+        if bus_ids.insert(id.to_string(), nodes.len()).is_some() {
+            return Err(format!("buses line {line_number}: duplicate bus id {id:?}"));
+        }
+        let pf: f64 = buses.parse_field(*line_number, fields, pf_column)?;
+        if !(0.0..=1.0).contains(&pf) {
+            return Err(format!(
+                "buses line {line_number}: pf {pf} is not in [0, 1]"
+            ));
+        }
+        nodes.push(Node {
+            pf,
+            latlng: LatLng(
+                buses.parse_field(*line_number, fields, lat_column)?,
+                buses.parse_field(*line_number, fields, lng_column)?,
+            ),
+            name: name_column
+                .map(|column| Table::field(fields, column))
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+            weight: match weight_column {
+                Some(column) if !Table::field(fields, column).is_empty() => {
+                    Some(buses.parse_field(*line_number, fields, column)?)
+                }
+                _ => None,
+            },
+        });
+    }
+
+    let branch_table = Table::parse("branches", branches_csv)?;
+    let from_column = branch_table.column("from")?;
+    let to_column = branch_table.column("to")?;
+    let branches = branch_table
+        .rows
+        .iter()
+        .map(|(line_number, fields)| {
+            let from = Table::field(fields, from_column);
+            let to = Table::field(fields, to_column);
+            Ok(Branch {
+                nodes: BranchNodes(
+                    find_bus(&bus_ids, &branch_table, *line_number, from)?,
+                    find_bus(&bus_ids, &branch_table, *line_number, to)?,
+                ),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let sources = Table::parse("sources", sources_csv)?;
+    let id_column = sources.column("id")?;
+    let lat_column = sources.column("lat")?;
+    let lng_column = sources.column("lng")?;
+    let bus_column = sources.column("bus")?;
+    let type_column = sources.optional_column("type");
+    let outage_column = sources.optional_column("outage");
+    let mut source_ids: BTreeMap<String, usize> = BTreeMap::new();
+    let mut resources: Vec<Resource> = Vec::new();
+    let mut external = Vec::new();
+    for (line_number, fields) in &sources.rows {
+        let id = Table::field(fields, id_column);
+        if id.is_empty() {
+            return Err(format!("sources line {line_number}: empty id"));
+        }
+        let has_coordinates = !Table::field(fields, lat_column).is_empty()
+            || !Table::field(fields, lng_column).is_empty();
+        let source = match source_ids.get(id) {
+            Some(&source) => {
+                if has_coordinates {
+                    let latlng = LatLng(
+                        sources.parse_field(*line_number, fields, lat_column)?,
+                        sources.parse_field(*line_number, fields, lng_column)?,
+                    );
+                    if latlng != resources[source].latlng {
+                        return Err(format!(
+                            "sources line {line_number}: coordinates of source {id:?} differ \
+                            from its first row"
+                        ));
+                    }
+                }
+                source
+            }
+            None => {
+                let outage = outage_column
+                    .map(|column| Table::field(fields, column).to_lowercase())
+                    .is_some_and(|outage| outage == "true" || outage == "1");
+                resources.push(Resource {
+                    latlng: LatLng(
+                        sources.parse_field(*line_number, fields, lat_column)?,
+                        sources.parse_field(*line_number, fields, lng_column)?,
+                    ),
+                    kind: type_column
+                        .map(|column| Table::field(fields, column))
+                        .filter(|kind| !kind.is_empty())
+                        .map(str::to_string),
+                    outage,
+                });
+                source_ids.insert(id.to_string(), resources.len() - 1);
+                resources.len() - 1
+            }
+        };
+        let bus = Table::field(fields, bus_column);
+        external.push(ExtBranch {
+            node: find_bus(&bus_ids, &sources, *line_number, bus)?,
+            source,
+        });
+    }
+
+    Ok(Graph {
+        name: String::new(),
+        branches,
+        external,
+        nodes,
+        resources,
+    })
+}
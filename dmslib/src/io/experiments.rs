@@ -10,6 +10,20 @@ pub struct OptimizationInfo {
     pub actions: String,
     /// Action applier
     pub transitions: String,
+    /// Policy synthesizer.
+    /// If not given, the default one for the action applier will be used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synthesizer: Option<String>,
+}
+
+impl OptimizationInfo {
+    /// Get the name of policy synthesizer, returning the default one if not given.
+    pub fn get_synthesizer(&self) -> &str {
+        match &self.synthesizer {
+            Some(synthesizer) => synthesizer,
+            None => teams::default_synthesizer(&self.transitions),
+        }
+    }
 }
 
 pub fn serialize_benchmark_result<S>(
@@ -41,6 +55,37 @@ pub struct OptimizationBenchmarkResult {
     pub result: Result<BenchmarkResult, SolveFailure>,
 }
 
+/// The best optimization combinations in a benchmark.
+#[derive(Serialize, Debug, Clone)]
+pub struct BestCombinations {
+    /// Successful combination with the minimum total time.
+    pub fastest: Option<OptimizationInfo>,
+    /// Successful combination with the minimum number of transitions.
+    pub smallest: Option<OptimizationInfo>,
+}
+
+/// Find the fastest and smallest optimization combinations in the given benchmark results.
+/// Failed benchmarks are skipped.
+pub fn best_combinations(results: &[OptimizationBenchmarkResult]) -> BestCombinations {
+    let successful = || {
+        results.iter().filter_map(|result| match &result.result {
+            Ok(benchmark) => Some((&result.optimizations, benchmark)),
+            Err(_) => None,
+        })
+    };
+    let fastest = successful()
+        .min_by(|a, b| {
+            a.1.total_time
+                .partial_cmp(&b.1.total_time)
+                .expect("Total time values must be comparable (not NaN)")
+        })
+        .map(|(optimizations, _)| optimizations.clone());
+    let smallest = successful()
+        .min_by_key(|(_, benchmark)| benchmark.transitions)
+        .map(|(optimizations, _)| optimizations.clone());
+    BestCombinations { fastest, smallest }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ExperimentTask {
     pub problems: Vec<TeamProblem>,
@@ -53,16 +98,15 @@ pub struct Experiment {
     pub tasks: Vec<ExperimentTask>,
 }
 
-pub fn read_experiment_from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Experiment> {
-    let content = std::fs::read_to_string(&path)?;
-    let value: serde_json::Value = serde_json::from_str(&content)?;
+pub fn read_experiment_from_file<P: AsRef<Path>>(path: P) -> Result<Experiment, DmsError> {
+    let value = fs::read_json_file(&path)?;
     read_experiment_from_value(value, path)
 }
 
 pub fn read_experiment_from_value<P: AsRef<Path>>(
     value: serde_json::Value,
     path: P,
-) -> std::io::Result<Experiment> {
+) -> Result<Experiment, DmsError> {
     let path = {
         let mut p = PathBuf::new();
         p.push(path);
@@ -79,7 +123,7 @@ pub fn read_experiment_from_value<P: AsRef<Path>>(
             if let serde_json::Value::Array(a) = tasks.take() {
                 let tasks = a
                     .into_iter()
-                    .map(|mut v| -> std::io::Result<ExperimentTask> {
+                    .map(|mut v| -> Result<ExperimentTask, DmsError> {
                         fs::read_field_from_file(&mut v, "optimizations", &path)?;
                         let taskmap = if let serde_json::Value::Object(mut v) = v {
                             if let Some(serde_json::Value::Array(problems)) = v.get_mut("problems")
@@ -90,33 +134,20 @@ pub fn read_experiment_from_value<P: AsRef<Path>>(
                             }
                             v
                         } else {
-                            return Err(std::io::Error::new(
-                                std::io::ErrorKind::Other,
-                                "Each task must be an object",
-                            ));
+                            return Err(DmsError::parse(&path, "Each task must be an object"));
                         };
                         let v = serde_json::Value::Object(taskmap);
-                        let e: ExperimentTask = serde_json::from_value(v)?;
-                        Ok(e)
+                        serde_json::from_value(v).map_err(|e| DmsError::parse(&path, e))
                     })
-                    .collect::<std::io::Result<Vec<ExperimentTask>>>()?;
+                    .collect::<Result<Vec<ExperimentTask>, DmsError>>()?;
                 Ok(Experiment { name, tasks })
             } else {
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "tasks field is not array",
-                ))
+                Err(DmsError::parse(path, "tasks field is not array"))
             }
         } else {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Missing field: tasks",
-            ))
+            Err(DmsError::parse(path, "Missing field: tasks"))
         }
     } else {
-        Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Experiment must be a JSON object",
-        ))
+        Err(DmsError::parse(path, "Experiment must be a JSON object"))
     }
 }
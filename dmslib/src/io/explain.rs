@@ -0,0 +1,68 @@
+//! Explanation of the decisions of the optimal policy.
+use super::*;
+
+/// An action in a [`StateExplanation`].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ActionExplanation {
+    /// Expected value of this action, i.e., the expected total cost until restoration.
+    pub value: Value,
+    /// Successor state index, probability and cost of each transition of this action.
+    pub transitions: Vec<(usize, Probability, Cost)>,
+}
+
+/// Breakdown of the expected values of all actions in a state, see
+/// [`TeamSolution::explain_state`].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StateExplanation {
+    /// Index of the explained state.
+    pub index: usize,
+    /// Index of the optimal action in this state.
+    pub optimal_action: usize,
+    /// All feasible actions in this state, in the same order as the transitions.
+    pub actions: Vec<ActionExplanation>,
+}
+
+impl<T: Transition> TeamSolution<T> {
+    /// Get the expected value of each action in the state at the given index together with the
+    /// transitions that contribute to it.
+    ///
+    /// Useful for explaining why the optimal policy chooses an action over the others.
+    ///
+    /// Returns an error if the index is out of bounds or only the value of the optimal action is
+    /// stored in this state, see
+    /// [`Config::store_all_action_values`](crate::teams::Config::store_all_action_values).
+    pub fn explain_state(&self, index: usize) -> Result<StateExplanation, String> {
+        if index >= self.transitions.len() {
+            return Err(format!("State index {index} is out of bounds"));
+        }
+        let values = &self.values[index];
+        if values.len() != self.transitions[index].len() {
+            return Err(format!(
+                "Only the value of the optimal action is stored in state {index}, \
+                set store_all_action_values to explain it"
+            ));
+        }
+        let actions = self.transitions[index]
+            .iter()
+            .zip(values.iter())
+            .map(|(transitions, &value)| ActionExplanation {
+                value,
+                transitions: transitions
+                    .iter()
+                    .map(|t| {
+                        (
+                            t.get_successor() as usize,
+                            t.get_probability(),
+                            t.get_cost(),
+                        )
+                    })
+                    .collect(),
+            })
+            .collect();
+        Ok(StateExplanation {
+            index,
+            optimal_action: self.policy[index] as usize,
+            actions,
+        })
+    }
+}
@@ -13,6 +13,9 @@ use std::collections::HashMap;
 #[cfg(feature = "hashbrown")]
 use hashbrown::HashMap;
 
+mod policy;
+pub use policy::*;
+mod prism;
 mod solution;
 pub use solution::*;
 
@@ -102,24 +105,93 @@ pub fn name_to_json(name: &str) -> String {
     sanitize_filename::sanitize(name)
 }
 
+/// Expand the placeholders in a file name template, e.g., `{problem}-{indexer}`, with the given
+/// values.
+///
+/// Values are sanitized to be valid file names, whereas the template itself may contain `/` to
+/// place the file in a subdirectory. Use `{{` and `}}` for literal braces.
+///
+/// Returns [`DmsError::Validation`] if a placeholder is unknown or a brace is unmatched.
+pub fn expand_name_template(template: &str, values: &[(&str, String)]) -> Result<String, DmsError> {
+    let mut output = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let mut key = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => key.push(c),
+                        None => {
+                            return Err(DmsError::Validation(format!(
+                                "Unterminated placeholder in name template {template:?}"
+                            )))
+                        }
+                    }
+                }
+                let value = values
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v)
+                    .ok_or_else(|| {
+                        DmsError::Validation(format!(
+                            "Unknown placeholder {{{key}}} in name template {template:?}"
+                        ))
+                    })?;
+                output.push_str(&sanitize_filename::sanitize(value));
+            }
+            '}' => {
+                return Err(DmsError::Validation(format!(
+                    "Unmatched '}}' in name template {template:?}"
+                )))
+            }
+            c => output.push(c),
+        }
+    }
+    Ok(output)
+}
+
+/// Get a path that doesn't exist yet by appending `-1`, `-2`, etc. to the file stem of the given
+/// path if it's already present.
+pub fn unique_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path.extension().map(|e| e.to_string_lossy().to_string());
+    (1..)
+        .map(|i| match &extension {
+            Some(extension) => path.with_file_name(format!("{stem}-{i}.{extension}")),
+            None => path.with_file_name(format!("{stem}-{i}")),
+        })
+        .find(|p| !p.exists())
+        .unwrap()
+}
+
 /// Given a `serde_json::Value`, save it to the [`EXPERIMENTS_PATH`] as a human-readable (pretty)
 /// JSON file.
-pub fn save_problem(content: &serde_json::Value) -> std::io::Result<()> {
+pub fn save_problem(content: &serde_json::Value) -> Result<(), DmsError> {
     let name: String = match content.get("name") {
         Some(name) => match name.as_str() {
             Some(s) => s.to_owned(),
             None => {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Problem has no name".to_string(),
-                ));
+                return Err(DmsError::Validation("Problem has no name".to_string()));
             }
         },
         None => {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Problem has no name".to_string(),
-            ));
+            return Err(DmsError::Validation("Problem has no name".to_string()));
         }
     };
     let name = name_to_json(&name);
@@ -130,32 +202,32 @@ pub fn save_problem(content: &serde_json::Value) -> std::io::Result<()> {
         .write(true)
         .create_new(true)
         .open(path)?;
-    let content = match serde_json::to_string_pretty(content) {
-        Ok(s) => s,
-        Err(e) => {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
-        }
-    };
+    let content = serde_json::to_string_pretty(content).map_err(std::io::Error::other)?;
     file.write_all(content.as_bytes())?;
     log::info!("Saved problem: {}", path.display());
     Ok(())
 }
 
+/// Read the JSON file at the given path.
+///
+/// Returns [`DmsError::Parse`] if the file is not valid JSON.
+pub fn read_json_file<P: AsRef<Path>>(path: P) -> Result<serde_json::Value, DmsError> {
+    let content = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&content).map_err(|e| DmsError::parse(path, e))
+}
+
 /// Given a `serde_json::Value`, read it from the path it specifies if it's a string,
 /// relative to the given `path`.
 pub fn read_json_value_from_file<P: AsRef<Path>>(
     value: &mut serde_json::Value,
     path: P,
-) -> std::io::Result<bool> {
+) -> Result<bool, DmsError> {
     if let serde_json::Value::String(s) = value {
         let mut graph_path = PathBuf::new();
         graph_path.push(path);
         graph_path.pop();
         graph_path.push(s);
-        *value = {
-            let content = std::fs::read_to_string(&graph_path)?;
-            serde_json::from_str(&content)?
-        };
+        *value = read_json_file(&graph_path)?;
         Ok(true)
     } else {
         Ok(false)
@@ -168,7 +240,7 @@ pub fn read_field_from_file<P: AsRef<Path>>(
     value: &mut serde_json::Value,
     field: &str,
     path: P,
-) -> std::io::Result<bool> {
+) -> Result<bool, DmsError> {
     let field = value.get_mut(field);
     if let Some(v) = field {
         read_json_value_from_file(v, path)
@@ -181,23 +253,20 @@ impl TeamProblem {
     pub fn read_from_value<P: AsRef<Path>>(
         mut value: serde_json::Value,
         path: P,
-    ) -> std::io::Result<TeamProblem> {
-        read_field_from_file(&mut value, "graph", path)?;
-        let team_problem: TeamProblem = serde_json::from_value(value)?;
-        Ok(team_problem)
+    ) -> Result<TeamProblem, DmsError> {
+        read_field_from_file(&mut value, "graph", &path)?;
+        serde_json::from_value(value).map_err(|e| DmsError::parse(path, e))
     }
 
-    pub fn read_from_file<P: AsRef<Path>>(path: P) -> std::io::Result<TeamProblem> {
-        let content = std::fs::read_to_string(&path)?;
-        let value: serde_json::Value = serde_json::from_str(&content)?;
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<TeamProblem, DmsError> {
+        let value = read_json_file(&path)?;
         TeamProblem::read_from_value(value, path)
     }
 }
 
 /// Read problems from a JSON file, whether it's a single problem file or experiment.
-pub fn read_problems_from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<TeamProblem>> {
-    let content = std::fs::read_to_string(&path)?;
-    let value: serde_json::Value = serde_json::from_str(&content)?;
+pub fn read_problems_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<TeamProblem>, DmsError> {
+    let value = read_json_file(&path)?;
     if value.get("tasks").is_some() {
         let experiment = read_experiment_from_value(value, path)?;
         let mut result: Vec<TeamProblem> = Vec::new();
@@ -0,0 +1,150 @@
+//! Compact export of the optimal policy without the transition space.
+use std::collections::HashMap;
+
+use bincode::Options;
+use num_traits::FromPrimitive;
+
+use crate::teams::state::State;
+
+use super::*;
+
+/// Binary representation of [`PolicyOnly`].
+#[derive(Serialize, Deserialize)]
+struct PolicyFile {
+    team_node_count: usize,
+    team_nodes: Vec<f64>,
+    travel_times: Vec<Time>,
+    bus_count: usize,
+    states: Vec<u8>,
+    teams: Vec<TeamState>,
+    policy: Vec<ActionIndex>,
+    targets: Vec<BusIndex>,
+}
+
+/// The optimal policy of a [`TeamSolution`] without its transitions and values, which is enough
+/// to look up the optimal actions during a live restoration.
+///
+/// Created with [`TeamSolution::export_policy_only`] and loaded with [`PolicyOnly::load`].
+pub struct PolicyOnly {
+    /// Latitude and longtitude values of vertices in team graph.
+    pub team_nodes: Array2<f64>,
+    /// Travel time between each node
+    pub travel_times: Array2<Time>,
+    /// Array of bus states.
+    pub states: Array2<BusState>,
+    /// Array of team states.
+    pub teams: Array2<TeamState>,
+    /// Index of optimal actions in each state.
+    pub policy: Vec<ActionIndex>,
+    /// Target node of each team in the optimal action of each state.
+    pub targets: Array2<BusIndex>,
+    /// Maps the contents of each state to its index.
+    indices: HashMap<State, usize>,
+}
+
+impl<T: Transition> TeamSolution<T> {
+    /// Write only the policy of this solution to the given file, see [`PolicyOnly`].
+    ///
+    /// The result is much smaller than a save file since the transitions and values are omitted.
+    pub fn export_policy_only<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let targets: Vec<BusIndex> = self
+            .policy
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &action)| {
+                let successor = self.transitions[i][action as usize][0].get_successor();
+                self.teams.row(successor as usize).to_vec()
+            })
+            .map(|team| team.index)
+            .collect();
+        let content = PolicyFile {
+            team_node_count: self.team_nodes.shape()[0],
+            team_nodes: self.team_nodes.iter().cloned().collect(),
+            travel_times: self.travel_times.iter().cloned().collect(),
+            bus_count: self.states.shape()[1],
+            states: self.states.iter().map(|&bus| bus as u8).collect(),
+            teams: self.teams.iter().cloned().collect(),
+            policy: self.policy.clone(),
+            targets,
+        };
+        let encoded: Vec<u8> = bincode::DefaultOptions::new()
+            .serialize(&content)
+            .map_err(std::io::Error::other)?;
+        std::fs::write(path, encoded)
+    }
+}
+
+impl PolicyOnly {
+    /// Load the policy written by [`TeamSolution::export_policy_only`] from the given file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<PolicyOnly, DmsError> {
+        let invalid = |e: String| DmsError::parse(&path, e);
+        let encoded = std::fs::read(&path)?;
+        let content: PolicyFile = bincode::DefaultOptions::new()
+            .deserialize(&encoded[..])
+            .map_err(|e| DmsError::parse(&path, e))?;
+        let PolicyFile {
+            team_node_count,
+            team_nodes,
+            travel_times,
+            bus_count,
+            states,
+            teams,
+            policy,
+            targets,
+        } = content;
+        let state_count = policy.len();
+        let team_count = teams.len().checked_div(state_count).unwrap_or(0);
+        let states = states
+            .into_iter()
+            .map(|bus| {
+                BusState::from_u8(bus).ok_or_else(|| invalid(format!("Invalid bus state {bus}")))
+            })
+            .collect::<Result<Vec<BusState>, DmsError>>()?;
+
+        let shape_error = |e: ndarray::ShapeError| invalid(e.to_string());
+        let policy_only = PolicyOnly {
+            team_nodes: Array2::from_shape_vec((team_node_count, 2), team_nodes)
+                .map_err(shape_error)?,
+            travel_times: Array2::from_shape_vec((team_node_count, team_node_count), travel_times)
+                .map_err(shape_error)?,
+            states: Array2::from_shape_vec((state_count, bus_count), states)
+                .map_err(shape_error)?,
+            teams: Array2::from_shape_vec((state_count, team_count), teams).map_err(shape_error)?,
+            targets: Array2::from_shape_vec((state_count, team_count), targets)
+                .map_err(shape_error)?,
+            policy,
+            indices: HashMap::new(),
+        };
+        let indices = (0..state_count)
+            .map(|i| (policy_only.get_state(i), i))
+            .collect();
+        Ok(PolicyOnly {
+            indices,
+            ..policy_only
+        })
+    }
+
+    /// Get the state at the given index.
+    pub fn get_state(&self, index: usize) -> State {
+        State {
+            buses: self.states.row(index).to_vec(),
+            teams: self.teams.row(index).to_vec(),
+        }
+    }
+
+    /// Get the index of the given state, or `None` if it's not in the policy.
+    pub fn state_index(&self, state: &State) -> Option<usize> {
+        self.indices.get(state).cloned()
+    }
+
+    /// Get the optimal action in the given state, or `None` if it's not in the policy.
+    ///
+    /// The targets are the current positions of the teams if the state is terminal.
+    pub fn get_action(&self, state: &State) -> Option<ReplayAction> {
+        let index = self.state_index(state)?;
+        Some(ReplayAction {
+            index: self.policy[index] as usize,
+            targets: self.targets.row(index).to_vec(),
+        })
+    }
+}
@@ -0,0 +1,90 @@
+//! Export of the MDP in the explicit model format of the PRISM model checker.
+use std::io::BufWriter;
+
+use super::*;
+
+impl<T: Transition> TeamSolution<T> {
+    /// Write the MDP of this solution to the given directory in the explicit model format of
+    /// [PRISM](https://www.prismmodelchecker.org/manual/Appendices/ExplicitModelFiles), which
+    /// can be imported with `prism -importmodel model.tra,sta,lab,trew -mdp`.
+    ///
+    /// - `model.sta`: Bus states (0: Unknown, 1: Damaged, 2: Energized) and team states.
+    /// - `model.tra`: Transitions of each action, numbered in the same order as in
+    ///   [`TeamSolution::transitions`].
+    /// - `model.lab`: The initial state labeled `init` and the terminal states labeled
+    ///   `terminal`.
+    /// - `model.trew`: The cost of each transition multiplied by its time.
+    ///
+    /// For [`RegularTransition`]s, the optimal value is `Rmin=? [ C<=horizon ]`.
+    pub fn export_prism<P: AsRef<Path>>(&self, dir: P) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let state_count = self.transitions.len();
+        let choice_count: usize = self.transitions.iter().map(Vec::len).sum();
+        let transition_count = get_transition_count(&self.transitions);
+
+        let mut sta = BufWriter::new(std::fs::File::create(dir.join("model.sta"))?);
+        let bus_count = self.states.shape()[1];
+        let team_count = self.teams.shape()[1];
+        let variables = (0..bus_count)
+            .map(|i| format!("b{i}"))
+            .chain((0..team_count).flat_map(|i| [format!("t{i}"), format!("t{i}_time")]))
+            .join(",");
+        writeln!(sta, "({variables})")?;
+        for i in 0..state_count {
+            let values = self
+                .states
+                .row(i)
+                .iter()
+                .map(|&bus| (bus as u8).to_string())
+                .chain(
+                    self.teams
+                        .row(i)
+                        .iter()
+                        .flat_map(|team| [team.index.to_string(), team.time.to_string()]),
+                )
+                .join(",");
+            writeln!(sta, "{i}:({values})")?;
+        }
+        sta.flush()?;
+
+        let mut tra = BufWriter::new(std::fs::File::create(dir.join("model.tra"))?);
+        writeln!(tra, "{state_count} {choice_count} {transition_count}")?;
+        let mut rewards: Vec<String> = Vec::new();
+        for (i, actions) in self.transitions.iter().enumerate() {
+            for (j, transitions) in actions.iter().enumerate() {
+                for t in transitions {
+                    let successor = t.get_successor();
+                    writeln!(tra, "{i} {j} {successor} {}", t.get_probability())?;
+                    let reward = (t.get_cost() as f64) * (t.get_time() as f64);
+                    if reward != 0.0 {
+                        rewards.push(format!("{i} {j} {successor} {reward}"));
+                    }
+                }
+            }
+        }
+        tra.flush()?;
+
+        let mut trew = BufWriter::new(std::fs::File::create(dir.join("model.trew"))?);
+        writeln!(trew, "{state_count} {choice_count} {}", rewards.len())?;
+        for line in rewards {
+            writeln!(trew, "{line}")?;
+        }
+        trew.flush()?;
+
+        let mut lab = BufWriter::new(std::fs::File::create(dir.join("model.lab"))?);
+        writeln!(lab, "0=\"init\" 1=\"terminal\"")?;
+        for (i, actions) in self.transitions.iter().enumerate() {
+            let terminal = actions.len() == 1
+                && actions[0].len() == 1
+                && actions[0][0].get_successor() as usize == i;
+            match (i == 0, terminal) {
+                (true, true) => writeln!(lab, "{i}: 0 1")?,
+                (true, false) => writeln!(lab, "{i}: 0")?,
+                (false, true) => writeln!(lab, "{i}: 1")?,
+                (false, false) => {}
+            }
+        }
+        lab.flush()
+    }
+}
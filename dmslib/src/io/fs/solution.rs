@@ -42,6 +42,12 @@ mod saveable {
         pub values: Vec<Vec<Value>>,
         pub policy: Vec<ActionIndex>,
         pub horizon: usize,
+
+        pub team_names: Vec<Option<String>>,
+        pub node_names: Vec<Option<String>>,
+        pub time_quantum: Time,
+        pub partial: bool,
+        pub actions: Option<Vec<Vec<Vec<BusIndex>>>>,
     }
 
     macro_rules! super_to_saveable {
@@ -58,6 +64,11 @@ mod saveable {
                 values,
                 policy,
                 horizon,
+                team_names,
+                node_names,
+                time_quantum,
+                partial,
+                actions,
             } = $a;
             TeamSolution {
                 total_time,
@@ -72,6 +83,11 @@ mod saveable {
                 values,
                 policy,
                 horizon,
+                team_names,
+                node_names,
+                time_quantum,
+                partial,
+                actions,
             }
         }};
     }
@@ -103,6 +119,11 @@ mod saveable {
                 values,
                 policy,
                 horizon,
+                team_names,
+                node_names,
+                time_quantum,
+                partial,
+                actions,
             } = $a;
             let state_count = transitions.len();
             let bus_count = states.len() / state_count;
@@ -131,6 +152,11 @@ mod saveable {
                 values,
                 policy,
                 horizon,
+                team_names,
+                node_names,
+                time_quantum,
+                partial,
+                actions,
             }
         }};
     }
@@ -173,20 +199,41 @@ mod saveable {
         DirectDistance {
             multiplier: Option<f64>,
             divider: Option<f64>,
+            metric: super::DistanceMetric,
+            radius: Option<f64>,
         },
         Constant {
             constant: Time,
         },
     }
 
+    #[derive(Serialize, Deserialize)]
+    pub enum HorizonSpec {
+        Auto,
+        Absolute(usize),
+        Multiple(f64),
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Objective {
+        Cost,
+        Makespan,
+    }
+
     #[derive(Serialize, Deserialize)]
     pub struct TeamProblem {
         pub name: Option<String>,
         pub graph: super::Graph,
         pub teams: Vec<super::Team>,
-        pub horizon: Option<usize>,
+        pub horizon: HorizonSpec,
         pub pfo: Option<f64>,
         pub time_func: TimeFunc,
+        pub strict_branches: bool,
+        pub repair_times: Option<Vec<Time>>,
+        pub time_scale: Option<f64>,
+        pub depots: Vec<super::LatLng>,
+        pub deadlines: Option<Vec<Option<Time>>>,
+        pub start_distribution: Option<Vec<super::WeightedStart>>,
     }
 
     impl From<TeamProblem> for super::TeamProblem {
@@ -201,10 +248,469 @@ mod saveable {
         }
     }
 
+    #[derive(Serialize, Deserialize)]
+    pub struct Config {
+        pub max_memory: usize,
+        pub horizon: HorizonSpec,
+        pub max_actions_per_state: usize,
+        pub max_states: usize,
+        pub debug_dump: Option<std::path::PathBuf>,
+        pub global_wait: Option<Time>,
+        pub action_stats: bool,
+        pub time_quantum: Time,
+        pub track_memory: bool,
+        pub team_move_cost: Option<f64>,
+        pub allow_partial: bool,
+        pub store_actions: bool,
+        pub enforce_progress: bool,
+        pub store_all_action_values: bool,
+        pub max_outcomes_per_action: Option<usize>,
+        pub pfs_schedule: Option<Vec<(Time, Vec<Probability>)>>,
+        #[serde(skip)]
+        pub exploration_gauge: Option<std::sync::Arc<crate::teams::ExplorationGauge>>,
+        pub objective: Objective,
+    }
+
+    impl From<HorizonSpec> for crate::policy::HorizonSpec {
+        fn from(value: HorizonSpec) -> Self {
+            match value {
+                HorizonSpec::Auto => crate::policy::HorizonSpec::Auto,
+                HorizonSpec::Absolute(horizon) => crate::policy::HorizonSpec::Absolute(horizon),
+                HorizonSpec::Multiple(factor) => crate::policy::HorizonSpec::Multiple(factor),
+            }
+        }
+    }
+
+    impl From<crate::policy::HorizonSpec> for HorizonSpec {
+        fn from(value: crate::policy::HorizonSpec) -> Self {
+            match value {
+                crate::policy::HorizonSpec::Auto => HorizonSpec::Auto,
+                crate::policy::HorizonSpec::Absolute(horizon) => HorizonSpec::Absolute(horizon),
+                crate::policy::HorizonSpec::Multiple(factor) => HorizonSpec::Multiple(factor),
+            }
+        }
+    }
+
+    impl From<Objective> for crate::teams::Objective {
+        fn from(value: Objective) -> Self {
+            match value {
+                Objective::Cost => crate::teams::Objective::Cost,
+                Objective::Makespan => crate::teams::Objective::Makespan,
+            }
+        }
+    }
+
+    impl From<crate::teams::Objective> for Objective {
+        fn from(value: crate::teams::Objective) -> Self {
+            match value {
+                crate::teams::Objective::Cost => Objective::Cost,
+                crate::teams::Objective::Makespan => Objective::Makespan,
+            }
+        }
+    }
+
+    impl From<Config> for crate::teams::Config {
+        fn from(value: Config) -> Self {
+            let Config {
+                max_memory,
+                horizon,
+                max_actions_per_state,
+                max_states,
+                debug_dump,
+                global_wait,
+                action_stats,
+                time_quantum,
+                track_memory,
+                team_move_cost,
+                allow_partial,
+                store_actions,
+                enforce_progress,
+                store_all_action_values,
+                max_outcomes_per_action,
+                pfs_schedule,
+                exploration_gauge,
+                objective,
+            } = value;
+            crate::teams::Config {
+                max_memory,
+                horizon: horizon.into(),
+                max_actions_per_state,
+                max_states,
+                debug_dump,
+                global_wait,
+                action_stats,
+                time_quantum,
+                track_memory,
+                team_move_cost,
+                allow_partial,
+                store_actions,
+                enforce_progress,
+                store_all_action_values,
+                max_outcomes_per_action,
+                pfs_schedule,
+                exploration_gauge,
+                objective: objective.into(),
+            }
+        }
+    }
+
+    impl From<crate::teams::Config> for Config {
+        fn from(value: crate::teams::Config) -> Self {
+            let crate::teams::Config {
+                max_memory,
+                horizon,
+                max_actions_per_state,
+                max_states,
+                debug_dump,
+                global_wait,
+                action_stats,
+                time_quantum,
+                track_memory,
+                team_move_cost,
+                allow_partial,
+                store_actions,
+                enforce_progress,
+                store_all_action_values,
+                max_outcomes_per_action,
+                pfs_schedule,
+                exploration_gauge,
+                objective,
+            } = value;
+            Config {
+                max_memory,
+                horizon: horizon.into(),
+                max_actions_per_state,
+                max_states,
+                debug_dump,
+                global_wait,
+                action_stats,
+                time_quantum,
+                track_memory,
+                team_move_cost,
+                allow_partial,
+                store_actions,
+                enforce_progress,
+                store_all_action_values,
+                max_outcomes_per_action,
+                pfs_schedule,
+                exploration_gauge,
+                objective: objective.into(),
+            }
+        }
+    }
+
+    /// The synthesizer is always given, unlike [`super::OptimizationInfo`] in which it's skipped
+    /// if not given, which is not supported by bincode.
+    #[derive(Serialize, Deserialize)]
+    pub struct SolveSettings {
+        pub config: Config,
+        pub indexer: String,
+        pub actions: String,
+        pub transitions: String,
+        pub synthesizer: String,
+    }
+
+    impl From<SolveSettings> for super::SolveSettings {
+        fn from(value: SolveSettings) -> Self {
+            let SolveSettings {
+                config,
+                indexer,
+                actions,
+                transitions,
+                synthesizer,
+            } = value;
+            super::SolveSettings {
+                config: config.into(),
+                optimizations: super::OptimizationInfo {
+                    indexer,
+                    actions,
+                    transitions,
+                    synthesizer: Some(synthesizer),
+                },
+            }
+        }
+    }
+
+    impl From<super::SolveSettings> for SolveSettings {
+        fn from(value: super::SolveSettings) -> Self {
+            let super::SolveSettings {
+                config,
+                optimizations,
+            } = value;
+            let synthesizer = optimizations.get_synthesizer().to_string();
+            let super::OptimizationInfo {
+                indexer,
+                actions,
+                transitions,
+                ..
+            } = optimizations;
+            SolveSettings {
+                config: config.into(),
+                indexer,
+                actions,
+                transitions,
+                synthesizer,
+            }
+        }
+    }
+
     #[derive(Serialize, Deserialize)]
     pub struct SaveFile {
         pub problem: TeamProblem,
         pub solution: GenericTeamSolution,
+        pub settings: Option<SolveSettings>,
+    }
+
+    /// Frozen copy of the original save file format, which lacks the settings and the fields
+    /// added to the problem and the solution later, e.g., the names of the nodes and teams.
+    ///
+    /// Bincode is positional, so these structs must not be changed. They are only used for
+    /// loading the save files created before.
+    pub mod legacy {
+        use super::{BusState, RegularTransition, TeamState, TimedTransition};
+        use crate::types::*;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize)]
+        pub struct LatLng(pub f64, pub f64);
+
+        #[derive(Serialize, Deserialize)]
+        pub struct Branch {
+            pub nodes: (usize, usize),
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct ExtBranch {
+            pub node: usize,
+            pub source: usize,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct Node {
+            pub pf: f64,
+            pub latlng: LatLng,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct Resource {
+            pub latlng: LatLng,
+            pub kind: Option<String>,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct Graph {
+            pub name: String,
+            pub branches: Vec<Branch>,
+            pub external: Vec<ExtBranch>,
+            pub nodes: Vec<Node>,
+            pub resources: Vec<Resource>,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct Team {
+            pub index: Option<usize>,
+            pub latlng: Option<LatLng>,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub enum TimeFunc {
+            DirectDistance {
+                multiplier: Option<f64>,
+                divider: Option<f64>,
+            },
+            Constant {
+                constant: Time,
+            },
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct TeamProblem {
+            pub name: Option<String>,
+            pub graph: Graph,
+            pub teams: Vec<Team>,
+            pub horizon: Option<usize>,
+            pub pfo: Option<f64>,
+            pub time_func: TimeFunc,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct TeamSolution<T> {
+            pub total_time: f64,
+            pub generation_time: f64,
+            pub max_memory: usize,
+
+            pub team_node_count: usize,
+            pub team_nodes: Vec<f64>,
+            pub travel_times: Vec<Time>,
+
+            pub states: Vec<BusState>,
+            pub teams: Vec<TeamState>,
+            pub transitions: Vec<Vec<Vec<T>>>,
+
+            pub values: Vec<Vec<Value>>,
+            pub policy: Vec<ActionIndex>,
+            pub horizon: usize,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub enum GenericTeamSolution {
+            Timed(TeamSolution<TimedTransition>),
+            Regular(TeamSolution<RegularTransition>),
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct SaveFile {
+            pub problem: TeamProblem,
+            pub solution: GenericTeamSolution,
+        }
+
+        impl From<LatLng> for crate::io::LatLng {
+            fn from(value: LatLng) -> Self {
+                crate::io::LatLng(value.0, value.1)
+            }
+        }
+
+        impl From<Graph> for crate::io::Graph {
+            fn from(value: Graph) -> Self {
+                let Graph {
+                    name,
+                    branches,
+                    external,
+                    nodes,
+                    resources,
+                } = value;
+                crate::io::Graph {
+                    name,
+                    branches: branches
+                        .into_iter()
+                        .map(|Branch { nodes: (a, b) }| crate::io::Branch {
+                            nodes: crate::io::BranchNodes(a, b),
+                        })
+                        .collect(),
+                    external: external
+                        .into_iter()
+                        .map(|ExtBranch { node, source }| crate::io::ExtBranch { node, source })
+                        .collect(),
+                    nodes: nodes
+                        .into_iter()
+                        .map(|Node { pf, latlng }| crate::io::Node {
+                            pf,
+                            latlng: latlng.into(),
+                            name: None,
+                            weight: None,
+                        })
+                        .collect(),
+                    resources: resources
+                        .into_iter()
+                        .map(|Resource { latlng, kind }| crate::io::Resource {
+                            latlng: latlng.into(),
+                            kind,
+                            outage: false,
+                        })
+                        .collect(),
+                }
+            }
+        }
+
+        impl From<TeamProblem> for crate::io::TeamProblem {
+            fn from(value: TeamProblem) -> Self {
+                let TeamProblem {
+                    name,
+                    graph,
+                    teams,
+                    horizon,
+                    pfo,
+                    time_func,
+                } = value;
+                crate::io::TeamProblem {
+                    name,
+                    graph: graph.into(),
+                    teams: teams
+                        .into_iter()
+                        .map(|Team { index, latlng }| crate::io::Team {
+                            index,
+                            latlng: latlng.map(Into::into),
+                            name: None,
+                            node: None,
+                        })
+                        .collect(),
+                    horizon: horizon.map_or(
+                        crate::policy::HorizonSpec::Auto,
+                        crate::policy::HorizonSpec::Absolute,
+                    ),
+                    pfo,
+                    time_func: match time_func {
+                        TimeFunc::DirectDistance {
+                            multiplier,
+                            divider,
+                        } => crate::io::TimeFunc::DirectDistance {
+                            multiplier,
+                            divider,
+                            metric: Default::default(),
+                            radius: None,
+                        },
+                        TimeFunc::Constant { constant } => {
+                            crate::io::TimeFunc::Constant { constant }
+                        }
+                    },
+                    strict_branches: false,
+                    repair_times: None,
+                    time_scale: None,
+                    depots: Vec::new(),
+                    deadlines: None,
+                    start_distribution: None,
+                }
+            }
+        }
+
+        impl<T> From<TeamSolution<T>> for super::TeamSolution<T> {
+            fn from(value: TeamSolution<T>) -> Self {
+                let TeamSolution {
+                    total_time,
+                    generation_time,
+                    max_memory,
+                    team_node_count,
+                    team_nodes,
+                    travel_times,
+                    states,
+                    teams,
+                    transitions,
+                    values,
+                    policy,
+                    horizon,
+                } = value;
+                super::TeamSolution {
+                    total_time,
+                    generation_time,
+                    max_memory,
+                    team_node_count,
+                    team_nodes,
+                    travel_times,
+                    states,
+                    teams,
+                    transitions,
+                    values,
+                    policy,
+                    horizon,
+                    team_names: Vec::new(),
+                    node_names: Vec::new(),
+                    time_quantum: 1,
+                    partial: false,
+                    actions: None,
+                }
+            }
+        }
+
+        impl From<GenericTeamSolution> for super::GenericTeamSolution {
+            fn from(value: GenericTeamSolution) -> Self {
+                match value {
+                    GenericTeamSolution::Timed(a) => super::GenericTeamSolution::Timed(a.into()),
+                    GenericTeamSolution::Regular(a) => {
+                        super::GenericTeamSolution::Regular(a.into())
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -238,10 +744,51 @@ impl From<TeamSolution<RegularTransition>> for saveable::GenericTeamSolution {
     }
 }
 
+/// The config and optimizations with which a solution is generated.
+#[derive(Debug, Clone)]
+pub struct SolveSettings {
+    pub config: teams::Config,
+    pub optimizations: OptimizationInfo,
+}
+
 /// Struct that represents the contents of a save file.
 pub struct SaveFile {
     pub problem: TeamProblem,
     pub solution: GenericTeamSolution,
+    /// The settings used for generating the solution if saved, see [`SaveFile::reproduce`].
+    /// `None` for the save files created without them.
+    pub settings: Option<SolveSettings>,
+}
+
+impl SaveFile {
+    /// Solve the problem again with the saved settings.
+    ///
+    /// [`teams::Config::debug_dump`] is ignored. Use [`TeamSolution::same_results`] to check
+    /// whether the results are identical.
+    pub fn reproduce(&self) -> Result<GenericTeamSolution, SolveFailure> {
+        let settings = self.settings.as_ref().ok_or_else(|| {
+            SolveFailure::BadInput("The save file doesn't contain the solve settings".to_string())
+        })?;
+        let (problem, _) = self
+            .problem
+            .clone()
+            .prepare()
+            .map_err(|e| SolveFailure::BadInput(e.to_string()))?;
+        let config = teams::Config {
+            debug_dump: None,
+            ..settings.config.clone()
+        };
+        let optimizations = &settings.optimizations;
+        teams::solve_custom(
+            &problem.graph,
+            problem.initial_teams,
+            &config,
+            &optimizations.indexer,
+            &optimizations.actions,
+            &optimizations.transitions,
+            optimizations.get_synthesizer(),
+        )
+    }
 }
 
 use bincode::Options;
@@ -253,9 +800,13 @@ macro_rules! bincode_options {
 }
 
 /// Save the field-teams restoration problem and solution to the given file.
+///
+/// The settings allow reproducing the solution later, see [`SaveFile::reproduce`].
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub fn save_solution<P: AsRef<Path>, S: Into<saveable::GenericTeamSolution>>(
     problem: TeamProblem,
     solution: S,
+    settings: Option<SolveSettings>,
     path: P,
 ) -> std::io::Result<()> {
     let start_time = Instant::now();
@@ -263,6 +814,7 @@ pub fn save_solution<P: AsRef<Path>, S: Into<saveable::GenericTeamSolution>>(
     let file_content = saveable::SaveFile {
         problem: problem.into(),
         solution: solution.into(),
+        settings: settings.map(Into::into),
     };
 
     let encoded: Vec<u8> = match bincode_options!().serialize(&file_content) {
@@ -286,27 +838,37 @@ pub fn save_solution<P: AsRef<Path>, S: Into<saveable::GenericTeamSolution>>(
 }
 
 /// Load the field-teams restoration problem and solution from the given file.
-pub fn load_solution<P: AsRef<Path>>(path: P) -> std::io::Result<SaveFile> {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn load_solution<P: AsRef<Path>>(path: P) -> Result<SaveFile, DmsError> {
     let start_time = Instant::now();
 
     let mut file = std::fs::File::open(&path)?;
     let mut encoded: Vec<u8> = Vec::new();
     file.read_to_end(&mut encoded)?;
 
-    let decoded: saveable::SaveFile = match bincode_options!().deserialize(&encoded[..]) {
-        Ok(v) => v,
+    let output = match bincode_options!().deserialize(&encoded[..]) {
+        Ok(saveable::SaveFile {
+            problem,
+            solution,
+            settings,
+        }) => SaveFile {
+            problem: problem.into(),
+            solution: solution.into(),
+            settings: settings.map(Into::into),
+        },
         Err(e) => {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
+            // Fall back to the original format.
+            match bincode_options!().deserialize(&encoded[..]) {
+                Ok(saveable::legacy::SaveFile { problem, solution }) => SaveFile {
+                    problem: problem.into(),
+                    solution: saveable::GenericTeamSolution::from(solution).into(),
+                    settings: None,
+                },
+                Err(_) => return Err(DmsError::parse(path, e)),
+            }
         }
     };
 
-    let saveable::SaveFile { problem, solution } = decoded;
-
-    let output = SaveFile {
-        problem: problem.into(),
-        solution: solution.into(),
-    };
-
     log::info!(
         "Loaded {} bytes from {} in {:.4} seconds.",
         encoded.len(),
@@ -329,6 +891,7 @@ mod tests {
         let file_content = saveable::SaveFile {
             problem: save_file.problem.clone().into(),
             solution: save_file.solution.clone().into(),
+            settings: save_file.settings.clone().map(Into::into),
         };
 
         let encoded = bincode_options!().serialize(&file_content).unwrap();
@@ -337,10 +900,23 @@ mod tests {
         let reconstructed = SaveFile {
             problem: decoded.problem.into(),
             solution: decoded.solution.into(),
+            settings: decoded.settings.map(Into::into),
         };
 
         assert_eq!(save_file.problem, reconstructed.problem);
         assert_eq!(save_file.solution, reconstructed.solution);
+        match (save_file.settings, reconstructed.settings) {
+            (Some(settings), Some(reconstructed)) => {
+                assert_eq!(settings.config, reconstructed.config);
+                let (a, b) = (settings.optimizations, reconstructed.optimizations);
+                assert_eq!(a.indexer, b.indexer);
+                assert_eq!(a.actions, b.actions);
+                assert_eq!(a.transitions, b.transitions);
+                assert_eq!(a.get_synthesizer(), b.get_synthesizer());
+            }
+            (None, None) => {}
+            _ => panic!("Settings are not reconstructed"),
+        }
     }
 
     #[test]
@@ -349,6 +925,8 @@ mod tests {
         let teams = vec![Team {
             index: Some(0),
             latlng: None,
+            name: None,
+            node: None,
         }];
         let (problem, config) = input_graph
             .clone()
@@ -358,9 +936,15 @@ mod tests {
             name: Some("Save Test".to_string()),
             graph: input_graph,
             teams,
-            horizon: Some(30),
+            horizon: HorizonSpec::Absolute(30),
             pfo: None,
             time_func: TimeFunc::default(),
+            strict_branches: false,
+            repair_times: None,
+            time_scale: None,
+            depots: Vec::new(),
+            deadlines: None,
+            start_distribution: None,
         };
 
         let solution = solve_custom_timed(
@@ -370,12 +954,14 @@ mod tests {
             "NaiveStateIndexer",
             "NaiveActions",
             "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
         )
         .unwrap();
 
         encode_decode_save_file(SaveFile {
             problem: team_problem.clone(),
             solution: GenericTeamSolution::Timed(solution.into_io(&problem.graph)),
+            settings: None,
         });
 
         let solution = solve_custom_regular(
@@ -384,11 +970,161 @@ mod tests {
             &config,
             "NaiveStateIndexer",
             "NaiveActions",
+            "NaivePolicySynthesizer",
         )
         .unwrap();
         encode_decode_save_file(SaveFile {
             problem: team_problem.clone(),
             solution: GenericTeamSolution::Regular(solution.into_io(&problem.graph)),
+            settings: None,
         });
     }
+
+    #[test]
+    fn config_save_file_round_trip() {
+        let input_graph: Graph = serde_json::from_str(TEST_GRAPH).unwrap();
+        let team_problem = TeamProblem {
+            name: Some("Config Test".to_string()),
+            graph: input_graph,
+            teams: vec![Team {
+                index: Some(0),
+                latlng: None,
+                name: None,
+                node: None,
+            }],
+            horizon: HorizonSpec::Multiple(1.5),
+            pfo: None,
+            time_func: TimeFunc::default(),
+            strict_branches: false,
+            repair_times: None,
+            time_scale: None,
+            depots: Vec::new(),
+            deadlines: None,
+            start_distribution: None,
+        };
+        let (_, config) = team_problem.clone().prepare().unwrap();
+        let config = teams::Config {
+            max_states: 100000,
+            debug_dump: Some(PathBuf::from("dump.txt")),
+            global_wait: Some(2),
+            action_stats: true,
+            team_move_cost: Some(0.5),
+            store_actions: true,
+            store_all_action_values: false,
+            max_outcomes_per_action: Some(16),
+            pfs_schedule: Some(vec![(3, vec![0.5; 3])]),
+            objective: teams::Objective::Makespan,
+            ..config
+        };
+
+        // JSON
+        let json = serde_json::to_string(&config).unwrap();
+        let deserialized: teams::Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, config);
+        // Missing fields are taken from the unlimited config.
+        let deserialized: teams::Config = serde_json::from_str(r#"{"max_states": 5}"#).unwrap();
+        assert_eq!(
+            deserialized,
+            teams::Config {
+                max_states: 5,
+                ..teams::Config::unlimited()
+            }
+        );
+
+        let optimizations = OptimizationInfo {
+            indexer: "NaiveStateIndexer".to_string(),
+            actions: "NaiveActions".to_string(),
+            transitions: "TimedActionApplier<TimeUntilEnergization>".to_string(),
+            synthesizer: None,
+        };
+        let (problem, _) = team_problem.clone().prepare().unwrap();
+        let solution = teams::solve_custom(
+            &problem.graph,
+            problem.initial_teams,
+            &teams::Config::unlimited(),
+            &optimizations.indexer,
+            &optimizations.actions,
+            &optimizations.transitions,
+            optimizations.get_synthesizer(),
+        )
+        .unwrap();
+        encode_decode_save_file(SaveFile {
+            problem: team_problem.clone(),
+            solution: solution.clone(),
+            settings: Some(SolveSettings {
+                config,
+                optimizations: optimizations.clone(),
+            }),
+        });
+
+        // Save and reproduce
+        let dir = std::env::temp_dir().join(format!("dms-config-save-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("solution.bin");
+        let settings = SolveSettings {
+            config: teams::Config::unlimited(),
+            optimizations,
+        };
+        save_solution(
+            team_problem.clone(),
+            solution.clone(),
+            Some(settings),
+            &path,
+        )
+        .unwrap();
+        let loaded = load_solution(&path).unwrap();
+        assert_eq!(
+            loaded.settings.as_ref().unwrap().config,
+            teams::Config::unlimited()
+        );
+        assert!(loaded.reproduce().unwrap().same_results(&solution));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_legacy_save_file_test() {
+        // Created with the original save file format, which lacks the settings.
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        for (filename, states) in [("baseline-regular.bin", 742), ("baseline-timed.bin", 60)] {
+            let loaded = load_solution(dir.join(filename)).unwrap();
+            let problem = &loaded.problem;
+            assert_eq!(problem.name.as_deref(), Some("Baseline"));
+            assert_eq!(problem.graph.name, "Baseline Graph");
+            assert_eq!(problem.graph.branches.len(), 2);
+            assert_eq!(
+                problem.graph.external,
+                vec![ExtBranch { node: 0, source: 0 }]
+            );
+            assert_eq!(
+                problem
+                    .graph
+                    .nodes
+                    .iter()
+                    .map(|node| node.pf)
+                    .collect::<Vec<_>>(),
+                vec![0.5, 0.25, 0.125]
+            );
+            assert_eq!(problem.graph.resources[0].latlng, LatLng(41.0, 28.99));
+            assert_eq!(problem.teams[0].latlng, Some(LatLng(41.0, 28.99)));
+            assert_eq!(problem.teams[1].index, Some(2));
+            assert_eq!(problem.horizon, HorizonSpec::Auto);
+            assert_eq!(
+                problem.time_func,
+                TimeFunc::DirectDistance {
+                    multiplier: Some(10.0),
+                    divider: None,
+                    metric: DistanceMetric::default(),
+                    radius: None,
+                }
+            );
+
+            let result = loaded.solution.get_benchmark_result();
+            assert_eq!(result.states, states);
+            assert_eq!(result.value, 81.234375);
+            assert_eq!(result.horizon, 36);
+            assert!(loaded.settings.is_none());
+            assert!(loaded.reproduce().is_err());
+        }
+    }
 }
@@ -0,0 +1,119 @@
+//! GeoJSON export for visualizing solutions in map tools and import of team positions.
+use serde_json::json;
+
+use super::*;
+
+impl<T: Transition> TeamSolution<T> {
+    /// Get the coordinates of the vertex in team graph at the given index in GeoJSON format.
+    ///
+    /// NOTE: GeoJSON uses `[longitude, latitude]` order.
+    fn geojson_coordinates(&self, index: BusIndex) -> serde_json::Value {
+        let row = self.team_nodes.row(index as usize);
+        json!([row[1], row[0]])
+    }
+
+    /// Export the optimal first moves of the teams in the initial state as a GeoJSON
+    /// `FeatureCollection`.
+    ///
+    /// Contains a `Point` for the starting position of each team, and a `LineString` from the
+    /// starting position to the target bus of each team that moves.
+    /// If energization succeeds without team movement in the initial state, there are no
+    /// `LineString`s.
+    pub fn to_geojson_first_moves(&self) -> serde_json::Value {
+        let action = &self.transitions[0][self.policy[0] as usize];
+        // All transitions of an action lead to the same team state.
+        let successor = action[0].get_successor() as usize;
+
+        let mut features: Vec<serde_json::Value> = Vec::new();
+        for (team, (start, target)) in self
+            .teams
+            .row(0)
+            .iter()
+            .zip(self.teams.row(successor).iter())
+            .enumerate()
+        {
+            features.push(json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "Point",
+                    "coordinates": self.geojson_coordinates(start.index),
+                },
+                "properties": {
+                    "team": team,
+                    "node": start.index,
+                },
+            }));
+            if start.index != target.index {
+                features.push(json!({
+                    "type": "Feature",
+                    "geometry": {
+                        "type": "LineString",
+                        "coordinates": [
+                            self.geojson_coordinates(start.index),
+                            self.geojson_coordinates(target.index),
+                        ],
+                    },
+                    "properties": {
+                        "team": team,
+                        "from": start.index,
+                        "to": target.index,
+                    },
+                }));
+            }
+        }
+
+        json!({
+            "type": "FeatureCollection",
+            "features": features,
+        })
+    }
+}
+
+impl GenericTeamSolution {
+    /// Export the optimal first moves of the teams in the initial state as a GeoJSON
+    /// `FeatureCollection`.
+    ///
+    /// See [`TeamSolution::to_geojson_first_moves`].
+    pub fn to_geojson_first_moves(&self) -> serde_json::Value {
+        match self {
+            GenericTeamSolution::Timed(solution) => solution.to_geojson_first_moves(),
+            GenericTeamSolution::Regular(solution) => solution.to_geojson_first_moves(),
+        }
+    }
+}
+
+/// Parse the positions of the teams from a GeoJSON `FeatureCollection`, e.g., a live feed from a
+/// vehicle location system.
+///
+/// Each `Point` feature becomes a [`Team`] with the given `latlng`, and features with other
+/// geometry types are ignored.
+/// The `name` of the team is read from the `name` property of the feature if present.
+///
+/// NOTE: GeoJSON uses `[longitude, latitude]` order, unlike [`LatLng`].
+pub fn parse_team_positions(value: &serde_json::Value) -> Result<Vec<Team>, String> {
+    if value["type"] != "FeatureCollection" {
+        return Err("GeoJSON must be a FeatureCollection".to_string());
+    }
+    let features = value["features"]
+        .as_array()
+        .ok_or_else(|| "FeatureCollection has no features array".to_string())?;
+    let mut teams = Vec::new();
+    for (i, feature) in features.iter().enumerate() {
+        let geometry = &feature["geometry"];
+        if geometry["type"] != "Point" {
+            continue;
+        }
+        let coordinates = geometry["coordinates"]
+            .as_array()
+            .and_then(|c| Some((c.first()?.as_f64()?, c.get(1)?.as_f64()?)))
+            .ok_or_else(|| format!("Feature {i} has invalid Point coordinates"))?;
+        let (lng, lat) = coordinates;
+        teams.push(Team {
+            index: None,
+            latlng: Some(LatLng(lat, lng)),
+            name: feature["properties"]["name"].as_str().map(str::to_string),
+            node: None,
+        });
+    }
+    Ok(teams)
+}
@@ -0,0 +1,173 @@
+//! Human-readable JSON output.
+use super::*;
+use serde_json::ser::Formatter;
+use std::io::Write;
+
+/// JSON formatter that pretty-prints the values up to a given depth and writes the deeper
+/// values compactly.
+///
+/// For solutions, this puts each state's entry (e.g., the transitions of all actions in a state)
+/// on a single line, which is readable and yields line-based diffs.
+struct DepthLimitedFormatter {
+    /// Current nesting depth of arrays and objects.
+    depth: usize,
+    /// Maximum depth at which the values are written on separate lines.
+    max_depth: usize,
+    /// Whether the current array or object has any values.
+    has_value: bool,
+}
+
+impl DepthLimitedFormatter {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            depth: 0,
+            max_depth,
+            has_value: false,
+        }
+    }
+
+    /// Whether the values in the current array or object are written on separate lines.
+    #[inline]
+    fn is_pretty(&self) -> bool {
+        self.depth <= self.max_depth
+    }
+
+    fn begin_value<W: ?Sized + Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        if !first {
+            writer.write_all(b",")?;
+        }
+        if self.is_pretty() {
+            writer.write_all(b"\n")?;
+            indent(writer, self.depth)?;
+        }
+        Ok(())
+    }
+
+    fn end_container<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        if self.is_pretty() && self.has_value {
+            writer.write_all(b"\n")?;
+            indent(writer, self.depth - 1)?;
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+}
+
+/// Write the indentation for the given depth.
+fn indent<W: ?Sized + Write>(writer: &mut W, depth: usize) -> std::io::Result<()> {
+    for _ in 0..depth {
+        writer.write_all(b"  ")?;
+    }
+    Ok(())
+}
+
+impl Formatter for DepthLimitedFormatter {
+    fn begin_array<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.depth += 1;
+        self.has_value = false;
+        writer.write_all(b"[")
+    }
+
+    fn end_array<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.end_container(writer)?;
+        writer.write_all(b"]")
+    }
+
+    fn begin_array_value<W: ?Sized + Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        self.begin_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + Write>(&mut self, _writer: &mut W) -> std::io::Result<()> {
+        self.has_value = true;
+        Ok(())
+    }
+
+    fn begin_object<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.depth += 1;
+        self.has_value = false;
+        writer.write_all(b"{")
+    }
+
+    fn end_object<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.end_container(writer)?;
+        writer.write_all(b"}")
+    }
+
+    fn begin_object_key<W: ?Sized + Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        self.begin_value(writer, first)
+    }
+
+    fn begin_object_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        if self.is_pretty() {
+            writer.write_all(b": ")
+        } else {
+            writer.write_all(b":")
+        }
+    }
+
+    fn end_object_value<W: ?Sized + Write>(&mut self, _writer: &mut W) -> std::io::Result<()> {
+        self.has_value = true;
+        Ok(())
+    }
+}
+
+/// Serialize the given value as JSON, writing the arrays and objects up to the given depth on
+/// separate lines with indentation and the deeper ones compactly.
+pub fn to_depth_limited_json<T: ?Sized + Serialize>(
+    value: &T,
+    max_depth: usize,
+) -> serde_json::Result<String> {
+    let mut writer = Vec::with_capacity(128);
+    let mut ser =
+        serde_json::Serializer::with_formatter(&mut writer, DepthLimitedFormatter::new(max_depth));
+    value.serialize(&mut ser)?;
+    // serde_json only emits valid UTF-8.
+    Ok(String::from_utf8(writer).expect("JSON output is not valid UTF-8"))
+}
+
+/// Depth up to which the solutions are pretty-printed: the fields and each entry of the top-level
+/// arrays (e.g., states, transitions of a state) are written on separate lines.
+const SOLUTION_PRETTY_DEPTH: usize = 2;
+
+impl<T: Transition> TeamSolution<T> {
+    /// Serialize this solution as pretty-printed JSON for human inspection and stable diffs.
+    ///
+    /// Fields are written in the same order as the compact representation, each on its own line.
+    /// The entries for each state are written on a single line.
+    pub fn to_pretty_json(&self) -> serde_json::Result<String> {
+        to_depth_limited_json(self, SOLUTION_PRETTY_DEPTH)
+    }
+}
+
+impl GenericTeamSolution {
+    /// Serialize this solution as pretty-printed JSON.
+    ///
+    /// See [`TeamSolution::to_pretty_json`].
+    pub fn to_pretty_json(&self) -> serde_json::Result<String> {
+        to_depth_limited_json(self, SOLUTION_PRETTY_DEPTH)
+    }
+}
+
+impl<'a, S: ?Sized> SolutionSerializer<'a, S>
+where
+    Self: Serialize,
+{
+    /// Serialize the solution with the given options as pretty-printed JSON.
+    ///
+    /// See [`TeamSolution::to_pretty_json`].
+    pub fn to_pretty_json(&self) -> serde_json::Result<String> {
+        to_depth_limited_json(self, SOLUTION_PRETTY_DEPTH)
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::teams::state::State;
+
+use super::*;
+
+/// An action in a state of [`SolutionReplay`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayAction {
+    /// Index of this action in the state.
+    pub index: usize,
+    /// Target bus of each team.
+    pub targets: Vec<BusIndex>,
+}
+
+/// Wraps a [`TeamSolution`] to step through its optimal policy manually, e.g., for interactive
+/// demonstrations.
+///
+/// Starts from the initial state of the solution. At each step, the outcome of the optimal action
+/// is chosen with [`SolutionReplay::apply`].
+pub struct SolutionReplay<T: Transition> {
+    /// Underlying solution.
+    solution: TeamSolution<T>,
+    /// Maps the contents of each state to its index in the solution.
+    indices: HashMap<State, usize>,
+    /// Index of the current state.
+    current: usize,
+}
+
+impl<T: Transition> SolutionReplay<T> {
+    /// Wrap the given solution, starting from its initial state.
+    pub fn new(solution: TeamSolution<T>) -> Self {
+        let indices = (0..solution.transitions.len())
+            .map(|i| (solution.get_state(i), i))
+            .collect();
+        Self {
+            solution,
+            indices,
+            current: 0,
+        }
+    }
+
+    /// Get the underlying solution.
+    pub fn solution(&self) -> &TeamSolution<T> {
+        &self.solution
+    }
+
+    /// Get the index of the current state in the solution.
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Get the current state.
+    pub fn current_state(&self) -> State {
+        self.solution.get_state(self.current)
+    }
+
+    /// Jump to the given state.
+    ///
+    /// Returns an error if the state is not in the solution.
+    pub fn set_state(&mut self, state: &State) -> Result<(), String> {
+        match self.indices.get(state) {
+            Some(&index) => {
+                self.current = index;
+                Ok(())
+            }
+            None => Err(format!("State {state} is not in the solution")),
+        }
+    }
+
+    /// Returns true if the current state is terminal, i.e., the optimal action is a
+    /// self-transition.
+    pub fn is_terminal(&self) -> bool {
+        let action = self.optimal_transitions();
+        action.len() == 1 && action[0].get_successor() as usize == self.current
+    }
+
+    /// Transitions of the optimal action in the current state.
+    fn optimal_transitions(&self) -> &[T] {
+        let action_index = self.solution.policy[self.current] as usize;
+        &self.solution.transitions[self.current][action_index]
+    }
+
+    /// Get the optimal action in the current state, or `None` if the current state is terminal.
+    ///
+    /// The targets of the teams are determined from the successor states.
+    pub fn optimal_action(&self) -> Option<ReplayAction> {
+        if self.is_terminal() {
+            return None;
+        }
+        let successor = self.optimal_transitions()[0].get_successor() as usize;
+        Some(ReplayAction {
+            index: self.solution.policy[self.current] as usize,
+            targets: self
+                .solution
+                .teams
+                .row(successor)
+                .iter()
+                .map(|team| team.index)
+                .collect(),
+        })
+    }
+
+    /// Get the possible successor states with their probabilities when the optimal action is
+    /// taken in the current state.
+    ///
+    /// Empty if the current state is terminal.
+    pub fn outcomes(&self) -> Vec<(Probability, State)> {
+        if self.is_terminal() {
+            return Vec::new();
+        }
+        self.optimal_transitions()
+            .iter()
+            .map(|t| {
+                (
+                    t.get_probability(),
+                    self.solution.get_state(t.get_successor() as usize),
+                )
+            })
+            .collect()
+    }
+
+    /// Take the optimal action in the current state and move to the outcome with the given index
+    /// in [`SolutionReplay::outcomes`].
+    ///
+    /// Returns the new state on success.
+    pub fn apply(&mut self, outcome_index: usize) -> Result<State, String> {
+        if self.is_terminal() {
+            return Err("Cannot apply an action in a terminal state".to_string());
+        }
+        let transition = self
+            .optimal_transitions()
+            .get(outcome_index)
+            .ok_or_else(|| format!("Outcome index {outcome_index} is out of bounds"))?;
+        self.current = transition.get_successor() as usize;
+        Ok(self.current_state())
+    }
+
+    /// Go back to the initial state.
+    pub fn reset(&mut self) {
+        self.current = 0;
+    }
+}
@@ -1,7 +1,5 @@
 use std::time::Instant;
 
-use crate::teams::state::State;
-
 use super::*;
 
 /// Result of taking all possible paths to terminal states in an MDP.
@@ -12,14 +10,55 @@ pub struct RestorationSimulationResult {
     pub energization_p: Vec<f64>,
     /// For each bus, average time until energization (in all paths that energize it).
     pub avg_time: Vec<f64>,
+    /// For each bus, variance of the time until energization in the paths that energize it.
+    /// NaN if the bus is never energized.
+    pub time_variance: Vec<f64>,
+    /// For each bus, standard deviation of the time until energization in the paths that
+    /// energize it. NaN if the bus is never energized.
+    pub time_std: Vec<f64>,
     /// Number of transitions simulated.
     pub simulated_transitions: usize,
     /// Execution time in seconds.
     pub runtime: f64,
 }
 
+/// Bus and team states at a point in time, used for animating a restoration process.
+/// See [`TeamSolution::trajectory_frames`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Frame {
+    /// State of each bus.
+    pub buses: Vec<BusState>,
+    /// State of each team.
+    pub teams: Vec<TeamState>,
+    /// Time elapsed since the initial state, in the same unit as [`TeamSolution::horizon`].
+    pub cumulative_time: usize,
+}
+
+/// Error for the analyses that require the optimal policy to reach a terminal state.
+fn cyclic_policy(index: usize) -> SolveFailure {
+    SolveFailure::BadInput(format!(
+        "The optimal policy is cyclic, state {index} is revisited before reaching a terminal state"
+    ))
+}
+
 impl<T: Transition> TeamSolution<T> {
+    /// Returns true if the state at the given index is terminal, i.e., its only action is a
+    /// self-transition, which includes the unexplored states of a partial solution.
+    ///
+    /// A wait action (see [`teams::Config::global_wait`]) is a self-transition too, but it's the
+    /// only action of a non-terminal state only if no other action is feasible in it.
+    pub fn is_terminal_state(&self, index: usize) -> bool {
+        match self.transitions[index].as_slice() {
+            [action] => matches!(action.as_slice(), [t] if t.get_successor() as usize == index),
+            _ => false,
+        }
+    }
+
     /// Simulate a all possible restoration processes starting from the inital state.
+    ///
+    /// Each process ends in a terminal state (see [`TeamSolution::is_terminal_state`]) or at the
+    /// horizon, whichever comes first.
     pub fn simulate_all(&self) -> RestorationSimulationResult {
         let start_time = Instant::now();
 
@@ -28,27 +67,32 @@ impl<T: Transition> TeamSolution<T> {
         let mut result = RestorationSimulationResult {
             energization_p: vec![0.0; bus_count],
             avg_time: vec![0.0; bus_count],
+            time_variance: vec![0.0; bus_count],
+            time_std: vec![0.0; bus_count],
             simulated_transitions: 0,
             runtime: 0.0,
         };
 
         fn visit<T: Transition>(
-            state: State,
             index: usize,
             p: f64,
             time: usize,
+            elapsed: usize,
             solution: &TeamSolution<T>,
             result: &mut RestorationSimulationResult,
+            squared_time: &mut [f64],
         ) {
-            let action_index = solution.policy[index] as usize;
-            let action = &solution.transitions[index][action_index];
-            if action.len() == 1 && action[0].get_successor() as usize == index {
-                // Terminal state
+            // The process stops at the horizon if the optimal policy is cyclic, e.g., waits in a
+            // non-terminal state.
+            if solution.is_terminal_state(index) || elapsed >= solution.horizon {
                 return;
             }
+            let action_index = solution.policy[index] as usize;
+            let action = &solution.transitions[index][action_index];
+            let buses = solution.states.row(index);
             for transition in action {
                 let successor_index = transition.get_successor() as usize;
-                let successor_state = solution.get_state(successor_index);
+                let successor_buses = solution.states.row(successor_index);
                 let p = p * (transition.get_probability() as f64);
                 // This is because costless transition still has time = 1.
                 let time = if transition.get_cost() == (0 as Cost) {
@@ -57,24 +101,48 @@ impl<T: Transition> TeamSolution<T> {
                     time + (transition.get_time() as usize)
                 };
 
-                for (i, (&a, &b)) in state
-                    .buses
-                    .iter()
-                    .zip(successor_state.buses.iter())
-                    .enumerate()
-                {
+                for (i, (&a, &b)) in buses.iter().zip(successor_buses.iter()).enumerate() {
                     if a != b && b == BusState::Energized {
                         result.energization_p[i] += p;
                         result.avg_time[i] += p * (time as f64);
+                        squared_time[i] += p * (time as f64).powi(2);
                     }
                 }
 
-                visit(successor_state, successor_index, p, time, solution, result);
+                visit(
+                    successor_index,
+                    p,
+                    time,
+                    elapsed + (transition.get_time() as usize),
+                    solution,
+                    result,
+                    squared_time,
+                );
                 result.simulated_transitions += 1;
             }
         }
 
-        visit(self.get_state(0), 0, 1.0, 0, self, &mut result);
+        // For each bus, the sum of p * time^2 in all paths that energize it.
+        let mut squared_time: Vec<f64> = vec![0.0; bus_count];
+
+        visit(0, 1.0, 0, 0, self, &mut result, &mut squared_time);
+
+        for (&p, &avg_time, &squared_time, variance, std) in itertools::izip!(
+            result.energization_p.iter(),
+            result.avg_time.iter(),
+            squared_time.iter(),
+            result.time_variance.iter_mut(),
+            result.time_std.iter_mut(),
+        ) {
+            *variance = if p > 0.0 {
+                let mean = avg_time / p;
+                // Clamp to avoid negative values due to floating point errors.
+                (squared_time / p - mean * mean).max(0.0)
+            } else {
+                f64::NAN
+            };
+            *std = variance.sqrt();
+        }
 
         result.runtime = start_time.elapsed().as_secs_f64();
 
@@ -86,6 +154,222 @@ impl<T: Transition> TeamSolution<T> {
 
         result
     }
+
+    /// Get the expected number of dispatch moves under the optimal policy starting from the
+    /// initial state, i.e., the expected number of transitions in which a ready team is sent to a
+    /// node other than the one it's on.
+    ///
+    /// Returns [`SolveFailure::BadInput`] if the optimal policy is cyclic, e.g., if it waits in a
+    /// non-terminal state (see [`teams::Config::global_wait`]), since a terminal state may never
+    /// be reached in that case.
+    pub fn expected_moves(&self) -> Result<f64, SolveFailure> {
+        fn visit<T: Transition>(
+            index: usize,
+            solution: &TeamSolution<T>,
+            memo: &mut [Option<f64>],
+            visiting: &mut [bool],
+        ) -> Result<f64, SolveFailure> {
+            if let Some(expected) = memo[index] {
+                return Ok(expected);
+            }
+            if visiting[index] {
+                return Err(cyclic_policy(index));
+            }
+            visiting[index] = true;
+            let action = &solution.transitions[index][solution.policy[index] as usize];
+            let expected = if solution.is_terminal_state(index) {
+                0.0
+            } else {
+                // All outcomes of an action have the same team states.
+                let successor = action[0].get_successor() as usize;
+                let moved = solution
+                    .teams
+                    .row(index)
+                    .iter()
+                    .zip(solution.teams.row(successor).iter())
+                    .any(|(team, next)| team.time == 0 && team.index != next.index);
+                let mut expected = if moved { 1.0 } else { 0.0 };
+                for transition in action {
+                    expected += (transition.get_probability() as f64)
+                        * visit(
+                            transition.get_successor() as usize,
+                            solution,
+                            memo,
+                            visiting,
+                        )?;
+                }
+                expected
+            };
+            memo[index] = Some(expected);
+            Ok(expected)
+        }
+
+        let mut memo = vec![None; self.transitions.len()];
+        let mut visiting = vec![false; self.transitions.len()];
+        visit(0, self, &mut memo, &mut visiting)
+    }
+
+    /// Get the expected fraction of time each team spends working under the optimal policy,
+    /// i.e., the expected active time of each team over the expected time until a terminal
+    /// state is reached.
+    ///
+    /// A team is active while it's en route or dispatched in a transition, or while it's
+    /// energizing the bus it's on in the initial state. Otherwise, it's idle.
+    /// The movements are inferred from the team states of the successors, and their durations are
+    /// given by [`teams::Graph::dispatch_time`] on the given graph (the graph that is solved,
+    /// before quantization). Zero for all teams if the initial state is terminal.
+    ///
+    /// Returns [`SolveFailure::BadInput`] if the optimal policy is cyclic like
+    /// [`TeamSolution::expected_moves`].
+    pub fn team_utilization(&self, graph: &teams::Graph) -> Result<Vec<f64>, SolveFailure> {
+        /// Expected total time and active time of each team until a terminal state.
+        type Expected = (f64, Vec<f64>);
+
+        fn visit<T: Transition>(
+            index: usize,
+            solution: &TeamSolution<T>,
+            graph: &teams::Graph,
+            memo: &mut [Option<Expected>],
+            visiting: &mut [bool],
+        ) -> Result<Expected, SolveFailure> {
+            if let Some(expected) = &memo[index] {
+                return Ok(expected.clone());
+            }
+            if visiting[index] {
+                return Err(cyclic_policy(index));
+            }
+            visiting[index] = true;
+            let team_count = solution.teams.ncols();
+            let action = &solution.transitions[index][solution.policy[index] as usize];
+            let expected = if solution.is_terminal_state(index) {
+                (0.0, vec![0.0; team_count])
+            } else {
+                let buses = solution.states.row(index);
+                let mut expected = (0.0, vec![0.0; team_count]);
+                for transition in action {
+                    let successor = transition.get_successor() as usize;
+                    let p = transition.get_probability() as f64;
+                    let time = transition.get_time();
+                    let (total, active) = visit(successor, solution, graph, memo, visiting)?;
+                    expected.0 += p * (time as f64 + total);
+                    for (i, (team, next)) in solution
+                        .teams
+                        .row(index)
+                        .iter()
+                        .zip(solution.teams.row(successor).iter())
+                        .enumerate()
+                    {
+                        let remaining = if team.time > 0 {
+                            team.time
+                        } else if team.index != next.index {
+                            graph.dispatch_time(team.index, next.index)
+                        } else if is_energizing(graph, buses, team.index) {
+                            time
+                        } else {
+                            0
+                        };
+                        let team_active = remaining.min(time) as f64;
+                        expected.1[i] += p * (team_active + active[i]);
+                    }
+                }
+                expected
+            };
+            memo[index] = Some(expected.clone());
+            Ok(expected)
+        }
+
+        /// Returns true if a team that stays on the given node energizes it, which only happens
+        /// in the initial state.
+        fn is_energizing(
+            graph: &teams::Graph,
+            buses: ArrayView1<BusState>,
+            node: BusIndex,
+        ) -> bool {
+            let i = node as usize;
+            i < buses.len()
+                && buses[i] == BusState::Unknown
+                && (graph.is_connected(i)
+                    || graph.branches[i]
+                        .iter()
+                        .any(|&j| buses[j as usize] == BusState::Energized))
+        }
+
+        let graph = graph.quantized(self.time_quantum);
+        let mut memo = vec![None; self.transitions.len()];
+        let mut visiting = vec![false; self.transitions.len()];
+        let (total, active) = visit(0, self, &graph, &mut memo, &mut visiting)?;
+        Ok(if total > 0.0 {
+            active.into_iter().map(|active| active / total).collect()
+        } else {
+            active
+        })
+    }
+
+    /// Get the most likely path from the initial state to a terminal state under the optimal
+    /// policy, following the transition with the highest probability (the first one if tied) in
+    /// each state.
+    ///
+    /// Each element is the index of a state and the optimal action in it. The last element is
+    /// the terminal state, or the first state reached at or after the horizon if the optimal
+    /// policy is cyclic, e.g., waits in a non-terminal state. The initial state is the first
+    /// element even if it has only zero-timed transitions, e.g., when a team starts on an
+    /// energizable bus.
+    pub fn most_likely_trajectory(&self) -> Vec<(usize, ActionIndex)> {
+        let mut trajectory = Vec::new();
+        let mut index = 0;
+        let mut elapsed: usize = 0;
+        loop {
+            let action_index = self.policy[index];
+            trajectory.push((index, action_index));
+            if self.is_terminal_state(index) || elapsed >= self.horizon {
+                return trajectory;
+            }
+            let action = &self.transitions[index][action_index as usize];
+            let most_likely = action
+                .iter()
+                .reduce(|best, t| {
+                    if t.get_probability() > best.get_probability() {
+                        t
+                    } else {
+                        best
+                    }
+                })
+                .expect("No transitions in an action");
+            index = most_likely.get_successor() as usize;
+            elapsed += most_likely.get_time() as usize;
+        }
+    }
+
+    /// Get a frame for each state in the most likely trajectory (see
+    /// [`TeamSolution::most_likely_trajectory`]), including the initial and terminal states.
+    ///
+    /// The cumulative time is the sum of the times of the transitions taken so far. It's
+    /// non-decreasing and doesn't exceed the horizon, to which it's clamped if the optimal policy
+    /// is cyclic. Consecutive frames may have the same time if a transition takes zero time.
+    pub fn trajectory_frames(&self) -> Vec<Frame> {
+        let trajectory = self.most_likely_trajectory();
+        let mut frames = Vec::with_capacity(trajectory.len());
+        let mut cumulative_time: usize = 0;
+        let mut prev: Option<(usize, ActionIndex)> = None;
+        for &(index, action) in trajectory.iter() {
+            if let Some((prev, prev_action)) = prev {
+                let time = self.transitions[prev][prev_action as usize]
+                    .iter()
+                    .find(|t| t.get_successor() as usize == index)
+                    .expect("Trajectory contains a state that is not a successor")
+                    .get_time();
+                cumulative_time = (cumulative_time + time as usize).min(self.horizon);
+            }
+            prev = Some((index, action));
+            let state = self.get_state(index);
+            frames.push(Frame {
+                buses: state.buses,
+                teams: state.teams,
+                cumulative_time,
+            });
+        }
+        frames
+    }
 }
 
 impl GenericTeamSolution {
@@ -96,4 +380,40 @@ impl GenericTeamSolution {
             GenericTeamSolution::Regular(solution) => solution.simulate_all(),
         }
     }
+
+    /// Get the expected number of dispatch moves under the optimal policy.
+    /// See [`TeamSolution::expected_moves`].
+    pub fn expected_moves(&self) -> Result<f64, SolveFailure> {
+        match self {
+            GenericTeamSolution::Timed(solution) => solution.expected_moves(),
+            GenericTeamSolution::Regular(solution) => solution.expected_moves(),
+        }
+    }
+
+    /// Get the expected fraction of time each team spends working under the optimal policy.
+    /// See [`TeamSolution::team_utilization`].
+    pub fn team_utilization(&self, graph: &teams::Graph) -> Result<Vec<f64>, SolveFailure> {
+        match self {
+            GenericTeamSolution::Timed(solution) => solution.team_utilization(graph),
+            GenericTeamSolution::Regular(solution) => solution.team_utilization(graph),
+        }
+    }
+
+    /// Get the most likely path under the optimal policy.
+    /// See [`TeamSolution::most_likely_trajectory`].
+    pub fn most_likely_trajectory(&self) -> Vec<(usize, ActionIndex)> {
+        match self {
+            GenericTeamSolution::Timed(solution) => solution.most_likely_trajectory(),
+            GenericTeamSolution::Regular(solution) => solution.most_likely_trajectory(),
+        }
+    }
+
+    /// Get the frames of the most likely trajectory under the optimal policy.
+    /// See [`TeamSolution::trajectory_frames`].
+    pub fn trajectory_frames(&self) -> Vec<Frame> {
+        match self {
+            GenericTeamSolution::Timed(solution) => solution.trajectory_frames(),
+            GenericTeamSolution::Regular(solution) => solution.trajectory_frames(),
+        }
+    }
 }
@@ -1,5 +1,51 @@
 use super::*;
 
+/// Parse the graph of the example in the paper, `graphs/FieldTeams/paperE0.json`.
+fn get_paper_example_graph() -> Graph {
+    serde_json::from_str(include_str!("../../../graphs/FieldTeams/paperE0.json")).unwrap()
+}
+
+/// Build a problem on [`get_paper_example_graph`] with the given teams and default settings.
+fn get_paper_example_problem(teams: Vec<Team>) -> TeamProblem {
+    TeamProblem {
+        name: None,
+        graph: get_paper_example_graph(),
+        teams,
+        horizon: HorizonSpec::Auto,
+        pfo: None,
+        time_func: TimeFunc::default(),
+        strict_branches: false,
+        repair_times: None,
+        time_scale: None,
+        depots: Vec::new(),
+        deadlines: None,
+        start_distribution: None,
+    }
+}
+
+/// Build an unnamed team that starts at the bus with the given index.
+fn team_at(index: usize) -> Team {
+    Team {
+        index: Some(index),
+        latlng: None,
+        name: None,
+        node: None,
+    }
+}
+
+/// Build a graph with two buses, the first of which is connected, and a separate team node at
+/// index 2.
+fn get_two_bus_graph() -> teams::Graph {
+    teams::Graph {
+        travel_times: ndarray::arr2(&[[0, 4, 2], [4, 0, 3], [2, 3, 0]]),
+        branches: vec![vec![1], vec![0]],
+        connected: vec![true, false],
+        pfs: ndarray::arr1(&[0.5, 0.25]),
+        team_nodes: ndarray::arr2(&[[41.0, 29.0], [41.5, 29.5], [40.75, 28.5]]),
+        ..Default::default()
+    }
+}
+
 #[test]
 fn basic() {
     let data = r#"
@@ -74,3 +120,1202 @@ fn basic() {
     assert_eq!(v.nodes[0].latlng.0, v.nodes[1].latlng.0);
     assert_eq!(v.nodes[0].latlng.1, v.nodes[1].latlng.1);
 }
+
+/// Build a small problem on a line graph where the branch between bus 1 and 2 is duplicated.
+fn duplicate_branch_problem(strict_branches: bool) -> TeamProblem {
+    let node = Node {
+        pf: 0.5,
+        latlng: LatLng(41.0, 29.0),
+        name: None,
+        weight: None,
+    };
+    TeamProblem {
+        name: None,
+        graph: Graph {
+            name: "Duplicate Branch".to_string(),
+            branches: vec![
+                Branch {
+                    nodes: BranchNodes(0, 1),
+                },
+                Branch {
+                    nodes: BranchNodes(1, 2),
+                },
+                Branch {
+                    nodes: BranchNodes(2, 1),
+                },
+            ],
+            external: vec![ExtBranch { node: 0, source: 0 }],
+            nodes: vec![node.clone(), node.clone(), node],
+            resources: vec![Resource {
+                latlng: LatLng(41.0, 29.0),
+                kind: None,
+                outage: false,
+            }],
+        },
+        teams: vec![team_at(0)],
+        horizon: HorizonSpec::Auto,
+        pfo: None,
+        time_func: TimeFunc::default(),
+        strict_branches,
+        repair_times: None,
+        time_scale: None,
+        depots: Vec::new(),
+        deadlines: None,
+        start_distribution: None,
+    }
+}
+
+#[test]
+fn duplicate_branch_dedup() {
+    let (problem, _config) = duplicate_branch_problem(false).prepare().unwrap();
+    let graph = problem.graph;
+    assert_eq!(graph.branches, vec![vec![1], vec![0, 2], vec![1]]);
+
+    let state = teams::state::State::start_state(&graph, problem.initial_teams);
+    assert_eq!(state.compute_minbeta(&graph), vec![1, 2, 3]);
+}
+
+#[test]
+fn duplicate_branch_strict() {
+    let result = duplicate_branch_problem(true).prepare();
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+/// Build a problem with three isolated buses: bus 0 is fed from source 0, bus 1 from source 1,
+/// and bus 2 from both.
+fn multi_source_problem(outage: bool) -> TeamProblem {
+    let node = Node {
+        pf: 0.5,
+        latlng: LatLng(41.0, 29.0),
+        name: None,
+        weight: None,
+    };
+    let resource = Resource {
+        latlng: LatLng(41.0, 29.0),
+        kind: None,
+        outage: false,
+    };
+    TeamProblem {
+        graph: Graph {
+            name: "Multiple Sources".to_string(),
+            branches: Vec::new(),
+            external: vec![
+                ExtBranch { node: 0, source: 0 },
+                ExtBranch { node: 1, source: 1 },
+                ExtBranch { node: 2, source: 0 },
+                ExtBranch { node: 2, source: 1 },
+            ],
+            nodes: vec![node.clone(), node.clone(), node],
+            resources: vec![resource.clone(), Resource { outage, ..resource }],
+        },
+        ..duplicate_branch_problem(false)
+    }
+}
+
+#[test]
+fn source_outage() {
+    let (problem, _config) = multi_source_problem(false).prepare().unwrap();
+    let state = teams::state::State::start_state(&problem.graph, problem.initial_teams);
+    assert_eq!(state.compute_minbeta(&problem.graph), vec![1, 1, 1]);
+
+    let (problem, _config) = multi_source_problem(true).prepare().unwrap();
+    let graph = problem.graph;
+    assert!(graph.is_connected(0));
+    assert!(!graph.is_connected(1));
+    assert!(graph.is_connected(2));
+    let state = teams::state::State::start_state(&graph, problem.initial_teams);
+    assert_eq!(state.compute_minbeta(&graph), vec![1, BusIndex::MAX, 1]);
+
+    // Bus 1 cannot be energized while its only source is out of service.
+    let solution = multi_source_problem(true).solve_naive().unwrap();
+    assert!(solution
+        .states
+        .rows()
+        .into_iter()
+        .all(|buses| buses[1] == BusState::Unknown));
+    let solution = multi_source_problem(false).solve_naive().unwrap();
+    assert!(solution
+        .states
+        .rows()
+        .into_iter()
+        .any(|buses| buses[1] != BusState::Unknown));
+}
+
+#[test]
+fn external_branch_out_of_bounds() {
+    let mut team_problem = multi_source_problem(false);
+    team_problem
+        .graph
+        .external
+        .push(ExtBranch { node: 3, source: 0 });
+    let result = team_problem.prepare();
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+
+    let mut team_problem = multi_source_problem(false);
+    team_problem
+        .graph
+        .external
+        .push(ExtBranch { node: 0, source: 2 });
+    let result = team_problem.prepare();
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+#[test]
+fn node_weights() {
+    let (problem, _config) = multi_source_problem(false).prepare().unwrap();
+    assert_eq!(problem.graph.weights, None);
+
+    let mut team_problem = multi_source_problem(false);
+    team_problem.graph.nodes[1].weight = Some(5);
+    let (problem, _config) = team_problem.prepare().unwrap();
+    let graph = problem.graph;
+    assert_eq!(graph.weights, Some(vec![1 as Cost, 5 as Cost, 1 as Cost]));
+    let state = teams::state::State::start_state(&graph, problem.initial_teams);
+    assert_eq!(state.get_cost(&graph), 7 as Cost);
+
+    // The total weight must be representable as the cost type.
+    let mut team_problem = multi_source_problem(false);
+    team_problem.graph.nodes[1].weight = Some(u32::MAX);
+    team_problem.graph.nodes[2].weight = Some(u32::MAX);
+    let result = team_problem.prepare();
+    #[cfg(feature = "minmem")]
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+    #[cfg(not(feature = "minmem"))]
+    assert!(result.is_ok());
+}
+
+#[test]
+fn infeasible_problems() {
+    let message = |problem: TeamProblem| match problem.prepare() {
+        Err(SolveFailure::BadInput(message)) => message,
+        _ => panic!("Infeasible problem is accepted"),
+    };
+
+    // The only source is out of service.
+    let mut problem = duplicate_branch_problem(false);
+    problem.graph.resources = vec![Resource {
+        latlng: LatLng(41.0, 29.0),
+        kind: None,
+        outage: true,
+    }];
+    assert_eq!(
+        message(problem),
+        "Infeasible problem: all 3 unknown buses are unreachable from the energy sources"
+    );
+
+    // No external branches.
+    let mut problem = duplicate_branch_problem(false);
+    problem.graph.external.clear();
+    assert_eq!(
+        message(problem),
+        "Infeasible problem: all 3 unknown buses are unreachable from the energy sources"
+    );
+
+    let mut problem = multi_source_problem(true);
+    problem.teams.clear();
+    assert_eq!(
+        message(problem),
+        "Infeasible problem: there are no teams but 2 unknown buses are reachable"
+    );
+
+    // Nothing to restore when all buses are damaged, even without teams.
+    let mut problem = duplicate_branch_problem(false);
+    problem.teams.clear();
+    problem.pfo = Some(1.0);
+    assert!(problem.prepare().is_ok());
+}
+
+#[test]
+fn named_teams() {
+    let mut problem = duplicate_branch_problem(false);
+    for (node, name) in problem.graph.nodes.iter_mut().zip(["A", "B", "C"]) {
+        node.name = Some(name.to_string());
+    }
+    problem.teams = vec![
+        Team {
+            index: None,
+            latlng: None,
+            name: Some("Crew 1".to_string()),
+            node: Some("C".to_string()),
+        },
+        Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: Some("C".to_string()),
+        },
+    ];
+
+    let (prepared, _config) = problem.clone().prepare().unwrap();
+    let indices: Vec<BusIndex> = prepared.initial_teams.iter().map(|t| t.index).collect();
+    // Index takes precedence over node name.
+    assert_eq!(indices, vec![2, 0]);
+
+    let solution = problem.clone().solve_naive().unwrap();
+    assert_eq!(solution.team_names, vec![Some("Crew 1".to_string()), None]);
+    assert_eq!(
+        solution.node_names,
+        vec![
+            Some("A".to_string()),
+            Some("B".to_string()),
+            Some("C".to_string())
+        ]
+    );
+
+    problem.teams[0].node = Some("D".to_string());
+    let result = problem.clone().prepare();
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+
+    problem.teams[0].node = Some("B".to_string());
+    problem.graph.nodes[0].name = Some("B".to_string());
+    let result = problem.prepare();
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+#[test]
+fn geojson_first_moves() {
+    let graph = get_paper_example_graph();
+    let problem = |team: Team| get_paper_example_problem(vec![team]);
+    let solve = |problem: TeamProblem| {
+        problem
+            .solve_custom_timed(
+                "NaiveStateIndexer",
+                "NaiveActions",
+                "TimedActionApplier<TimeUntilEnergization>",
+                "NaiveTimedPolicySynthesizer",
+            )
+            .unwrap()
+    };
+
+    // The team starts at a separate location and moves to a bus.
+    let start = LatLng(41.0153, 29.0800);
+    let solution = solve(problem(Team {
+        index: None,
+        latlng: Some(start.clone()),
+        name: None,
+        node: None,
+    }));
+    let geojson = solution.to_geojson_first_moves();
+    assert_eq!(geojson["type"], "FeatureCollection");
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 2);
+    assert_eq!(features[0]["geometry"]["type"], "Point");
+    assert_eq!(
+        features[0]["geometry"]["coordinates"],
+        serde_json::json!([start.1, start.0])
+    );
+    assert_eq!(features[1]["geometry"]["type"], "LineString");
+    assert_eq!(features[1]["properties"]["from"], graph.nodes.len());
+
+    // The team starts on a bus connected to the source, energizes it without moving.
+    let solution = solve(problem(team_at(0)));
+    let geojson = solution.to_geojson_first_moves();
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 1);
+    assert_eq!(features[0]["geometry"]["type"], "Point");
+}
+
+#[test]
+fn geojson_team_positions() {
+    let feed = serde_json::json!({
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [29.08, 41.01] },
+                "properties": { "name": "Crew 1" },
+            },
+            {
+                "type": "Feature",
+                "geometry": { "type": "LineString", "coordinates": [[29.0, 41.0], [29.1, 41.1]] },
+                "properties": {},
+            },
+            {
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [28.97, 40.99] },
+                "properties": null,
+            },
+        ],
+    });
+    let teams = geojson::parse_team_positions(&feed).unwrap();
+    assert_eq!(teams.len(), 2);
+    assert_eq!(teams[0].latlng, Some(LatLng(41.01, 29.08)));
+    assert_eq!(teams[0].name, Some("Crew 1".to_string()));
+    assert_eq!(teams[0].index, None);
+    assert_eq!(teams[1].latlng, Some(LatLng(40.99, 28.97)));
+    assert_eq!(teams[1].name, None);
+
+    let invalid = serde_json::json!({
+        "type": "FeatureCollection",
+        "features": [{ "type": "Feature", "geometry": { "type": "Point", "coordinates": [29.0] } }],
+    });
+    assert!(geojson::parse_team_positions(&invalid).is_err());
+    assert!(geojson::parse_team_positions(&serde_json::json!({ "type": "Feature" })).is_err());
+}
+
+#[test]
+fn time_scale_prepare() {
+    let mut problem = get_paper_example_problem(vec![team_at(0)]);
+    problem.repair_times = Some(vec![1; problem.graph.nodes.len()]);
+    let (original, _) = problem.clone().prepare().unwrap();
+    let (scaled, _) = TeamProblem {
+        time_scale: Some(10.0),
+        ..problem
+    }
+    .prepare()
+    .unwrap();
+
+    // Scaling happens before rounding up
+    for (&t, &scaled_t) in original
+        .graph
+        .travel_times
+        .iter()
+        .zip(scaled.graph.travel_times.iter())
+    {
+        assert!(scaled_t <= t * 10);
+        assert!(scaled_t + 10 > t * 10);
+    }
+    assert_eq!(
+        scaled.graph.repair_times,
+        Some(Array1::from_elem(original.graph.branches.len(), 10))
+    );
+}
+
+#[test]
+fn best_combinations_test() {
+    let optimizations = |indexer: &str| OptimizationInfo {
+        indexer: indexer.to_string(),
+        actions: "NaiveActions".to_string(),
+        transitions: "NaiveActionApplier".to_string(),
+        synthesizer: None,
+    };
+    let benchmark = |total_time: f64, transitions: usize| BenchmarkResult {
+        total_time,
+        generation_time: total_time / 2.0,
+        max_memory: 0,
+        states: transitions / 2,
+        transitions,
+        value: 1.0,
+        horizon: 10,
+        action_stats: None,
+    };
+    let results = vec![
+        OptimizationBenchmarkResult {
+            optimizations: optimizations("A"),
+            result: Ok(benchmark(2.0, 100)),
+        },
+        OptimizationBenchmarkResult {
+            optimizations: optimizations("B"),
+            result: Err(SolveFailure::OutOfMemory { used: 2, limit: 1 }),
+        },
+        OptimizationBenchmarkResult {
+            optimizations: optimizations("C"),
+            result: Ok(benchmark(1.0, 200)),
+        },
+        OptimizationBenchmarkResult {
+            optimizations: optimizations("D"),
+            result: Ok(benchmark(3.0, 50)),
+        },
+    ];
+
+    let best = best_combinations(&results);
+    assert_eq!(best.fastest.unwrap().indexer, "C");
+    assert_eq!(best.smallest.unwrap().indexer, "D");
+
+    // Only failures
+    let best = best_combinations(&results[1..2]);
+    assert!(best.fastest.is_none());
+    assert!(best.smallest.is_none());
+}
+
+#[test]
+fn distance_metrics() {
+    let a = LatLng(0.0, 0.0);
+    let b = LatLng(3.0, 4.0);
+    assert_eq!(DistanceMetric::Euclidean.distance(&a, &b), 5.0);
+    assert_eq!(DistanceMetric::Manhattan.distance(&a, &b), 7.0);
+
+    // One degree on the equator
+    let b = LatLng(0.0, 1.0);
+    let expected = 6373.0 * 1.0_f64.to_radians();
+    assert!((DistanceMetric::Haversine.distance(&a, &b) - expected).abs() < 1e-9);
+    assert!((DistanceMetric::Equirectangular.distance(&a, &b) - expected).abs() < 1e-9);
+
+    // Equirectangular approximation is accurate for short distances
+    let a = LatLng(41.0153, 29.0800);
+    let b = LatLng(41.0255, 29.0923);
+    let haversine = DistanceMetric::Haversine.distance(&a, &b);
+    let equirectangular = DistanceMetric::Equirectangular.distance(&a, &b);
+    assert!((haversine - equirectangular).abs() / haversine < 1e-4);
+    assert_eq!(haversine, a.distance_to(&b));
+
+    let time_func: TimeFunc =
+        serde_json::from_str(r#"{"type": "DirectDistance", "metric": "Manhattan"}"#).unwrap();
+    let travel_times = time_func.get_travel_times(&vec![LatLng(0.0, 0.0), LatLng(1.5, 2.0)]);
+    assert_eq!(travel_times, ndarray::arr2(&[[0, 4], [4, 0]]));
+    assert_eq!(
+        time_func.get_distance(&LatLng(0.0, 0.0), &LatLng(1.5, 2.0)),
+        4
+    );
+
+    // Haversine is the default
+    let time_func: TimeFunc = serde_json::from_str(r#"{"type": "DirectDistance"}"#).unwrap();
+    assert_eq!(time_func, TimeFunc::default());
+}
+
+#[test]
+fn constant_travel_times() {
+    let locations = vec![LatLng(41.0, 29.0), LatLng(41.5, 29.5), LatLng(40.75, 28.5)];
+    let travel_times = TimeFunc::Constant { constant: 5 }.get_travel_times(&locations);
+    assert_eq!(
+        travel_times,
+        ndarray::arr2(&[[0, 5, 5], [5, 0, 5], [5, 5, 0]])
+    );
+}
+
+#[test]
+fn distance_radius() {
+    let a = LatLng(41.0153, 29.0800);
+    let b = LatLng(41.0255, 29.0923);
+    assert_eq!(
+        a.distance_to_with_radius(&b, EARTH_RADIUS),
+        a.distance_to(&b)
+    );
+    // Distances are proportional to the radius.
+    let miles = a.distance_to_with_radius(&b, 3959.0);
+    assert!((miles / a.distance_to(&b) - 3959.0 / EARTH_RADIUS).abs() < 1e-12);
+    for metric in [DistanceMetric::Haversine, DistanceMetric::Equirectangular] {
+        let half = metric.distance_with_radius(&a, &b, EARTH_RADIUS / 2.0);
+        assert!((2.0 * half - metric.distance(&a, &b)).abs() < 1e-12);
+    }
+    // Planar metrics ignore the radius.
+    assert_eq!(
+        DistanceMetric::Euclidean.distance_with_radius(&a, &b, 1.0),
+        DistanceMetric::Euclidean.distance(&a, &b)
+    );
+
+    // One degree on the equator is 6373 * pi / 180 = 111.2 km, but 1 * pi / 180 on a unit sphere.
+    let locations = vec![LatLng(0.0, 0.0), LatLng(0.0, 1.0)];
+    let time_func: TimeFunc = serde_json::from_str(r#"{"type": "DirectDistance"}"#).unwrap();
+    assert_eq!(time_func.get_travel_times(&locations)[(0, 1)], 112);
+    let time_func: TimeFunc =
+        serde_json::from_str(r#"{"type": "DirectDistance", "radius": 1.0, "multiplier": 1000.0}"#)
+            .unwrap();
+    assert_eq!(time_func.get_travel_times(&locations)[(0, 1)], 18);
+    assert_eq!(time_func.get_distance(&locations[0], &locations[1]), 18);
+}
+
+#[test]
+fn solution_json_round_trip() {
+    let graph = get_two_bus_graph();
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let config = teams::Config::default();
+
+    // Serialize -> deserialize -> serialize must be stable.
+    fn check(solution: GenericTeamSolution) -> GenericTeamSolution {
+        let json = serde_json::to_string(&solution).unwrap();
+        let deserialized: GenericTeamSolution = serde_json::from_str(&json).unwrap();
+        assert_eq!(json, serde_json::to_string(&deserialized).unwrap());
+        deserialized
+    }
+
+    let mut solution = teams::solve_custom_timed(
+        &graph,
+        initial_teams.clone(),
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
+    )
+    .unwrap()
+    .into_io(&graph);
+    solution.total_time = 1.5;
+    solution.generation_time = 0.5;
+    let deserialized = check(GenericTeamSolution::Timed(solution.clone()));
+    assert_eq!(deserialized, GenericTeamSolution::Timed(solution));
+
+    let mut solution = teams::solve_naive(&graph, initial_teams, &config)
+        .unwrap()
+        .into_io(&graph);
+    solution.total_time = 1.5;
+    solution.generation_time = 0.5;
+    let deserialized = check(GenericTeamSolution::Regular(solution.clone()));
+    assert_eq!(deserialized, GenericTeamSolution::Regular(solution.clone()));
+
+    // A timed solution must stay timed even if all of its transitions take 1 unit of time.
+    let json = serde_json::to_string(&solution).unwrap().replacen(
+        r#""timed":false"#,
+        r#""timed":true"#,
+        1,
+    );
+    let GenericTeamSolution::Timed(timed) = serde_json::from_str(&json).unwrap() else {
+        panic!("Timed solution is deserialized as a regular one");
+    };
+    assert_eq!(
+        timed.transitions,
+        to_timed_transitions(&solution.transitions)
+    );
+    assert!(serde_json::from_str::<TeamSolution<RegularTransition>>(&json).is_err());
+}
+
+#[test]
+fn rounded_solution_json() {
+    assert_eq!(round_significant(0.1 + 0.2, 3), 0.3);
+    assert_eq!(round_significant(123456.0, 2), 120000.0);
+    assert_eq!(round_significant(0.000123456, 3), 0.000123);
+    assert_eq!(round_significant(0.0, 3), 0.0);
+
+    let graph = teams::Graph {
+        pfs: ndarray::arr1(&[0.3, 0.7 / 3.0]),
+        ..get_two_bus_graph()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let solution = GenericTeamSolution::Regular(
+        teams::solve_naive(&graph, initial_teams, &teams::Config::default())
+            .unwrap()
+            .into_io(&graph),
+    );
+    let digits = 3;
+    let json = serde_json::to_string(&solution.rounded(digits)).unwrap();
+    let rounded: GenericTeamSolution = serde_json::from_str(&json).unwrap();
+    let (GenericTeamSolution::Regular(solution), GenericTeamSolution::Regular(rounded)) =
+        (solution, rounded)
+    else {
+        panic!("Solution type changed");
+    };
+    assert_eq!(rounded.values, solution.values);
+    assert_eq!(rounded.policy, solution.policy);
+    let tolerance = 5.0 * 10f64.powi(-(digits as i32));
+    for (state, rounded_state) in solution.transitions.iter().zip(rounded.transitions.iter()) {
+        assert_eq!(state.len(), rounded_state.len());
+        for (action, rounded_action) in state.iter().zip(rounded_state.iter()) {
+            assert_eq!(action.len(), rounded_action.len());
+            let mut sum = 0.0;
+            for (t, r) in action.iter().zip(rounded_action.iter()) {
+                assert_eq!(t.successor, r.successor);
+                assert_eq!(t.cost, r.cost);
+                let (p, rp) = (t.p as f64, r.p as f64);
+                assert!(
+                    (p - rp).abs() <= tolerance * p + 1e-7,
+                    "{p} rounded to {rp}"
+                );
+                sum += rp;
+            }
+            assert!(
+                (sum - 1.0).abs() <= tolerance,
+                "Probabilities add up to {sum}"
+            );
+        }
+    }
+
+    // The pretty-printed JSON contains the same rounded values.
+    let solution = GenericTeamSolution::Regular(solution);
+    let pretty = solution.rounded(digits);
+    let value: serde_json::Value = serde_json::from_str(&pretty.to_pretty_json().unwrap()).unwrap();
+    assert_eq!(
+        value,
+        serde_json::from_str::<serde_json::Value>(&json).unwrap()
+    );
+}
+
+#[test]
+fn solution_without_terminal_loops() {
+    let graph = get_two_bus_graph();
+    // The costs of the terminal states depend on the weights of the buses.
+    let weighted_graph = teams::Graph {
+        weights: Some(vec![3 as Cost, 5 as Cost]),
+        ..graph.clone()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    for graph in [graph, weighted_graph] {
+        let solution = teams::solve_naive(&graph, initial_teams.clone(), &teams::Config::default())
+            .unwrap()
+            .into_io(&graph);
+        let terminals: Vec<usize> = (0..solution.transitions.len())
+            .filter(|&i| is_terminal_loop(i, &solution.transitions[i]))
+            .collect();
+        assert!(!terminals.is_empty());
+
+        let json = serde_json::to_string(&solution.without_terminal_loops()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let empty_states = |value: &serde_json::Value| -> Vec<usize> {
+            value["transitions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .enumerate()
+                .filter(|(_, state)| state.as_array().unwrap().is_empty())
+                .map(|(i, _)| i)
+                .collect()
+        };
+        assert_eq!(empty_states(&value), terminals);
+        // Terminal loops are restored with their costs when deserialized.
+        let deserialized: TeamSolution<RegularTransition> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.transitions, solution.transitions);
+        assert_eq!(
+            serde_json::to_string(&deserialized).unwrap(),
+            serde_json::to_string(&solution).unwrap()
+        );
+        // The in-memory form is not affected, so value iteration gives the same results.
+        assert_eq!(
+            NaivePolicySynthesizer::synthesize_policy(&deserialized.transitions, solution.horizon),
+            NaivePolicySynthesizer::synthesize_policy(&solution.transitions, solution.horizon),
+        );
+
+        // Options can be combined.
+        let generic = GenericTeamSolution::Regular(solution);
+        let rounded: serde_json::Value =
+            serde_json::to_value(generic.rounded(3).without_terminal_loops()).unwrap();
+        assert_eq!(empty_states(&rounded), terminals);
+    }
+}
+
+#[test]
+fn pretty_solution_json() {
+    let graph = get_two_bus_graph();
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let solution = GenericTeamSolution::Regular(
+        teams::solve_naive(&graph, initial_teams, &teams::Config::default())
+            .unwrap()
+            .into_io(&graph),
+    );
+    let pretty = solution.to_pretty_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+    assert_eq!(value, serde_json::to_value(&solution).unwrap());
+
+    // Each field is on its own line and each entry of a non-empty array field is on its own line.
+    let fields = value.as_object().unwrap();
+    let expected_lines: usize = 2 + fields
+        .values()
+        .map(|v| match v.as_array() {
+            Some(a) if !a.is_empty() => a.len() + 2,
+            _ => 1,
+        })
+        .sum::<usize>();
+    assert_eq!(pretty.lines().count(), expected_lines);
+    let keys: Vec<&str> = pretty
+        .lines()
+        .filter_map(|line| line.strip_prefix("  \"")?.split('"').next())
+        .collect();
+    assert_eq!(
+        keys[..4],
+        ["timed", "totalTime", "generationTime", "maxMemory"]
+    );
+    assert_eq!(keys.len(), fields.len());
+}
+
+#[test]
+fn solution_replay() {
+    let graph = get_two_bus_graph();
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let solution = teams::solve_naive(&graph, initial_teams, &teams::Config::default())
+        .unwrap()
+        .into_io(&graph);
+    let mut replay = SolutionReplay::new(solution.clone());
+    assert_eq!(replay.current_state(), solution.get_state(0));
+
+    let mut steps = 0;
+    while !replay.is_terminal() {
+        let action = replay.optimal_action().unwrap();
+        assert_eq!(
+            action.index,
+            solution.policy[replay.current_index()] as usize
+        );
+        let outcomes = replay.outcomes();
+        let total_p: f64 = outcomes.iter().map(|(p, _)| *p as f64).sum();
+        assert!((total_p - 1.0).abs() < 1e-6);
+        // Always pick the last outcome.
+        let (_, expected) = outcomes.last().unwrap().clone();
+        let state = replay.apply(outcomes.len() - 1).unwrap();
+        assert_eq!(state, expected);
+        steps += 1;
+        assert!(steps <= solution.horizon);
+    }
+    assert!(steps > 0);
+    assert!(replay.optimal_action().is_none());
+    assert!(replay.outcomes().is_empty());
+    assert!(replay.apply(0).is_err());
+
+    // Jump back to the initial state by its contents.
+    let initial = solution.get_state(0);
+    replay.set_state(&initial).unwrap();
+    assert_eq!(replay.current_index(), 0);
+    assert!(replay.apply(usize::MAX).is_err());
+}
+
+#[test]
+fn dms_error_variants() {
+    let mut path: std::path::PathBuf = std::env::temp_dir();
+    path.push("dmslib-dms-error-missing.json");
+    let _ = std::fs::remove_file(&path);
+    let result = fs::read_problems_from_file(&path);
+    assert!(matches!(result, Err(DmsError::Io(_))));
+
+    let mut path: std::path::PathBuf = std::env::temp_dir();
+    path.push("dmslib-dms-error-invalid.json");
+    std::fs::write(&path, "{ not json").unwrap();
+    match TeamProblem::read_from_file(&path) {
+        Err(DmsError::Parse { path: p, .. }) => assert_eq!(p, path),
+        other => panic!("Expected a parse error, got {other:?}"),
+    }
+    std::fs::write(&path, r#"{"tasks": 1}"#).unwrap();
+    let result = read_experiment_from_file(&path);
+    assert!(matches!(result, Err(DmsError::Parse { .. })));
+    std::fs::remove_file(&path).unwrap();
+
+    let problem = duplicate_branch_problem(false);
+    let result = problem.graph.find_node("missing");
+    assert!(matches!(result, Err(DmsError::Validation(_))));
+
+    fn solve_with_dms_error(problem: TeamProblem) -> Result<usize, DmsError> {
+        let solution = problem.solve_naive()?;
+        Ok(solution.horizon)
+    }
+    let mut problem = duplicate_branch_problem(false);
+    problem.teams[0].node = Some("missing".to_string());
+    problem.teams[0].index = None;
+    let result = solve_with_dms_error(problem);
+    assert!(matches!(
+        result,
+        Err(DmsError::Solve(SolveFailure::BadInput(_)))
+    ));
+    let error = DmsError::from(SolveFailure::BadInput("test".to_string()));
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+#[test]
+fn explain_state() {
+    let solution = duplicate_branch_problem(false).solve_naive().unwrap();
+    for index in 0..solution.transitions.len() {
+        let explanation = solution.explain_state(index).unwrap();
+        assert_eq!(explanation.index, index);
+        assert_eq!(explanation.optimal_action, solution.policy[index] as usize);
+        let values: Vec<Value> = explanation.actions.iter().map(|a| a.value).collect();
+        assert_eq!(values, solution.values[index]);
+        for (action, transitions) in explanation
+            .actions
+            .iter()
+            .zip(solution.transitions[index].iter())
+        {
+            assert_eq!(action.transitions.len(), transitions.len());
+            let total_p: f64 = action.transitions.iter().map(|t| t.1 as f64).sum();
+            assert!((total_p - 1.0).abs() < 1e-6);
+        }
+    }
+    assert!(!solution.explain_state(0).unwrap().actions.is_empty());
+    assert!(solution.explain_state(solution.transitions.len()).is_err());
+}
+
+#[test]
+fn name_template() {
+    let values = [
+        ("problem", "Small Grid".to_string()),
+        ("indexer", "NaiveStateIndexer".to_string()),
+        ("timestamp", "1700000000".to_string()),
+        ("slash", "a/b".to_string()),
+    ];
+    let name = fs::expand_name_template("{problem}/{indexer}-{timestamp}", &values).unwrap();
+    assert_eq!(name, "Small Grid/NaiveStateIndexer-1700000000");
+    // Values cannot introduce subdirectories.
+    let name = fs::expand_name_template("{{{slash}}}", &values).unwrap();
+    assert_eq!(name, "{ab}");
+    for template in ["{unknown}", "{problem", "problem}"] {
+        let result = fs::expand_name_template(template, &values);
+        assert!(matches!(result, Err(DmsError::Validation(_))), "{template}");
+    }
+
+    let mut dir: std::path::PathBuf = std::env::temp_dir();
+    dir.push("dmslib-name-template");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join(name).with_extension("bin");
+    assert_eq!(fs::unique_path(path.clone()), path);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "").unwrap();
+    let second = fs::unique_path(path.clone());
+    assert_eq!(second, dir.join("{ab}-1.bin"));
+    std::fs::write(&second, "").unwrap();
+    assert_eq!(fs::unique_path(path), dir.join("{ab}-2.bin"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn expected_moves() {
+    let graph = teams::Graph {
+        travel_times: ndarray::arr2(&[[0, 1, 2], [1, 0, 1], [2, 1, 0]]),
+        pfs: ndarray::arr1(&[0.5, 0.5]),
+        ..get_two_bus_graph()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let solution = teams::solve_naive(&graph, initial_teams, &teams::Config::default())
+        .unwrap()
+        .into_io(&graph);
+    // The team always moves to bus 0, and then to bus 1 only if bus 0 is energized.
+    assert!((solution.expected_moves().unwrap() - 1.5).abs() < 1e-6);
+
+    // A policy that waits in the initial state never reaches a terminal state.
+    let mut solution = solution;
+    solution.transitions[0].push(vec![RegularTransition::wait_transition(0, 1 as Cost, 1)]);
+    solution.policy[0] = (solution.transitions[0].len() - 1) as ActionIndex;
+    assert!(!solution.is_terminal_state(0));
+    assert!(matches!(
+        solution.expected_moves(),
+        Err(SolveFailure::BadInput(_))
+    ));
+    // Simulation stops at the horizon instead.
+    let result = solution.simulate_all();
+    assert_eq!(result.simulated_transitions, solution.horizon);
+    assert!(result.energization_p.iter().all(|&p| p == 0.0));
+}
+
+#[test]
+fn team_utilization() {
+    let mut problem = get_paper_example_problem(vec![team_at(0)]);
+    // A single team is always dispatched to an energizable bus until the restoration ends, so
+    // it's never idle. This also holds when repairs take time.
+    for repair_times in [None, Some(vec![2; problem.graph.nodes.len()])] {
+        problem.repair_times = repair_times;
+        let solution = problem
+            .clone()
+            .solve_custom_timed(
+                "NaiveStateIndexer",
+                "NaiveActions",
+                "TimedActionApplier<TimeUntilEnergization>",
+                "NaiveTimedPolicySynthesizer",
+            )
+            .unwrap();
+        let (prepared, _) = problem.clone().prepare().unwrap();
+        let utilization = solution.team_utilization(&prepared.graph).unwrap();
+        assert_eq!(utilization.len(), 1);
+        assert!((utilization[0] - 1.0).abs() < 1e-6, "{utilization:?}");
+    }
+
+    problem.teams.push(team_at(0));
+    let mut solution = problem.clone().solve_naive().unwrap();
+    let (prepared, _) = problem.prepare().unwrap();
+    let utilization = solution.team_utilization(&prepared.graph).unwrap();
+    assert_eq!(utilization.len(), 2);
+    assert!(utilization.iter().all(|u| (0.0..=1.0 + 1e-6).contains(u)));
+
+    // A policy that waits in the initial state never reaches a terminal state.
+    solution.transitions[0].push(vec![RegularTransition::wait_transition(0, 1 as Cost, 1)]);
+    solution.policy[0] = (solution.transitions[0].len() - 1) as ActionIndex;
+    assert!(matches!(
+        solution.team_utilization(&prepared.graph),
+        Err(SolveFailure::BadInput(_))
+    ));
+}
+
+#[test]
+fn most_likely_trajectory() {
+    // The team starts on an energizable bus, so the initial state has zero-timed transitions.
+    let problem = get_paper_example_problem(vec![team_at(0)]);
+    let solution = problem
+        .solve_custom_timed(
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap();
+    let trajectory = solution.most_likely_trajectory();
+    assert_eq!(trajectory[0], (0, solution.policy[0]));
+    for window in trajectory.windows(2) {
+        let ((index, action), (next, next_action)) = (window[0], window[1]);
+        assert_eq!(action, solution.policy[index]);
+        assert_eq!(next_action, solution.policy[next]);
+        let transitions = &solution.transitions[index][action as usize];
+        let max_p = transitions
+            .iter()
+            .map(|t| t.p)
+            .fold(Probability::MIN, Probability::max);
+        assert!(transitions
+            .iter()
+            .any(|t| t.successor as usize == next && t.p == max_p));
+    }
+    let &(last, action) = trajectory.last().unwrap();
+    let transitions = &solution.transitions[last][action as usize];
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].successor as usize, last);
+    assert!(trajectory.len() > 2);
+
+    // A policy that waits in the initial state is followed until the horizon.
+    let mut solution = solution;
+    solution.transitions[0].push(vec![TimedTransition::wait_transition(0, 1 as Cost, 1)]);
+    let wait = (solution.transitions[0].len() - 1) as ActionIndex;
+    solution.policy[0] = wait;
+    let trajectory = solution.most_likely_trajectory();
+    assert_eq!(trajectory.len(), solution.horizon + 1);
+    assert!(trajectory.iter().all(|&step| step == (0, wait)));
+}
+
+#[test]
+fn trajectory_frames() {
+    let problem = get_paper_example_problem(vec![team_at(0)]);
+    let solution = problem
+        .solve_custom_timed(
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap();
+    let trajectory = solution.most_likely_trajectory();
+    let frames = solution.trajectory_frames();
+    assert_eq!(frames.len(), trajectory.len());
+    for (frame, &(index, _)) in frames.iter().zip(trajectory.iter()) {
+        let state = solution.get_state(index);
+        assert_eq!(frame.buses, state.buses);
+        assert_eq!(frame.teams, state.teams);
+    }
+    assert_eq!(frames[0].cumulative_time, 0);
+    assert!(frames
+        .windows(2)
+        .all(|w| w[0].cumulative_time <= w[1].cumulative_time));
+    let last = frames.last().unwrap();
+    assert!(last.cumulative_time > 0);
+    assert!(last.cumulative_time <= solution.horizon);
+
+    let json = serde_json::to_value(&frames).unwrap();
+    assert!(json[0].get("cumulativeTime").is_some());
+
+    // A policy that waits in the initial state ends at the horizon, even if the last wait
+    // exceeds it.
+    let mut solution = solution;
+    let wait_time = (solution.horizon - 1) as Time;
+    solution.transitions[0].push(vec![TimedTransition::wait_transition(
+        0, 1 as Cost, wait_time,
+    )]);
+    solution.policy[0] = (solution.transitions[0].len() - 1) as ActionIndex;
+    let times: Vec<usize> = solution
+        .trajectory_frames()
+        .iter()
+        .map(|frame| frame.cumulative_time)
+        .collect();
+    assert_eq!(times, [0, solution.horizon - 1, solution.horizon]);
+}
+
+#[test]
+fn export_prism() {
+    let graph = teams::Graph {
+        travel_times: ndarray::arr2(&[[0, 1, 2], [1, 0, 1], [2, 1, 0]]),
+        pfs: ndarray::arr1(&[0.5, 0.5]),
+        ..get_two_bus_graph()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let solution = teams::solve_naive(&graph, initial_teams, &teams::Config::default())
+        .unwrap()
+        .into_io(&graph);
+
+    let mut dir: std::path::PathBuf = std::env::temp_dir();
+    dir.push("dmslib-prism-export");
+    let _ = std::fs::remove_dir_all(&dir);
+    solution.export_prism(&dir).unwrap();
+    let read_lines = |name: &str| -> Vec<String> {
+        std::fs::read_to_string(dir.join(name))
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    };
+
+    let state_count = solution.transitions.len();
+    let choice_count: usize = solution.transitions.iter().map(Vec::len).sum();
+    let transition_count = get_transition_count(&solution.transitions);
+
+    let sta = read_lines("model.sta");
+    assert_eq!(sta[0], "(b0,b1,t0,t0_time)");
+    assert_eq!(sta.len(), state_count + 1);
+    assert_eq!(sta[1], "0:(0,0,2,0)");
+
+    let tra = read_lines("model.tra");
+    assert_eq!(
+        tra[0],
+        format!("{state_count} {choice_count} {transition_count}")
+    );
+    assert_eq!(tra.len(), transition_count + 1);
+    let total_p: f64 = tra[1..]
+        .iter()
+        .map(|line| line.split(' ').nth(3).unwrap().parse::<f64>().unwrap())
+        .sum();
+    assert!((total_p - choice_count as f64).abs() < 1e-6);
+
+    let trew = read_lines("model.trew");
+    let reward_count: usize = trew[0].split(' ').nth(2).unwrap().parse().unwrap();
+    assert_eq!(trew.len(), reward_count + 1);
+
+    let lab = read_lines("model.lab");
+    assert_eq!(lab[0], "0=\"init\" 1=\"terminal\"");
+    assert_eq!(lab[1], "0: 0");
+    assert!(lab.len() > 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn start_distribution() {
+    let problem = TeamProblem {
+        start_distribution: Some(vec![
+            WeightedStart {
+                weight: 1.0,
+                teams: vec![team_at(0)],
+            },
+            WeightedStart {
+                weight: 3.0,
+                teams: vec![team_at(5)],
+            },
+        ]),
+        ..get_paper_example_problem(vec![Team {
+            index: None,
+            latlng: None,
+            name: Some("Crew".to_string()),
+            node: None,
+        }])
+    };
+    let (problem, config) = problem.prepare().unwrap();
+    // The most likely configuration is used for the initial teams.
+    assert_eq!(problem.initial_teams, vec![TeamState { time: 0, index: 5 }]);
+    let starts = problem.start_distribution.unwrap();
+    assert_eq!(starts.len(), 2);
+    assert!((starts[0].0 - 0.25).abs() < 1e-9);
+    assert!((starts[1].0 - 0.75).abs() < 1e-9);
+
+    let settings = (
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
+    );
+    let solution = teams::solve_start_distribution(
+        &problem.graph,
+        &starts,
+        &config,
+        settings.0,
+        settings.1,
+        settings.2,
+        settings.3,
+    )
+    .unwrap();
+    let values: Vec<f64> = starts
+        .iter()
+        .map(|(_, teams)| {
+            teams::solve_custom(
+                &problem.graph,
+                teams.clone(),
+                &config,
+                settings.0,
+                settings.1,
+                settings.2,
+                settings.3,
+            )
+            .unwrap()
+            .get_benchmark_result()
+            .value as f64
+        })
+        .collect();
+    assert_ne!(values[0], values[1]);
+    let value = solution.get_min_value();
+    assert!(values[0].min(values[1]) < value && value < values[0].max(values[1]));
+    assert!((value - (0.25 * values[0] + 0.75 * values[1])).abs() < 1e-4);
+}
+
+#[test]
+fn csv_graph() {
+    let json_graph = get_paper_example_graph();
+    let buses = "\
+id,lat,lng,pf,name
+K1,41.015300110394236,29.086003303527836,0.125,Kadikoy #1
+B1,41.01983331919438,29.081025123596195,0.5,Bulgurlu #1
+B3,41.01996283456085,29.07467365264893,0.25,Bulgurlu #3
+DP,41.014620114274955,29.078450202941895,0.5,Dostluk Park
+KC1,41.013357189848705,29.071283340454105,0.5,Küçükçamlıca #1
+KC3,41.01225622702989,29.065575599670414,0.5,\"Küçükçamlıca #3\"
+C1,41.00998891796685,29.08235549926758,0.125,Cumhuriyet #1
+X,41.008305431621075,29.075489044189453,0.125,
+";
+    let branches = "\
+from,to
+K1,DP
+DP,KC1
+KC1,KC3
+K1,B1
+B1,B3
+K1,C1
+X,C1
+";
+    let sources = "\
+ID,Lat,Lng,Bus
+S,41.01559155019519,29.092054367065433,K1
+";
+    let mut graph = csv::parse_graph(buses, branches, sources).unwrap();
+    assert_eq!(graph.name, "");
+    graph.name = json_graph.name.clone();
+    // Decimal coordinates may not round-trip exactly, compare them with a tolerance.
+    let assert_latlng_eq = |a: &LatLng, b: &LatLng| {
+        assert!(
+            (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9,
+            "{a:?} != {b:?}"
+        );
+    };
+    assert_eq!(graph.nodes.len(), json_graph.nodes.len());
+    for (node, json_node) in graph.nodes.iter_mut().zip(&json_graph.nodes) {
+        assert_latlng_eq(&node.latlng, &json_node.latlng);
+        assert!((node.pf - json_node.pf).abs() < 1e-9);
+        node.latlng = json_node.latlng.clone();
+        node.pf = json_node.pf;
+    }
+    assert_eq!(graph.resources.len(), json_graph.resources.len());
+    for (resource, json_resource) in graph.resources.iter_mut().zip(&json_graph.resources) {
+        assert_latlng_eq(&resource.latlng, &json_resource.latlng);
+        resource.latlng = json_resource.latlng.clone();
+    }
+    assert_eq!(graph, json_graph);
+
+    // A source with multiple buses
+    let multi_sources = "\
+id,lat,lng,bus,type
+S,41.01559155019519,29.092054367065433,K1,substation
+S,,,X
+";
+    let graph = csv::parse_graph(buses, branches, multi_sources).unwrap();
+    assert_eq!(graph.resources.len(), 1);
+    assert_eq!(graph.resources[0].kind.as_deref(), Some("substation"));
+    assert_eq!(
+        graph.external,
+        vec![
+            ExtBranch { node: 0, source: 0 },
+            ExtBranch { node: 7, source: 0 }
+        ]
+    );
+
+    // Errors point to the offending rows.
+    let error = csv::parse_graph(buses, "from,to\nK1,DP\nK1,Y\n", sources).unwrap_err();
+    assert_eq!(error, "branches line 3: unknown bus \"Y\"");
+    let error = csv::parse_graph("id,lat,lng,pf\nA,1,2,high\n", branches, sources).unwrap_err();
+    assert_eq!(error, "buses line 2: invalid pf \"high\"");
+    let error = csv::parse_graph("id,lat,lng\n", branches, sources).unwrap_err();
+    assert_eq!(error, "buses: missing column \"pf\"");
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn solve_async() {
+    let problem = TeamProblem {
+        horizon: Some(30).into(),
+        ..get_paper_example_problem(vec![Team {
+            name: Some("Crew".to_string()),
+            ..team_at(0)
+        }])
+    };
+    let optimizations = OptimizationInfo {
+        indexer: "NaiveStateIndexer".to_string(),
+        actions: "NaiveActions".to_string(),
+        transitions: "NaiveActionApplier".to_string(),
+        synthesizer: None,
+    };
+    let solution = problem.solve_async(optimizations).await.unwrap();
+    let GenericTeamSolution::Regular(solution) = solution else {
+        panic!("Expected a regular solution");
+    };
+    assert_eq!(solution.get_benchmark_result().value, 137.283203125);
+    assert_eq!(solution.team_names, vec![Some("Crew".to_string())]);
+}
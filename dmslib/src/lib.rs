@@ -10,9 +10,25 @@ pub mod teams;
 pub mod types;
 pub mod utils;
 
+#[cfg(feature = "track-memory")]
 #[global_allocator]
 static ALLOCATOR: cap::Cap<std::alloc::System> = cap::Cap::new(std::alloc::System, usize::MAX);
 
+/// Get the number of bytes currently allocated by the global allocator.
+///
+/// Always 0 if the `track-memory` feature is disabled.
+#[inline]
+pub fn allocated_memory() -> usize {
+    #[cfg(feature = "track-memory")]
+    {
+        ALLOCATOR.allocated()
+    }
+    #[cfg(not(feature = "track-memory"))]
+    {
+        0
+    }
+}
+
 /// Path where graphs are stored.
 /// Must end with `/`, or all subdirectory names will start with `/`.
 pub const GRAPHS_PATH: &str = "../graphs/";
@@ -25,7 +41,21 @@ pub const EXPERIMENTS_PATH: &str = "../experiments/";
 #[serde(tag = "type", content = "content")] // content will be used for BadInput(String)
 pub enum SolveFailure {
     BadInput(String),
-    OutOfMemory { used: usize, limit: usize },
+    OutOfMemory {
+        used: usize,
+        limit: usize,
+    },
+    /// The number of indexed states exceeds [`teams::Config::max_states`].
+    TooManyStates {
+        count: usize,
+        limit: usize,
+    },
+    /// The number of actions in a state exceeds [`teams::Config::max_actions_per_state`].
+    TooManyActions {
+        limit: usize,
+    },
+    /// Writing the debug dump failed, see [`teams::Config::debug_dump`].
+    Io(String),
 }
 
 impl std::error::Error for SolveFailure {}
@@ -37,6 +67,83 @@ impl std::fmt::Display for SolveFailure {
             SolveFailure::OutOfMemory { used, limit } => {
                 write!(f, "Out of memory! Used {} of {}.", used, limit)
             }
+            SolveFailure::TooManyStates { count, limit } => {
+                write!(
+                    f,
+                    "State limit exceeded ({count} states reached, limit is {limit})"
+                )
+            }
+            SolveFailure::TooManyActions { limit } => {
+                write!(
+                    f,
+                    "Number of actions in a state exceeds the limit ({limit})"
+                )
+            }
+            SolveFailure::Io(reason) => write!(f, "I/O error: {}", reason),
+        }
+    }
+}
+
+/// Represents the reasons why reading, parsing or solving a problem might fail.
+///
+/// Unlike [`SolveFailure`], this covers the file system and parsing errors so that the callers
+/// can match on the kind of failure.
+#[derive(Debug)]
+pub enum DmsError {
+    /// Reading or writing a file failed.
+    Io(std::io::Error),
+    /// The contents of the file at the given path cannot be parsed.
+    Parse {
+        path: std::path::PathBuf,
+        message: String,
+    },
+    /// The input is well-formed but invalid, e.g., refers to a node that doesn't exist.
+    Validation(String),
+    /// Solution attempt failed.
+    Solve(SolveFailure),
+}
+
+impl DmsError {
+    /// Construct a [`DmsError::Parse`] for the file at the given path.
+    pub fn parse<P: AsRef<std::path::Path>, M: std::fmt::Display>(path: P, message: M) -> Self {
+        DmsError::Parse {
+            path: path.as_ref().to_path_buf(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::error::Error for DmsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DmsError::Io(e) => Some(e),
+            DmsError::Solve(e) => Some(e),
+            DmsError::Parse { .. } | DmsError::Validation(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DmsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DmsError::Io(e) => write!(f, "I/O error: {}", e),
+            DmsError::Parse { path, message } => {
+                write!(f, "Cannot parse {}: {}", path.display(), message)
+            }
+            DmsError::Validation(reason) => write!(f, "{}", reason),
+            DmsError::Solve(e) => write!(f, "{}", e),
         }
     }
 }
+
+impl From<std::io::Error> for DmsError {
+    fn from(value: std::io::Error) -> Self {
+        DmsError::Io(value)
+    }
+}
+
+impl From<SolveFailure> for DmsError {
+    fn from(value: SolveFailure) -> Self {
+        DmsError::Solve(value)
+    }
+}
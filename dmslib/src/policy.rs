@@ -1,16 +1,31 @@
 //! Contains methods and utilities for policy synthesis.
 use crate::types::*;
+use crate::SolveFailure;
 
 use ndarray::Array1;
 use serde::ser::SerializeSeq;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+mod columnar;
+pub use columnar::*;
 
 /// Marker trait for all structs that represent state transitions.
 pub trait Transition: Serialize {
+    /// True if the transitions take arbitrary times, false if each one takes 1 unit of time.
+    const TIMED: bool;
+
     /// Generate a self-transition for a terminal state.
     fn terminal_transition(index: StateIndex, cost: Cost) -> Self;
     /// Generate a transition with given cost, probability and time = 1.
     fn time1_transition(index: StateIndex, cost: Cost, p: Probability) -> Self;
+    /// Generate a self-transition for a global wait action that advances time by given amount.
+    ///
+    /// The time is ignored for transition types that don't support it.
+    fn wait_transition(index: StateIndex, cost: Cost, time: Time) -> Self;
+    /// Generate a transition with given cost, probability and time.
+    ///
+    /// The time is ignored for transition types that don't support it.
+    fn from_parts(index: StateIndex, cost: Cost, p: Probability, time: Time) -> Self;
 
     /// Set the index of successor state.
     fn set_successor(&mut self, index: StateIndex);
@@ -44,6 +59,8 @@ enum DfsState<T> {
 }
 
 impl Transition for RegularTransition {
+    const TIMED: bool = false;
+
     #[inline]
     fn terminal_transition(index: StateIndex, cost: Cost) -> Self {
         Self {
@@ -62,6 +79,16 @@ impl Transition for RegularTransition {
         }
     }
 
+    #[inline]
+    fn wait_transition(index: StateIndex, cost: Cost, _time: Time) -> Self {
+        Self::terminal_transition(index, cost)
+    }
+
+    #[inline]
+    fn from_parts(index: StateIndex, cost: Cost, p: Probability, _time: Time) -> Self {
+        Self::time1_transition(index, cost, p)
+    }
+
     #[inline]
     fn set_successor(&mut self, index: StateIndex) {
         self.successor = index;
@@ -137,6 +164,18 @@ impl Serialize for RegularTransition {
     }
 }
 
+impl<'de> Deserialize<'de> for RegularTransition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Time is always 1 for regular transitions.
+        let (successor, p, cost, _): (StateIndex, Probability, Cost, Time) =
+            Deserialize::deserialize(deserializer)?;
+        Ok(RegularTransition { successor, p, cost })
+    }
+}
+
 /// A regular MDP transition with probability and cost.
 #[derive(Clone, PartialEq, Debug)]
 pub struct TimedTransition {
@@ -152,6 +191,8 @@ pub struct TimedTransition {
 }
 
 impl Transition for TimedTransition {
+    const TIMED: bool = true;
+
     #[inline]
     fn terminal_transition(index: StateIndex, cost: Cost) -> Self {
         Self {
@@ -172,6 +213,21 @@ impl Transition for TimedTransition {
         }
     }
 
+    #[inline]
+    fn wait_transition(index: StateIndex, cost: Cost, time: Time) -> Self {
+        Self::from_parts(index, cost, 1.0, time)
+    }
+
+    #[inline]
+    fn from_parts(index: StateIndex, cost: Cost, p: Probability, time: Time) -> Self {
+        Self {
+            successor: index,
+            p,
+            cost,
+            time,
+        }
+    }
+
     #[inline]
     fn set_successor(&mut self, index: StateIndex) {
         self.successor = index;
@@ -212,20 +268,84 @@ impl Serialize for TimedTransition {
     }
 }
 
+impl<'de> Deserialize<'de> for TimedTransition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (successor, p, cost, time): (StateIndex, Probability, Cost, Time) =
+            Deserialize::deserialize(deserializer)?;
+        Ok(TimedTransition {
+            successor,
+            p,
+            cost,
+            time,
+        })
+    }
+}
+
+/// Round the given number to the given number of significant digits.
+///
+/// The result is the closest float to the rounded decimal number, which is formatted without
+/// noise digits by serde_json (e.g., `0.3` instead of `0.30000000000000004`).
+pub fn round_significant(x: f64, digits: u32) -> f64 {
+    if x == 0.0 || !x.is_finite() {
+        return x;
+    }
+    let exponent = digits as i32 - 1 - x.abs().log10().floor() as i32;
+    // Powers of 10 with negative exponents are not exact, hence divide by the exact inverse.
+    if exponent >= 0 {
+        let scale = 10f64.powi(exponent);
+        (x * scale).round() / scale
+    } else {
+        let scale = 10f64.powi(-exponent);
+        (x / scale).round() * scale
+    }
+}
+
+/// Serializes a [`Transition`] in the same layout as its [`Serialize`] implementation with the
+/// probability and cost rounded to the given number of significant digits.
+///
+/// The relative error of each rounded number is at most `5e-digits`. Hence, the rounded
+/// probabilities of an action add up to 1 within `5e-digits`. Integer costs are not rounded.
+pub struct RoundedTransition<'a, T: Transition>(pub &'a T, pub u32);
+
+impl<'a, T: Transition> Serialize for RoundedTransition<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let RoundedTransition(t, digits) = self;
+        let p = round_significant(t.get_probability() as f64, *digits) as Probability;
+        #[cfg(not(feature = "minmem"))]
+        let cost = round_significant(t.get_cost() as f64, *digits) as Cost;
+        #[cfg(feature = "minmem")]
+        let cost = t.get_cost();
+        let mut seq = serializer.serialize_seq(Some(4))?;
+        seq.serialize_element(&t.get_successor())?;
+        seq.serialize_element(&p)?;
+        seq.serialize_element(&cost)?;
+        seq.serialize_element(&t.get_time())?;
+        seq.end()
+    }
+}
+
 /// Run depth-first search on the transition space.
-fn dfs<T: Transition>(transitions: &[Vec<Vec<T>>]) -> Vec<DfsState<usize>> {
+///
+/// Returns `None` if the MDP state graph is cyclic, ignoring self-transitions.
+fn dfs<T: Transition>(transitions: &[Vec<Vec<T>>]) -> Option<Vec<DfsState<usize>>> {
     let mut memoization = vec![DfsState::<usize>::New; transitions.len()];
 
     fn visit<T: Transition>(
         index: StateIndex,
         transitions: &[Vec<Vec<T>>],
         memoization: &mut [DfsState<usize>],
-    ) -> usize {
+    ) -> Option<usize> {
         let m = &mut memoization[index as usize];
         if let DfsState::Done(v) = m {
-            return *v;
+            return Some(*v);
         } else if *m == DfsState::Visiting {
-            panic!("MDP state graph is cyclic");
+            return None;
         }
         *m = DfsState::Visiting;
         let mut max_depth = 0;
@@ -236,17 +356,17 @@ fn dfs<T: Transition>(transitions: &[Vec<Vec<T>>]) -> Vec<DfsState<usize>> {
                 let depth: usize = if successor == index {
                     time
                 } else {
-                    visit(successor, transitions, memoization) + time
+                    visit(successor, transitions, memoization)? + time
                 };
                 max_depth = std::cmp::max(max_depth, depth);
             }
         }
         memoization[index as usize] = DfsState::Done(max_depth);
-        max_depth
+        Some(max_depth)
     }
-    visit(0, transitions, &mut memoization);
+    visit(0, transitions, &mut memoization)?;
 
-    memoization
+    Some(memoization)
 }
 
 /// Returns 1 plus the length of the longest path starting from each state to a
@@ -256,6 +376,7 @@ fn dfs<T: Transition>(transitions: &[Vec<Vec<T>>]) -> Vec<DfsState<usize>> {
 /// Panics in case of unreachable states.
 pub fn longest_path_lengths<T: Transition>(transitions: &[Vec<Vec<T>>]) -> Vec<usize> {
     dfs(transitions)
+        .expect("MDP state graph is cyclic")
         .into_iter()
         .map(|dfs_state| {
             if let DfsState::Done(depth) = dfs_state {
@@ -268,12 +389,179 @@ pub fn longest_path_lengths<T: Transition>(transitions: &[Vec<Vec<T>>]) -> Vec<u
 }
 
 /// Determine the optimization horizon from transition space.
+///
+/// Panics if the MDP state graph is cyclic, see [`try_determine_horizon`].
 pub fn determine_horizon<T: Transition>(transitions: &[Vec<Vec<T>>]) -> usize {
-    let memoization = dfs(transitions);
+    try_determine_horizon(transitions).expect("MDP state graph is cyclic")
+}
+
+/// Determine the optimization horizon from transition space like [`determine_horizon`].
+///
+/// The horizon is the length of the longest path from the initial state plus one time step for
+/// the terminal state, where the time step is the greatest common divisor of the transition
+/// times (see [`get_time_step`]). Hence, scaling all transition times scales the horizon too.
+///
+/// The time step is 1 for [`RegularTransition`]s and for timed problems whose times are not
+/// scaled, so their horizon is the same as the longest path length from the initial state (see
+/// [`longest_path_lengths`]). However, the horizon of a timed problem whose travel and repair
+/// times all share a common divisor, e.g., 10 after [`crate::io::TeamProblem::time_scale`], is
+/// longer by the time step minus 1.
+///
+/// Returns `None` if the MDP state graph is cyclic apart from self-transitions, e.g., when
+/// [`crate::teams::Config::enforce_progress`] is disabled, in which case there's no horizon
+/// that is long enough to reach a terminal state from every state.
+pub fn try_determine_horizon<T: Transition>(transitions: &[Vec<Vec<T>>]) -> Option<usize> {
+    let memoization = dfs(transitions)?;
     let DfsState::Done(depth) = memoization[0] else {
         unreachable!()
     };
-    depth
+    // The self-transitions of terminal states take 1 unit of time.
+    Some(depth - 1 + get_time_step(transitions))
+}
+
+/// Get the greatest common divisor of the nonzero times of the transitions between different
+/// states, or 1 if there are no such transitions.
+///
+/// This is 1 for [`RegularTransition`]s, and the time unit in which the travel times are
+/// expressed for [`TimedTransition`]s, e.g., 10 if all travel and repair times are multiples of
+/// 10.
+pub fn get_time_step<T: Transition>(transitions: &[Vec<Vec<T>>]) -> usize {
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+    let step = transitions
+        .iter()
+        .enumerate()
+        .flat_map(|(i, actions)| {
+            actions
+                .iter()
+                .flatten()
+                .filter(move |t| t.get_successor() as usize != i)
+        })
+        .fold(0, |step, t| gcd(step, t.get_time() as usize));
+    step.max(1)
+}
+
+/// Specifies how the optimization horizon is determined.
+///
+/// Serialized as `null` for [`HorizonSpec::Auto`], a number for [`HorizonSpec::Absolute`], and
+/// `{ "multiple": x }` for [`HorizonSpec::Multiple`].
+/// Hence, it's backwards compatible with `Option<usize>`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(try_from = "HorizonSpecRepr", into = "HorizonSpecRepr")]
+pub enum HorizonSpec {
+    /// Determine the horizon automatically from transitions.
+    #[default]
+    Auto,
+    /// Use the given value as the horizon.
+    Absolute(usize),
+    /// Multiply the automatically determined horizon with the given factor, rounding up.
+    /// The factor must be finite and positive.
+    Multiple(f64),
+}
+
+impl HorizonSpec {
+    /// Get the optimization horizon given the automatically determined one.
+    ///
+    /// Returns [`SolveFailure::BadInput`] if the factor of [`HorizonSpec::Multiple`] is not
+    /// finite and positive.
+    pub fn resolve(&self, auto_horizon: usize) -> Result<usize, SolveFailure> {
+        match *self {
+            HorizonSpec::Auto => Ok(auto_horizon),
+            HorizonSpec::Absolute(horizon) => Ok(horizon),
+            HorizonSpec::Multiple(factor) => {
+                validate_horizon_factor(factor).map_err(SolveFailure::BadInput)?;
+                Ok(((auto_horizon as f64) * factor).ceil() as usize)
+            }
+        }
+    }
+}
+
+/// Check whether the factor of [`HorizonSpec::Multiple`] is finite and positive.
+fn validate_horizon_factor(factor: f64) -> Result<(), String> {
+    if factor.is_finite() && factor > 0.0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Horizon multiple must be finite and positive, got {factor}"
+        ))
+    }
+}
+
+impl From<Option<usize>> for HorizonSpec {
+    fn from(value: Option<usize>) -> Self {
+        match value {
+            Some(horizon) => HorizonSpec::Absolute(horizon),
+            None => HorizonSpec::Auto,
+        }
+    }
+}
+
+/// Private helper for [`HorizonSpec`] serialization.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum HorizonSpecRepr {
+    Auto(()),
+    Absolute(usize),
+    Multiple { multiple: f64 },
+}
+
+impl TryFrom<HorizonSpecRepr> for HorizonSpec {
+    type Error = String;
+
+    fn try_from(value: HorizonSpecRepr) -> Result<Self, Self::Error> {
+        Ok(match value {
+            HorizonSpecRepr::Auto(()) => HorizonSpec::Auto,
+            HorizonSpecRepr::Absolute(horizon) => HorizonSpec::Absolute(horizon),
+            HorizonSpecRepr::Multiple { multiple } => {
+                validate_horizon_factor(multiple)?;
+                HorizonSpec::Multiple(multiple)
+            }
+        })
+    }
+}
+
+impl From<HorizonSpec> for HorizonSpecRepr {
+    fn from(value: HorizonSpec) -> Self {
+        match value {
+            HorizonSpec::Auto => HorizonSpecRepr::Auto(()),
+            HorizonSpec::Absolute(horizon) => HorizonSpecRepr::Absolute(horizon),
+            HorizonSpec::Multiple(multiple) => HorizonSpecRepr::Multiple { multiple },
+        }
+    }
+}
+
+/// Probabilities of the transitions that change over time.
+///
+/// Each entry contains the time from which the probabilities apply and the probability of each
+/// transition in the same layout as the transitions. A transition uses the probabilities of the
+/// last entry that starts at or before the time it ends, or its own probability if there's no
+/// such entry. Entries must be sorted by time.
+pub type ProbabilitySchedule = Vec<(Time, Vec<Vec<Vec<Probability>>>)>;
+
+/// Get the probability of the given transition (`j`th action of state `i`, `k`th transition)
+/// that ends at the given time according to the [`ProbabilitySchedule`].
+#[inline]
+fn scheduled_probability<T: Transition>(
+    schedule: Option<&ProbabilitySchedule>,
+    (i, j, k): (usize, usize, usize),
+    t: &T,
+    end: usize,
+) -> Value {
+    schedule
+        .and_then(|schedule| {
+            schedule
+                .iter()
+                .rev()
+                .find(|(start, _)| (*start as usize) <= end)
+        })
+        .map_or(t.get_probability() as Value, |(_, probabilities)| {
+            probabilities[i][j][k] as Value
+        })
 }
 
 /// Generic policy synthesizer for the given transition type.
@@ -284,38 +572,455 @@ pub trait PolicySynthesizer<TransitionType: Transition> {
         transitions: &[Vec<Vec<TransitionType>>],
         horizon: usize,
     ) -> (Vec<Vec<Value>>, Vec<ActionIndex>);
+
+    /// Synthesize a policy like [`PolicySynthesizer::synthesize_policy`], but add a cost of 1 for
+    /// every time unit a bus remains unenergized past its deadline.
+    ///
+    /// `deadlines[i]` contains the deadlines of unenergized buses in state `i`.
+    ///
+    /// Returns `None` if deadlines are not supported by this policy synthesizer.
+    fn synthesize_policy_with_deadlines(
+        _transitions: &[Vec<Vec<TransitionType>>],
+        _horizon: usize,
+        _deadlines: &[Vec<Time>],
+    ) -> Option<(Vec<Vec<Value>>, Vec<ActionIndex>)> {
+        None
+    }
+
+    /// Synthesize a policy like [`PolicySynthesizer::synthesize_policy`], but the probabilities
+    /// of the transitions depend on the time at which they end, i.e., when the energization
+    /// outcomes are resolved, according to the given [`ProbabilitySchedule`].
+    ///
+    /// The time is measured from the start of the horizon.
+    ///
+    /// Returns `None` if probability schedules are not supported by this policy synthesizer.
+    fn synthesize_policy_with_schedule(
+        _transitions: &[Vec<Vec<TransitionType>>],
+        _horizon: usize,
+        _schedule: &ProbabilitySchedule,
+    ) -> Option<(Vec<Vec<Value>>, Vec<ActionIndex>)> {
+        None
+    }
+
+    /// Synthesize a policy like [`PolicySynthesizer::synthesize_policy`], but start from the
+    /// given value of each state for the same horizon, e.g., the values of the previous solution
+    /// of a slightly modified problem with the same states (see [`get_state_values`]).
+    ///
+    /// The initial values may only reduce the work needed to converge, and the result must be
+    /// identical to [`PolicySynthesizer::synthesize_policy`]. The synthesizers that iterate over
+    /// the horizon don't support this, since the initial values would act as the values beyond
+    /// the horizon and change the result.
+    ///
+    /// Returns `None` if warm starts are not supported by this policy synthesizer.
+    fn synthesize_policy_warm(
+        _transitions: &[Vec<Vec<TransitionType>>],
+        _horizon: usize,
+        _initial_values: &[Value],
+    ) -> Option<(Vec<Vec<Value>>, Vec<ActionIndex>)> {
+        None
+    }
+}
+
+/// Get the additional cost of the buses with given deadlines that remain unenergized from time
+/// `start` for `duration` time units.
+#[inline]
+fn deadline_cost(deadlines: &[Time], start: usize, duration: usize) -> Value {
+    let end = start + duration;
+    deadlines
+        .iter()
+        .map(|&deadline| end.saturating_sub(std::cmp::max(start, deadline as usize)))
+        .sum::<usize>() as Value
+}
+
+/// Minimum number of states for which [`NaivePolicySynthesizer`] computes the values of the
+/// states in parallel, see [`NaivePolicySynthesizer::synthesize_with_threshold`].
+#[cfg(feature = "parallel")]
+const PARALLEL_SYNTHESIS_THRESHOLD: usize = 4096;
+
+/// Get the threshold for parallel policy synthesis, which is never reached if the `parallel`
+/// feature is disabled.
+#[inline]
+fn parallel_synthesis_threshold() -> usize {
+    #[cfg(feature = "parallel")]
+    let parallel_threshold = PARALLEL_SYNTHESIS_THRESHOLD;
+    #[cfg(not(feature = "parallel"))]
+    let parallel_threshold = usize::MAX;
+    parallel_threshold
 }
 
 /// The most basic policy synthesizer for `RegularTransition`s.
 /// Uses a bottom-up approach, computing each `V_{i}` before `V_{i+1}`.
 ///
 /// The complexity is `O(optimization_horizon * transitions)`.
+///
+/// If the `parallel` feature is enabled, the states are processed in parallel when there are
+/// many of them. The value of each state is computed in the same way, so the results are
+/// identical to the serial version.
 pub struct NaivePolicySynthesizer;
 
-impl PolicySynthesizer<RegularTransition> for NaivePolicySynthesizer {
-    fn synthesize_policy(
+impl NaivePolicySynthesizer {
+    /// Compute the value of an action given the values for the previous horizon.
+    #[inline]
+    fn action_value(transitions: &[RegularTransition], prev_val: &[Value]) -> Value {
+        transitions
+            .iter()
+            .map(|t| {
+                let p = t.p as Value;
+                let cost = t.cost as Value;
+                let successor = t.successor as usize;
+                p * (cost + prev_val[successor])
+            })
+            .sum()
+    }
+
+    /// Compute the values of the actions in a state and the index of the optimal one.
+    /// Ties are broken in favor of the lowest action index.
+    fn action_values(
+        actions: &[Vec<RegularTransition>],
+        prev_val: &[Value],
+    ) -> (Vec<Value>, ActionIndex) {
+        let action_values: Vec<Value> = actions
+            .iter()
+            .map(|transitions| Self::action_value(transitions, prev_val))
+            .collect();
+        let optimal_action = action_values
+            .iter()
+            .enumerate()
+            .min_by(|a: &(usize, &Value), b: &(usize, &Value)| {
+                a.1.partial_cmp(b.1)
+                    .expect("Transition values must be comparable in value iteration")
+            })
+            .expect("No actions in a state")
+            .0;
+        (action_values, optimal_action as ActionIndex)
+    }
+
+    /// Compute the optimal value of each state for the next horizon given the values for the
+    /// previous horizon, in parallel if there are at least `parallel_threshold` states.
+    #[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+    fn next_values(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        prev_val: &[Value],
+        parallel_threshold: usize,
+    ) -> Vec<Value> {
+        let optimal_value = |actions: &Vec<Vec<RegularTransition>>| -> Value {
+            actions
+                .iter()
+                .map(|transitions| Self::action_value(transitions, prev_val))
+                .min_by(|a: &Value, b| {
+                    a.partial_cmp(b)
+                        .expect("Transition values must be comparable in value iteration")
+                })
+                .expect("No actions in a state")
+        };
+        let mut values: Vec<Value> = vec![0.0; transitions.len()];
+
+        #[cfg(feature = "parallel")]
+        if transitions.len() >= parallel_threshold {
+            use rayon::prelude::*;
+            values
+                .par_iter_mut()
+                .zip(transitions.par_iter())
+                .for_each(|(value, actions)| *value = optimal_value(actions));
+            return values;
+        }
+
+        for (value, actions) in values.iter_mut().zip(transitions.iter()) {
+            *value = optimal_value(actions);
+        }
+        values
+    }
+
+    /// Compute the optimal value of the initial state for each horizon from 1 to `max_horizon`
+    /// in a single pass.
+    ///
+    /// The value at index `h - 1` is equal to the minimum value in the initial state after
+    /// synthesizing a policy with horizon `h`, see [`get_min_value`].
+    /// The complexity is `O(max_horizon * transitions)`, whereas synthesizing a policy for each
+    /// horizon separately is `O(max_horizon^2 * transitions)`.
+    pub fn synthesize_value_sweep(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        max_horizon: usize,
+    ) -> Vec<Value> {
+        assert!(
+            !transitions.is_empty(),
+            "States must be non-empty during policy synthesis"
+        );
+        let parallel_threshold = parallel_synthesis_threshold();
+        let mut values: Vec<Value> = vec![0.0; transitions.len()];
+        (0..max_horizon)
+            .map(|_| {
+                values = Self::next_values(transitions, &values, parallel_threshold);
+                values[0]
+            })
+            .collect()
+    }
+
+    /// Synthesize a policy like [`PolicySynthesizer::synthesize_policy`].
+    ///
+    /// The states are processed in parallel if the `parallel` feature is enabled and there are
+    /// at least `parallel_threshold` states. The result doesn't depend on the threshold.
+    pub fn synthesize_with_threshold(
         transitions: &[Vec<Vec<RegularTransition>>],
         horizon: usize,
+        parallel_threshold: usize,
     ) -> (Vec<Vec<Value>>, Vec<ActionIndex>) {
         assert!(
             !transitions.is_empty(),
             "States must be non-empty during policy synthesis"
         );
-        let mut values: Array1<Value> = Array1::zeros(transitions.len());
+        let mut values: Vec<Value> = vec![0.0; transitions.len()];
         for _ in 1..horizon {
-            let prev_val = values;
-            values = Array1::zeros(transitions.len());
-            for (i, action) in transitions.iter().enumerate() {
-                let optimal_value: Value = action
+            values = Self::next_values(transitions, &values, parallel_threshold);
+        }
+
+        let prev_val = values;
+        #[cfg(feature = "parallel")]
+        if transitions.len() >= parallel_threshold {
+            use rayon::prelude::*;
+            return transitions
+                .par_iter()
+                .map(|actions| Self::action_values(actions, &prev_val))
+                .unzip();
+        }
+
+        transitions
+            .iter()
+            .map(|actions| Self::action_values(actions, &prev_val))
+            .unzip()
+    }
+}
+
+impl PolicySynthesizer<RegularTransition> for NaivePolicySynthesizer {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(states = transitions.len(), horizon = horizon))
+    )]
+    fn synthesize_policy(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>) {
+        Self::synthesize_with_threshold(transitions, horizon, parallel_synthesis_threshold())
+    }
+}
+
+/// Policy synthesizer for `RegularTransition`s that uses Gauss-Seidel (in-place) value
+/// iteration.
+///
+/// Terminal states keep incurring their cost in each time step. Hence, the value of a state
+/// grows linearly with the horizon once the horizon is long enough to reach a terminal state:
+/// `V_h(s) = U(s) + h * R(s)`, where `R(s)` is the expected cost rate of the terminal state
+/// that is eventually reached, and `U(s)` is the relative value of the state.
+/// `R` and `U` don't depend on the horizon, so they are computed by sweeping over the states
+/// and updating the values in-place until they no longer change.
+/// The state-action values for the given horizon are then derived from them.
+/// This reaches the same values as [`NaivePolicySynthesizer`] up to floating point errors.
+///
+/// ## State ordering
+///
+/// The states are swept in reverse index order, so a state is updated after its successors if
+/// the successors have larger indices.
+/// In that case, a single sweep is enough to determine the values, and another one is needed
+/// to confirm the convergence.
+/// The indices assigned during exploration (see [`crate::teams::NaiveExplorer`]) mostly satisfy
+/// this since states are discovered in breadth-first order.
+/// Otherwise, the result is still correct but more sweeps are needed, up to the length of the
+/// longest path in the MDP.
+///
+/// ## Fallback
+///
+/// Falls back to [`NaivePolicySynthesizer`] if:
+/// - the MDP state graph is cyclic or the horizon is shorter than the one returned by
+///   [`determine_horizon`], or
+/// - the terminal cost rate depends on the chosen actions (not the case in field-teams
+///   restoration problem), or
+/// - a non-terminal state has a self-transition, e.g., [`crate::teams::Config::global_wait`].
+pub struct GaussSeidelPolicySynthesizer;
+
+/// Maximum relative difference between the terminal cost rates of actions in a state.
+/// See [`GaussSeidelPolicySynthesizer`].
+const TERMINAL_RATE_TOLERANCE: Value = 1e-4;
+
+/// Returns true if the terminal cost rate of each action in each state is equal to the rate of
+/// the state within [`TERMINAL_RATE_TOLERANCE`], i.e., the rates don't depend on the chosen
+/// actions.
+fn consistent_terminal_rates(transitions: &[Vec<Vec<RegularTransition>>], rates: &[Value]) -> bool {
+    transitions.iter().enumerate().all(|(i, actions)| {
+        actions.iter().all(|transitions| {
+            let rate: Value = transitions
+                .iter()
+                .map(|t| (t.p as Value) * rates[t.successor as usize])
+                .sum();
+            (rate - rates[i]).abs() <= TERMINAL_RATE_TOLERANCE * rates[i].abs().max(1.0)
+        })
+    })
+}
+
+/// Derive the state-action values and the optimal policy for the given horizon from the
+/// terminal cost rate `R` and the relative value `U` of each state, see
+/// [`GaussSeidelPolicySynthesizer`].
+fn horizon_action_values(
+    transitions: &[Vec<Vec<RegularTransition>>],
+    rates: &[Value],
+    relative: &[Value],
+    horizon: usize,
+) -> (Vec<Vec<Value>>, Vec<ActionIndex>) {
+    // Value of each state with horizon - 1, see NaivePolicySynthesizer::synthesize.
+    let prev_horizon = (horizon - 1) as Value;
+    let mut state_action_values: Vec<Vec<Value>> = Vec::with_capacity(transitions.len());
+    let mut policy: Vec<ActionIndex> = vec![0; transitions.len()];
+    for (i, action) in transitions.iter().enumerate() {
+        let action_values: Vec<Value> = action
+            .iter()
+            .map(|transitions| {
+                transitions
+                    .iter()
+                    .map(|t| {
+                        let successor = t.successor as usize;
+                        let prev_val = relative[successor] + prev_horizon * rates[successor];
+                        (t.p as Value) * ((t.cost as Value) + prev_val)
+                    })
+                    .sum()
+            })
+            .collect();
+        let optimal_action = action_values
+            .iter()
+            .enumerate()
+            .min_by(|a: &(usize, &Value), b: &(usize, &Value)| {
+                a.1.partial_cmp(b.1)
+                    .expect("Transition values must be comparable in value iteration")
+            })
+            .expect("No actions in a state")
+            .0;
+        state_action_values.push(action_values);
+        policy[i] = optimal_action as ActionIndex;
+    }
+    (state_action_values, policy)
+}
+
+impl GaussSeidelPolicySynthesizer {
+    /// Returns true if the given actions belong to a terminal state.
+    #[inline]
+    fn is_terminal(index: usize, actions: &[Vec<RegularTransition>]) -> bool {
+        match actions {
+            [action] => match action.as_slice() {
+                [t] => t.successor as usize == index,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Get the terminal cost rate of each terminal state, which is its cost, and 0 for the other
+    /// states.
+    ///
+    /// Returns `None` if a non-terminal state has a self-transition.
+    fn terminal_state_rates(transitions: &[Vec<Vec<RegularTransition>>]) -> Option<Vec<Value>> {
+        let mut rates: Vec<Value> = vec![0.0; transitions.len()];
+        for (i, actions) in transitions.iter().enumerate() {
+            if Self::is_terminal(i, actions) {
+                rates[i] = actions[0][0].cost as Value;
+            } else if actions.iter().flatten().any(|t| t.successor as usize == i) {
+                // Self-transitions in non-terminal states, e.g., global wait action.
+                return None;
+            }
+        }
+        Some(rates)
+    }
+
+    /// Compute the terminal cost rate `R` and the relative value `U` of each state by in-place
+    /// sweeps, see [`GaussSeidelPolicySynthesizer`].
+    ///
+    /// Returns the rates, the relative values, and the number of sweeps, including the last one
+    /// that confirms the convergence.
+    /// Returns `None` if the terminal cost rate depends on the chosen actions or a non-terminal
+    /// state has a self-transition.
+    pub fn relative_values(
+        transitions: &[Vec<Vec<RegularTransition>>],
+    ) -> Option<(Vec<Value>, Vec<Value>, usize)> {
+        let rates = Self::terminal_state_rates(transitions)?;
+        let relative: Vec<Value> = vec![0.0; transitions.len()];
+        Self::sweep_until_convergence(transitions, rates, relative)
+    }
+
+    /// Compute the terminal cost rates and the relative values like
+    /// [`GaussSeidelPolicySynthesizer::relative_values`], but start the sweeps from the given
+    /// value of each state for the given horizon instead of zeros.
+    ///
+    /// The rates are computed exactly in topological order first, and the relative values are
+    /// seeded with `V_h(s) - h * R(s)`. Since the MDP state graph is acyclic, the sweeps converge
+    /// to the same values regardless of the seeds.
+    ///
+    /// Panics if the MDP state graph is cyclic or the number of initial values doesn't match the
+    /// number of states.
+    pub fn warm_relative_values(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+        initial_values: &[Value],
+    ) -> Option<(Vec<Value>, Vec<Value>, usize)> {
+        assert_eq!(
+            initial_values.len(),
+            transitions.len(),
+            "Number of initial values must match the number of states"
+        );
+        let mut rates = Self::terminal_state_rates(transitions)?;
+        let depths = longest_path_lengths(transitions);
+        let mut order: Vec<usize> = (0..transitions.len()).collect();
+        // The successors of a state have smaller depths, except for the terminal self-transitions.
+        order.sort_unstable_by_key(|&i| depths[i]);
+        for i in order {
+            if !Self::is_terminal(i, &transitions[i]) {
+                rates[i] = transitions[i][0]
+                    .iter()
+                    .map(|t| (t.p as Value) * rates[t.successor as usize])
+                    .sum();
+            }
+        }
+        let relative: Vec<Value> = transitions
+            .iter()
+            .enumerate()
+            .map(|(i, actions)| {
+                if Self::is_terminal(i, actions) {
+                    0.0
+                } else {
+                    initial_values[i] - (horizon as Value) * rates[i]
+                }
+            })
+            .collect();
+        Self::sweep_until_convergence(transitions, rates, relative)
+    }
+
+    /// Update the terminal cost rates and the relative values by in-place sweeps until they no
+    /// longer change, starting from the given ones.
+    ///
+    /// See [`GaussSeidelPolicySynthesizer::relative_values`] for the returned values.
+    fn sweep_until_convergence(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        mut rates: Vec<Value>,
+        mut relative: Vec<Value>,
+    ) -> Option<(Vec<Value>, Vec<Value>, usize)> {
+        let mut sweeps: usize = 0;
+        loop {
+            assert!(sweeps <= transitions.len(), "MDP state graph is cyclic");
+            sweeps += 1;
+            let mut changed = false;
+            for (i, actions) in transitions.iter().enumerate().rev() {
+                if Self::is_terminal(i, actions) {
+                    continue;
+                }
+                let rate: Value = actions[0]
+                    .iter()
+                    .map(|t| (t.p as Value) * rates[t.successor as usize])
+                    .sum();
+                let value: Value = actions
                     .iter()
                     .map(|transitions| {
                         transitions
                             .iter()
                             .map(|t| {
-                                let p = t.p as Value;
-                                let cost = t.cost as Value;
                                 let successor = t.successor as usize;
-                                p * (cost + prev_val[successor])
+                                (t.p as Value)
+                                    * ((t.cost as Value) + relative[successor] - rates[successor])
                             })
                             .sum()
                     })
@@ -324,42 +1029,263 @@ impl PolicySynthesizer<RegularTransition> for NaivePolicySynthesizer {
                             .expect("Transition values must be comparable in value iteration")
                     })
                     .expect("No actions in a state");
-                values[i] = optimal_value;
+                if rate != rates[i] || value != relative[i] {
+                    rates[i] = rate;
+                    relative[i] = value;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
             }
         }
 
-        let mut state_action_values: Vec<Vec<Value>> = Vec::with_capacity(transitions.len());
-        let mut policy: Vec<ActionIndex> = vec![0; transitions.len()];
+        if consistent_terminal_rates(transitions, &rates) {
+            Some((rates, relative, sweeps))
+        } else {
+            None
+        }
+    }
 
-        let prev_val = values;
-        for (i, action) in transitions.iter().enumerate() {
-            let action_values: Vec<Value> = action
-                .iter()
-                .map(|transitions| {
-                    transitions
-                        .iter()
-                        .map(|t| {
-                            let p = t.p as Value;
-                            let cost = t.cost as Value;
-                            let successor = t.successor as usize;
-                            p * (cost + prev_val[successor])
-                        })
-                        .sum()
-                })
-                .collect();
-            let optimal_action = action_values
-                .iter()
-                .enumerate()
-                .min_by(|a: &(usize, &Value), b: &(usize, &Value)| {
-                    a.1.partial_cmp(b.1)
-                        .expect("Transition values must be comparable in value iteration")
-                })
-                .expect("No actions in a state")
-                .0;
-            state_action_values.push(action_values);
-            policy[i] = optimal_action as ActionIndex;
+    /// Synthesize a policy and return the number of sweeps alongside it.
+    ///
+    /// The number of sweeps is `None` if the synthesizer fell back to [`NaivePolicySynthesizer`].
+    pub fn synthesize_with_sweeps(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>, Option<usize>) {
+        Self::synthesize_seeded(transitions, horizon, None)
+    }
+
+    /// Synthesize a policy starting from the given value of each state like
+    /// [`PolicySynthesizer::synthesize_policy_warm`], and return the number of sweeps alongside
+    /// it like [`GaussSeidelPolicySynthesizer::synthesize_with_sweeps`].
+    ///
+    /// The result is identical to [`GaussSeidelPolicySynthesizer::synthesize_with_sweeps`], and
+    /// the initial values are ignored if the synthesizer falls back to
+    /// [`NaivePolicySynthesizer`].
+    pub fn synthesize_warm_with_sweeps(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+        initial_values: &[Value],
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>, Option<usize>) {
+        Self::synthesize_seeded(transitions, horizon, Some(initial_values))
+    }
+
+    /// Synthesize a policy, starting the sweeps from the given state values if any.
+    fn synthesize_seeded(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+        initial_values: Option<&[Value]>,
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>, Option<usize>) {
+        assert!(
+            !transitions.is_empty(),
+            "States must be non-empty during policy synthesis"
+        );
+        if !matches!(try_determine_horizon(transitions), Some(auto_horizon) if horizon >= auto_horizon)
+        {
+            let (values, policy) = NaivePolicySynthesizer::synthesize_policy(transitions, horizon);
+            return (values, policy, None);
         }
-        (state_action_values, policy)
+        let relative_values = match initial_values {
+            Some(initial_values) => {
+                Self::warm_relative_values(transitions, horizon, initial_values)
+            }
+            None => Self::relative_values(transitions),
+        };
+        let Some((rates, relative, sweeps)) = relative_values else {
+            let (values, policy) = NaivePolicySynthesizer::synthesize_policy(transitions, horizon);
+            return (values, policy, None);
+        };
+
+        let (values, policy) = horizon_action_values(transitions, &rates, &relative, horizon);
+        (values, policy, Some(sweeps))
+    }
+}
+
+impl PolicySynthesizer<RegularTransition> for GaussSeidelPolicySynthesizer {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(states = transitions.len(), horizon = horizon))
+    )]
+    fn synthesize_policy(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>) {
+        let (values, policy, _) = Self::synthesize_with_sweeps(transitions, horizon);
+        (values, policy)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(states = transitions.len(), horizon = horizon))
+    )]
+    fn synthesize_policy_warm(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+        initial_values: &[Value],
+    ) -> Option<(Vec<Vec<Value>>, Vec<ActionIndex>)> {
+        let (values, policy, _) =
+            Self::synthesize_warm_with_sweeps(transitions, horizon, initial_values);
+        Some((values, policy))
+    }
+}
+
+/// Policy synthesizer for `RegularTransition`s that uses policy iteration.
+///
+/// Like [`GaussSeidelPolicySynthesizer`], it computes the terminal cost rate `R` and the
+/// relative value `U` of each state, which don't depend on the horizon, and derives the
+/// state-action values for the given horizon from them.
+/// Starting from the first action in each state, it alternates between:
+/// - policy evaluation: computing `R` and `U` of the current policy, and
+/// - policy improvement: choosing the action that minimizes `U` in each state,
+///
+/// until the policy no longer changes.
+///
+/// Since the MDP state graph is acyclic apart from the self-transitions of terminal states,
+/// the policy is evaluated in a single pass by visiting the successors of a state before the
+/// state itself, see [`longest_path_lengths`].
+/// This reaches the same values as [`NaivePolicySynthesizer`] up to floating point errors.
+///
+/// Falls back to [`NaivePolicySynthesizer`] in the same cases as
+/// [`GaussSeidelPolicySynthesizer`].
+pub struct PolicyIterationSynthesizer;
+
+impl PolicyIterationSynthesizer {
+    /// Compute the terminal cost rate `R` and the relative value `U` of each state under the
+    /// given policy, visiting the states in the given order.
+    fn evaluate(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        order: &[usize],
+        policy: &[ActionIndex],
+        rates: &mut [Value],
+        relative: &mut [Value],
+    ) {
+        for &i in order {
+            let actions = &transitions[i];
+            if GaussSeidelPolicySynthesizer::is_terminal(i, actions) {
+                rates[i] = actions[0][0].cost as Value;
+                relative[i] = 0.0;
+                continue;
+            }
+            let mut rate: Value = 0.0;
+            let mut value: Value = 0.0;
+            for t in actions[policy[i] as usize].iter() {
+                let successor = t.successor as usize;
+                let p = t.p as Value;
+                rate += p * rates[successor];
+                value += p * ((t.cost as Value) + relative[successor] - rates[successor]);
+            }
+            rates[i] = rate;
+            relative[i] = value;
+        }
+    }
+
+    /// Choose the action that minimizes the relative value in each state.
+    /// The current action is kept unless another one is strictly better.
+    ///
+    /// Returns true if the policy has changed.
+    fn improve(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        policy: &mut [ActionIndex],
+        rates: &[Value],
+        relative: &[Value],
+    ) -> bool {
+        let mut changed = false;
+        for (i, actions) in transitions.iter().enumerate() {
+            let action_values = actions.iter().map(|transitions| {
+                transitions
+                    .iter()
+                    .map(|t| {
+                        let successor = t.successor as usize;
+                        (t.p as Value)
+                            * ((t.cost as Value) + relative[successor] - rates[successor])
+                    })
+                    .sum::<Value>()
+            });
+            let current = policy[i] as usize;
+            let mut best = (current, relative[i]);
+            for (j, value) in action_values.enumerate() {
+                if value < best.1 {
+                    best = (j, value);
+                }
+            }
+            if best.0 != current {
+                policy[i] = best.0 as ActionIndex;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Synthesize a policy and return the number of policy iterations alongside it, including
+    /// the last one in which the policy didn't change.
+    ///
+    /// The number of iterations is `None` if the synthesizer fell back to
+    /// [`NaivePolicySynthesizer`].
+    pub fn synthesize_with_iterations(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>, Option<usize>) {
+        assert!(
+            !transitions.is_empty(),
+            "States must be non-empty during policy synthesis"
+        );
+        let fallback = || {
+            let (values, policy) = NaivePolicySynthesizer::synthesize_policy(transitions, horizon);
+            (values, policy, None)
+        };
+        if !matches!(try_determine_horizon(transitions), Some(auto_horizon) if horizon >= auto_horizon)
+        {
+            return fallback();
+        }
+        let has_self_transitions = transitions.iter().enumerate().any(|(i, actions)| {
+            !GaussSeidelPolicySynthesizer::is_terminal(i, actions)
+                && actions.iter().flatten().any(|t| t.successor as usize == i)
+        });
+        if has_self_transitions {
+            // e.g., global wait action.
+            return fallback();
+        }
+
+        // Successors come before their predecessors in this order.
+        let depths = longest_path_lengths(transitions);
+        let mut order: Vec<usize> = (0..transitions.len()).collect();
+        order.sort_by_key(|&i| depths[i]);
+
+        let mut policy: Vec<ActionIndex> = vec![0; transitions.len()];
+        let mut rates: Vec<Value> = vec![0.0; transitions.len()];
+        let mut relative: Vec<Value> = vec![0.0; transitions.len()];
+        Self::evaluate(transitions, &order, &policy, &mut rates, &mut relative);
+        if !consistent_terminal_rates(transitions, &rates) {
+            return fallback();
+        }
+        let mut iterations: usize = 1;
+        while Self::improve(transitions, &mut policy, &rates, &relative) {
+            assert!(
+                iterations <= transitions.len(),
+                "Policy iteration did not converge"
+            );
+            iterations += 1;
+            Self::evaluate(transitions, &order, &policy, &mut rates, &mut relative);
+        }
+
+        let (values, policy) = horizon_action_values(transitions, &rates, &relative, horizon);
+        (values, policy, Some(iterations))
+    }
+}
+
+impl PolicySynthesizer<RegularTransition> for PolicyIterationSynthesizer {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(states = transitions.len(), horizon = horizon))
+    )]
+    fn synthesize_policy(
+        transitions: &[Vec<Vec<RegularTransition>>],
+        horizon: usize,
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>) {
+        let (values, policy, _) = Self::synthesize_with_iterations(transitions, horizon);
+        (values, policy)
     }
 }
 
@@ -375,109 +1301,192 @@ impl PolicySynthesizer<RegularTransition> for NaivePolicySynthesizer {
 /// energizable bus.
 pub struct NaiveTimedPolicySynthesizer;
 
-impl PolicySynthesizer<TimedTransition> for NaiveTimedPolicySynthesizer {
-    fn synthesize_policy(
+impl NaiveTimedPolicySynthesizer {
+    /// Synthesize a policy with optional deadlines and probability schedule.
+    /// See [`PolicySynthesizer::synthesize_policy_with_deadlines`] and
+    /// [`PolicySynthesizer::synthesize_policy_with_schedule`].
+    ///
+    /// The costs and probabilities depend on time in that case, so the actions of each state
+    /// are optimized for the earliest time at which it can be reached from the initial state,
+    /// see [`earliest_start_times`]. Otherwise, all states are assumed to start at time 0.
+    fn synthesize(
         transitions: &[Vec<Vec<TimedTransition>>],
         horizon: usize,
+        deadlines: Option<&[Vec<Time>]>,
+        schedule: Option<&ProbabilitySchedule>,
     ) -> (Vec<Vec<Value>>, Vec<ActionIndex>) {
         assert!(
             !transitions.is_empty(),
             "States must be non-empty during policy synthesis"
         );
+        // Additional cost of state i for the time interval starting at `start` with `duration`.
+        let extra_cost = |i: usize, start: usize, duration: usize| -> Value {
+            match deadlines {
+                Some(deadlines) => deadline_cost(&deadlines[i], start, duration),
+                None => 0.0,
+            }
+        };
+        // Time at which the actions of each state are optimized, which is at most the start of
+        // the last iteration.
+        let start_times: Vec<usize> = if deadlines.is_some() || schedule.is_some() {
+            earliest_start_times(transitions)
+                .into_iter()
+                .map(|time| std::cmp::min(time, horizon.saturating_sub(1)))
+                .collect()
+        } else {
+            vec![0; transitions.len()]
+        };
+        let mut state_action_values: Vec<Vec<Value>> = vec![Vec::new(); transitions.len()];
+        let mut policy: Vec<ActionIndex> = vec![0; transitions.len()];
+        // Values of the actions of the current state, reused to avoid allocations.
+        let mut action_values: Vec<Value> = Vec::new();
+        // Select the optimal action of state i that starts at `start` and return its value.
+        // The action values are stored if the actions of state i are optimized for this time.
+        let mut select_action = |i: usize, start: usize, action_values: &[Value]| -> Value {
+            let (optimal_action, optimal_value) = action_values
+                .iter()
+                .enumerate()
+                .min_by(|a: &(usize, &Value), b: &(usize, &Value)| {
+                    a.1.partial_cmp(b.1)
+                        .expect("Transition values must be comparable in value iteration")
+                })
+                .expect("No actions in a state");
+            if start_times[i] == start {
+                state_action_values[i] = action_values.to_vec();
+                policy[i] = optimal_action as ActionIndex;
+            }
+            *optimal_value
+        };
         // Special handling for first iteration: figure out maximum transition time, which will be
         // used to determine how many value functions we need to remember from previous iterations.
         let (values, max_time): (Array1<Value>, usize) = {
             let mut values = Array1::zeros(transitions.len());
             let mut max_time: usize = 0;
+            let start = horizon.saturating_sub(1);
             for (i, action) in transitions.iter().enumerate().rev() {
-                let optimal_value: Value = action
-                    .iter()
-                    .map(|transitions| {
-                        transitions
-                            .iter()
-                            .map(|t| {
-                                max_time = std::cmp::max(max_time, t.time as usize);
-                                (t.p as Value) * (t.cost as Value)
-                            })
-                            .sum()
-                    })
-                    .min_by(|a: &Value, b| {
-                        a.partial_cmp(b)
-                            .expect("Transition values must be comparable in value iteration")
-                    })
-                    .expect("No actions in a state");
-                values[i] = optimal_value;
+                action_values.clear();
+                action_values.extend(action.iter().enumerate().map(|(j, transitions)| {
+                    transitions
+                        .iter()
+                        .enumerate()
+                        .map(|(k, t)| {
+                            max_time = std::cmp::max(max_time, t.time as usize);
+                            let cost = (t.cost as Value)
+                                + extra_cost(i, start, std::cmp::min(t.time, 1) as usize);
+                            let p = scheduled_probability(
+                                schedule,
+                                (i, j, k),
+                                t,
+                                start + t.time as usize,
+                            );
+                            p * cost
+                        })
+                        .sum::<Value>()
+                }));
+                values[i] = select_action(i, start, &action_values);
             }
             (values, max_time)
         };
         // Array of values from previous iterations.
         // `values[0]`: current iteration, `values[1]`: previous iteration, etc.
         let mut values: Vec<Array1<Value>> = vec![values; max_time + 1];
-        for iteration in 2..horizon {
+        // The last iteration starts at time 0.
+        for iteration in 2..=horizon.max(1) {
             values[max_time] = Array1::zeros(transitions.len());
             values.rotate_right(1);
+            let start = horizon - iteration;
             for (i, action) in transitions.iter().enumerate().rev() {
-                let optimal_value: Value = action
-                    .iter()
-                    .map(|transitions| {
-                        transitions
-                            .iter()
-                            .map(|t| {
-                                let time = t.time as usize;
-                                let successor = t.successor as usize;
-                                let cost =
-                                    (t.cost as Value) * (std::cmp::min(time, iteration) as Value);
-                                t.p * (cost + values[time][successor])
-                            })
-                            .sum()
-                    })
-                    .min_by(|a: &Value, b| {
-                        a.partial_cmp(b)
-                            .expect("Transition values must be comparable in value iteration")
-                    })
-                    .expect("No actions in a state");
-                values[0][i] = optimal_value;
-            }
-        }
-
-        let mut state_action_values: Vec<Vec<Value>> = vec![Vec::new(); transitions.len()];
-        state_action_values.reserve(transitions.len());
-        let mut policy: Vec<ActionIndex> = vec![0; transitions.len()];
-
-        values[max_time] = Array1::zeros(transitions.len());
-        values.rotate_right(1);
-        for (i, action) in transitions.iter().enumerate().rev() {
-            let action_values: Vec<Value> = action
-                .iter()
-                .map(|transitions| {
+                action_values.clear();
+                action_values.extend(action.iter().enumerate().map(|(j, transitions)| {
                     transitions
                         .iter()
-                        .map(|t| {
+                        .enumerate()
+                        .map(|(k, t)| {
                             let time = t.time as usize;
                             let successor = t.successor as usize;
-                            let cost = (t.cost as Value) * (std::cmp::min(time, horizon) as Value);
-                            t.p * (cost + values[time][successor])
+                            let duration = std::cmp::min(time, iteration);
+                            let cost = (t.cost as Value) * (duration as Value)
+                                + extra_cost(i, start, duration);
+                            let p = scheduled_probability(schedule, (i, j, k), t, start + time);
+                            p * (cost + values[time][successor])
                         })
-                        .sum()
-                })
-                .collect();
-            let (optimal_action, optimal_value) = action_values
-                .iter()
-                .enumerate()
-                .min_by(|a: &(usize, &Value), b: &(usize, &Value)| {
-                    a.1.partial_cmp(b.1)
-                        .expect("Transition values must be comparable in value iteration")
-                })
-                .expect("No actions in a state");
-            // This might be required for zero-timed transitions.
-            values[0][i] = *optimal_value;
-            state_action_values[i] = action_values;
-            policy[i] = optimal_action as ActionIndex;
+                        .sum::<Value>()
+                }));
+                // Values of the current iteration are required for zero-timed transitions.
+                values[0][i] = select_action(i, start, &action_values);
+            }
         }
         (state_action_values, policy)
     }
 }
 
+/// Get the earliest time at which each state can be reached from the initial state, i.e., the
+/// first one, which is `usize::MAX` for unreachable states.
+///
+/// A state may be reached at different times depending on the actions and their outcomes.
+pub fn earliest_start_times(transitions: &[Vec<Vec<TimedTransition>>]) -> Vec<usize> {
+    let mut times = vec![usize::MAX; transitions.len()];
+    let mut queue = std::collections::BinaryHeap::new();
+    times[0] = 0;
+    queue.push(std::cmp::Reverse((0, 0)));
+    while let Some(std::cmp::Reverse((time, i))) = queue.pop() {
+        if time > times[i] {
+            continue;
+        }
+        for t in transitions[i].iter().flatten() {
+            let successor = t.successor as usize;
+            let arrival = time + t.time as usize;
+            if arrival < times[successor] {
+                times[successor] = arrival;
+                queue.push(std::cmp::Reverse((arrival, successor)));
+            }
+        }
+    }
+    times
+}
+
+impl PolicySynthesizer<TimedTransition> for NaiveTimedPolicySynthesizer {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(states = transitions.len(), horizon = horizon))
+    )]
+    fn synthesize_policy(
+        transitions: &[Vec<Vec<TimedTransition>>],
+        horizon: usize,
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>) {
+        Self::synthesize(transitions, horizon, None, None)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(states = transitions.len(), horizon = horizon))
+    )]
+    fn synthesize_policy_with_deadlines(
+        transitions: &[Vec<Vec<TimedTransition>>],
+        horizon: usize,
+        deadlines: &[Vec<Time>],
+    ) -> Option<(Vec<Vec<Value>>, Vec<ActionIndex>)> {
+        Some(Self::synthesize(
+            transitions,
+            horizon,
+            Some(deadlines),
+            None,
+        ))
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(states = transitions.len(), horizon = horizon))
+    )]
+    fn synthesize_policy_with_schedule(
+        transitions: &[Vec<Vec<TimedTransition>>],
+        horizon: usize,
+        schedule: &ProbabilitySchedule,
+    ) -> Option<(Vec<Vec<Value>>, Vec<ActionIndex>)> {
+        Some(Self::synthesize(transitions, horizon, None, Some(schedule)))
+    }
+}
+
 /// Get the minimum value of value function in the first state.
 pub fn get_min_value(values: &[Vec<Value>]) -> Value {
     *(values[0]
@@ -486,6 +1495,21 @@ pub fn get_min_value(values: &[Vec<Value>]) -> Value {
         .unwrap())
 }
 
+/// Get the value of each state, i.e., the minimum value among its actions.
+///
+/// These can be used as the initial values in [`PolicySynthesizer::synthesize_policy_warm`].
+pub fn get_state_values(values: &[Vec<Value>]) -> Vec<Value> {
+    values
+        .iter()
+        .map(|action_values| {
+            *action_values
+                .iter()
+                .min_by(|a, b| a.partial_cmp(b).unwrap())
+                .expect("No actions in a state")
+        })
+        .collect()
+}
+
 /// Get the total number of transitions.
 pub fn get_transition_count<T>(transitions: &[Vec<Vec<T>>]) -> usize {
     transitions
@@ -545,6 +1569,46 @@ mod tests {
         assert!(ser == "[2,0.5,6.0,12]" || ser == "[2,0.5,6,12]");
     }
 
+    #[test]
+    fn horizon_spec_test() {
+        for auto_horizon in 0..20 {
+            assert_eq!(
+                HorizonSpec::Multiple(1.0).resolve(auto_horizon).unwrap(),
+                HorizonSpec::Auto.resolve(auto_horizon).unwrap()
+            );
+            assert_eq!(
+                HorizonSpec::Multiple(2.0).resolve(auto_horizon).unwrap(),
+                2 * auto_horizon
+            );
+            assert_eq!(HorizonSpec::Absolute(5).resolve(auto_horizon).unwrap(), 5);
+        }
+        assert_eq!(HorizonSpec::Multiple(1.5).resolve(3).unwrap(), 5);
+        for factor in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                HorizonSpec::Multiple(factor).resolve(3),
+                Err(SolveFailure::BadInput(_))
+            ));
+        }
+
+        assert_eq!(HorizonSpec::from(None), HorizonSpec::Auto);
+        assert_eq!(HorizonSpec::from(Some(7)), HorizonSpec::Absolute(7));
+
+        // Backwards compatibility with Option<usize>
+        let spec: HorizonSpec = serde_json::from_str("null").unwrap();
+        assert_eq!(spec, HorizonSpec::Auto);
+        let spec: HorizonSpec = serde_json::from_str("12").unwrap();
+        assert_eq!(spec, HorizonSpec::Absolute(12));
+        let spec: HorizonSpec = serde_json::from_str(r#"{ "multiple": 2.5 }"#).unwrap();
+        assert_eq!(spec, HorizonSpec::Multiple(2.5));
+        assert!(serde_json::from_str::<HorizonSpec>(r#"{ "multiple": 0 }"#).is_err());
+        assert!(serde_json::from_str::<HorizonSpec>(r#"{ "multiple": -2.5 }"#).is_err());
+        assert_eq!(serde_json::to_string(&HorizonSpec::Auto).unwrap(), "null");
+        assert_eq!(
+            serde_json::to_string(&HorizonSpec::Absolute(3)).unwrap(),
+            "3"
+        );
+    }
+
     #[test]
     fn naive_policy_test() {
         let transitions: Vec<Vec<Vec<RegularTransition>>> = vec![
@@ -683,6 +1747,97 @@ mod tests {
         assert_eq!(actions, vec![0, 0, 0]);
     }
 
+    /// Test that the actions are optimized for the time at which a state is reached when there
+    /// are deadlines.
+    #[test]
+    fn deadline_start_time_policy_test() {
+        let transition = |successor: StateIndex, cost: Cost, time: Time| TimedTransition {
+            successor,
+            cost,
+            p: 1.0,
+            time,
+        };
+        let transitions: Vec<Vec<Vec<TimedTransition>>> = vec![
+            vec![vec![transition(1, 0 as Cost, 3)]],
+            vec![
+                vec![transition(2, 5 as Cost, 2)],
+                vec![transition(2, 1 as Cost, 4)],
+            ],
+            vec![vec![transition(2, 0 as Cost, 1)]],
+        ];
+        assert_eq!(earliest_start_times(&transitions), vec![0, 3, 5]);
+        let deadlines = vec![Vec::new(), vec![4; 4], Vec::new()];
+        let (values, actions) = NaiveTimedPolicySynthesizer::synthesize_policy_with_deadlines(
+            &transitions,
+            20,
+            &deadlines,
+        )
+        .unwrap();
+        // State 1 is reached at time 3, when the slower action misses the deadlines by 3 units.
+        // It would be optimal at time 0.
+        assert_eq!(
+            values,
+            vec![
+                vec![14 as Value],
+                vec![14 as Value, 16 as Value],
+                vec![0 as Value]
+            ]
+        );
+        assert_eq!(actions, vec![0, 0, 0]);
+    }
+
+    /// Test that the actions are optimized for the time at which a state is reached when the
+    /// probabilities change over time.
+    #[test]
+    fn schedule_start_time_policy_test() {
+        let transition =
+            |successor: StateIndex, cost: Cost, p: Probability, time: Time| TimedTransition {
+                successor,
+                cost,
+                p,
+                time,
+            };
+        let transitions: Vec<Vec<Vec<TimedTransition>>> = vec![
+            vec![vec![transition(1, 0 as Cost, 1.0, 3)]],
+            vec![
+                vec![
+                    transition(2, 0 as Cost, 0.5, 1),
+                    transition(3, 0 as Cost, 0.5, 1),
+                ],
+                vec![transition(2, 3 as Cost, 1.0, 1)],
+            ],
+            vec![vec![transition(2, 0 as Cost, 1.0, 1)]],
+            vec![vec![transition(3, 1 as Cost, 1.0, 1)]],
+        ];
+        let schedule: ProbabilitySchedule = vec![(
+            4,
+            vec![
+                vec![vec![1.0]],
+                vec![vec![0.75, 0.25], vec![1.0]],
+                vec![vec![1.0]],
+                vec![vec![1.0]],
+            ],
+        )];
+        let (values, actions) = NaiveTimedPolicySynthesizer::synthesize_policy_with_schedule(
+            &transitions,
+            10,
+            &schedule,
+        )
+        .unwrap();
+        // State 1 is reached at time 3, so the risky action ends when it's more likely to
+        // succeed. It would be suboptimal at time 0.
+        assert_eq!(
+            values,
+            vec![
+                vec![1.5 as Value],
+                vec![1.5 as Value, 3 as Value],
+                vec![0 as Value],
+                vec![6 as Value]
+            ]
+        );
+        assert_eq!(actions, vec![0, 0, 0, 0]);
+    }
+
     #[test]
     fn longest_path_lengths_simple_test() {
         let transitions: Vec<Vec<Vec<RegularTransition>>> = vec![
@@ -747,4 +1902,22 @@ mod tests {
         ];
         longest_path_lengths(&transitions);
     }
+
+    #[test]
+    fn time_step_horizon_test() {
+        let transitions = |scale: Time| -> Vec<Vec<Vec<TimedTransition>>> {
+            vec![
+                vec![vec![
+                    TimedTransition::from_parts(1, 1 as Cost, 0.5, 2 * scale),
+                    TimedTransition::from_parts(2, 1 as Cost, 0.5, 3 * scale),
+                ]],
+                vec![vec![TimedTransition::terminal_transition(1, 0 as Cost)]],
+                vec![vec![TimedTransition::terminal_transition(2, 1 as Cost)]],
+            ]
+        };
+        assert_eq!(get_time_step(&transitions(1)), 1);
+        assert_eq!(determine_horizon(&transitions(1)), 4);
+        assert_eq!(get_time_step(&transitions(10)), 10);
+        assert_eq!(determine_horizon(&transitions(10)), 40);
+    }
 }
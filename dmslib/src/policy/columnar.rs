@@ -0,0 +1,271 @@
+//! Structure-of-arrays representation of the transition space.
+use super::*;
+
+/// Transitions of all states stored in a structure-of-arrays layout.
+///
+/// The nested `Vec<Vec<Vec<T>>>` layout requires an allocation for each state and action, which
+/// makes it slow to serialize and deserialize for large solutions. This layout stores each field
+/// of the transitions in a flat array instead.
+///
+/// The transitions of action `a` (counting the actions of all states) are in the range
+/// `action_offsets[a]..action_offsets[a + 1]` of the transition arrays, and the actions of state
+/// `s` are in the range `state_offsets[s]..state_offsets[s + 1]`.
+///
+/// Offsets are serialized as the number of transitions in each action and the number of actions
+/// in each state, which are smaller numbers.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "ColumnarTransitionsRepr", into = "ColumnarTransitionsRepr")]
+pub struct ColumnarTransitions {
+    /// Index of the successor state of each transition.
+    pub successors: Vec<StateIndex>,
+    /// Probability of each transition.
+    pub probabilities: Vec<Probability>,
+    /// Cost of each transition.
+    pub costs: Vec<Cost>,
+    /// Time of each transition.
+    ///
+    /// Empty if all transitions take a single time unit, e.g., [`RegularTransition`]s.
+    pub times: Vec<Time>,
+    /// Index of the first transition of each action, followed by the total number of transitions.
+    pub action_offsets: Vec<usize>,
+    /// Index of the first action of each state, followed by the total number of actions.
+    pub state_offsets: Vec<usize>,
+}
+
+impl ColumnarTransitions {
+    /// Convert the transitions from the nested representation.
+    pub fn from_nested<T: Transition>(transitions: &[Vec<Vec<T>>]) -> Self {
+        let transition_count = get_transition_count(transitions);
+        let action_count: usize = transitions.iter().map(Vec::len).sum();
+
+        let mut successors = Vec::with_capacity(transition_count);
+        let mut probabilities = Vec::with_capacity(transition_count);
+        let mut costs = Vec::with_capacity(transition_count);
+        let mut times = Vec::with_capacity(transition_count);
+        let mut action_offsets = Vec::with_capacity(action_count + 1);
+        let mut state_offsets = Vec::with_capacity(transitions.len() + 1);
+        action_offsets.push(0);
+        state_offsets.push(0);
+        for actions in transitions {
+            for action in actions {
+                for t in action {
+                    successors.push(t.get_successor());
+                    probabilities.push(t.get_probability());
+                    costs.push(t.get_cost());
+                    times.push(t.get_time());
+                }
+                action_offsets.push(successors.len());
+            }
+            state_offsets.push(action_offsets.len() - 1);
+        }
+        if times.iter().all(|&time| time == 1) {
+            times = Vec::new();
+        }
+
+        ColumnarTransitions {
+            successors,
+            probabilities,
+            costs,
+            times,
+            action_offsets,
+            state_offsets,
+        }
+    }
+
+    /// Convert the transitions to the nested representation.
+    pub fn to_nested<T: Transition>(&self) -> Vec<Vec<Vec<T>>> {
+        self.state_offsets
+            .windows(2)
+            .map(|state| {
+                self.action_offsets[state[0]..=state[1]]
+                    .windows(2)
+                    .map(|action| {
+                        (action[0]..action[1])
+                            .map(|i| {
+                                T::from_parts(
+                                    self.successors[i],
+                                    self.costs[i],
+                                    self.probabilities[i],
+                                    self.get_time(i),
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Get the time of the transition at given index.
+    #[inline]
+    fn get_time(&self, index: usize) -> Time {
+        if self.times.is_empty() {
+            1
+        } else {
+            self.times[index]
+        }
+    }
+
+    /// Get the number of states.
+    pub fn state_count(&self) -> usize {
+        self.state_offsets.len() - 1
+    }
+}
+
+/// Private helper for [`ColumnarTransitions`] serialization.
+#[derive(Serialize, Deserialize)]
+struct ColumnarTransitionsRepr {
+    successors: Vec<StateIndex>,
+    probabilities: Vec<Probability>,
+    costs: Vec<Cost>,
+    times: Vec<Time>,
+    /// Number of transitions in each action.
+    action_lengths: Vec<usize>,
+    /// Number of actions in each state.
+    state_lengths: Vec<usize>,
+}
+
+/// Get the differences between consecutive offsets.
+fn offsets_to_lengths(offsets: &[usize]) -> Vec<usize> {
+    offsets.windows(2).map(|w| w[1] - w[0]).collect()
+}
+
+/// Get the offsets from lengths, starting from 0 and ending with the total length.
+fn lengths_to_offsets(lengths: &[usize]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(lengths.len() + 1);
+    offsets.push(0);
+    let mut total = 0;
+    for length in lengths {
+        total += length;
+        offsets.push(total);
+    }
+    offsets
+}
+
+impl From<ColumnarTransitions> for ColumnarTransitionsRepr {
+    fn from(value: ColumnarTransitions) -> Self {
+        ColumnarTransitionsRepr {
+            action_lengths: offsets_to_lengths(&value.action_offsets),
+            state_lengths: offsets_to_lengths(&value.state_offsets),
+            successors: value.successors,
+            probabilities: value.probabilities,
+            costs: value.costs,
+            times: value.times,
+        }
+    }
+}
+
+impl TryFrom<ColumnarTransitionsRepr> for ColumnarTransitions {
+    type Error = String;
+
+    fn try_from(value: ColumnarTransitionsRepr) -> Result<Self, Self::Error> {
+        let transition_count = value.successors.len();
+        if value.probabilities.len() != transition_count || value.costs.len() != transition_count {
+            return Err("Transition arrays have different lengths".to_string());
+        }
+        if !value.times.is_empty() && value.times.len() != transition_count {
+            return Err(
+                "Transition times must be empty or have a value for each transition".to_string(),
+            );
+        }
+        let action_offsets = lengths_to_offsets(&value.action_lengths);
+        if action_offsets[action_offsets.len() - 1] != transition_count {
+            return Err("Action lengths don't add up to the number of transitions".to_string());
+        }
+        let state_offsets = lengths_to_offsets(&value.state_lengths);
+        if state_offsets[state_offsets.len() - 1] != value.action_lengths.len() {
+            return Err("State lengths don't add up to the number of actions".to_string());
+        }
+        Ok(ColumnarTransitions {
+            successors: value.successors,
+            probabilities: value.probabilities,
+            costs: value.costs,
+            times: value.times,
+            action_offsets,
+            state_offsets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io;
+    use crate::teams::{solve_custom_timed, solve_naive, Config, Problem};
+    use bincode::Options;
+
+    const TEST_GRAPH: &str = include_str!("../../../graphs/FieldTeams/paperE0.json");
+
+    fn get_problem() -> (Problem, Config) {
+        let input_graph: io::Graph = serde_json::from_str(TEST_GRAPH).unwrap();
+        let teams = vec![
+            io::Team {
+                index: Some(0),
+                latlng: None,
+                name: None,
+                node: None,
+            };
+            2
+        ];
+        input_graph.to_teams_problem(teams, None).unwrap()
+    }
+
+    #[test]
+    fn columnar_regular_round_trip() {
+        let (problem, config) = get_problem();
+        let solution = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+
+        let columnar = ColumnarTransitions::from_nested(&solution.transitions);
+        assert_eq!(columnar.state_count(), solution.transitions.len());
+        assert!(columnar.times.is_empty());
+        assert_eq!(
+            columnar.to_nested::<RegularTransition>(),
+            solution.transitions
+        );
+
+        let options = bincode::DefaultOptions::new();
+        let encoded = options.serialize(&columnar).unwrap();
+        let decoded: ColumnarTransitions = options.deserialize(&encoded[..]).unwrap();
+        assert_eq!(decoded, columnar);
+
+        let nested_encoded = options.serialize(&solution.transitions).unwrap();
+        assert!(
+            encoded.len() < nested_encoded.len(),
+            "Columnar: {} bytes, nested: {} bytes",
+            encoded.len(),
+            nested_encoded.len()
+        );
+    }
+
+    #[test]
+    fn columnar_timed_round_trip() {
+        let (problem, config) = get_problem();
+        let solution = solve_custom_timed(
+            &problem.graph,
+            problem.initial_teams,
+            &config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap();
+
+        let columnar = ColumnarTransitions::from_nested(&solution.transitions);
+        assert_eq!(columnar.times.len(), columnar.successors.len());
+        assert_eq!(
+            columnar.to_nested::<TimedTransition>(),
+            solution.transitions
+        );
+
+        let encoded = serde_json::to_string(&columnar).unwrap();
+        let decoded: ColumnarTransitions = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(decoded, columnar);
+    }
+
+    #[test]
+    fn columnar_invalid_lengths() {
+        let json = r#"{"successors":[0],"probabilities":[1.0],"costs":[0],"times":[],"action_lengths":[2],"state_lengths":[1]}"#;
+        assert!(serde_json::from_str::<ColumnarTransitions>(json).is_err());
+    }
+}
@@ -3,6 +3,7 @@ mod actions;
 mod exploration;
 mod solve_variations;
 pub mod state;
+mod symmetry;
 pub mod transitions;
 
 pub use actions::*;
@@ -18,7 +19,10 @@ use crate::SolveFailure;
 
 use itertools::Itertools;
 use ndarray::{Array1, Array2};
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
 
 use sysinfo::{System, SystemExt};
@@ -35,7 +39,7 @@ use hashbrown::HashMap;
 pub type TeamAction = BusIndex;
 
 /// Contains information about the distribution system.
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct Graph {
     /// Travel times between each edge.
     ///
@@ -46,14 +50,242 @@ pub struct Graph {
     /// Adjacency list for branch connections.
     pub branches: Vec<Vec<BusIndex>>,
     /// True if a bus at given index is directly connected to energy resource.
+    ///
+    /// Ignored if [`Graph::bus_sources`] is given, use [`Graph::is_connected`] instead.
     pub connected: Vec<bool>,
     /// Failure probabilities.
     pub pfs: Array1<Probability>,
     /// The latitude and longtitude for each vertex in team graph.
     pub team_nodes: Array2<f64>,
+    /// Optional repair time for each bus.
+    ///
+    /// A team that is dispatched to a bus has to repair it after arriving, before an energization
+    /// attempt can happen. Hence, the repair time is added to the travel time.
+    pub repair_times: Option<Array1<Time>>,
+    /// Indices of depot nodes in team graph.
+    ///
+    /// Depots are not buses, but teams are allowed to move to them in order to reposition for
+    /// future travel. Moving to a depot has no energization effect.
+    /// Depot moves are disabled if this is empty.
+    ///
+    /// Must be sorted in ascending order.
+    pub depots: Vec<BusIndex>,
+    /// Optional deadline for each bus, `None` if the bus has no deadline.
+    ///
+    /// Each bus contributes an additional cost of 1 for every time unit it remains unenergized past
+    /// its deadline. Only supported by the timed policy synthesizers.
+    pub deadlines: Option<Vec<Option<Time>>>,
+    /// Optional matrix that determines whether team `i` can be dispatched to bus `j` at `(i, j)`,
+    /// e.g., due to voltage level or equipment requirements.
+    ///
+    /// All teams can be dispatched to all buses if `None`. A team that cannot be dispatched to any
+    /// of the remaining buses waits in place.
+    pub team_bus_allowed: Option<Array2<bool>>,
+    /// Optional class of each team. Teams in the same class are interchangeable, e.g., they
+    /// have the same equipment, which allows [`SymmetricStateIndexer`] to eliminate the
+    /// symmetric states.
+    ///
+    /// Teams in the same class must have the same rows in [`Graph::team_bus_allowed`].
+    pub team_classes: Option<Vec<usize>>,
+    /// Optional indices of the energy sources that each bus is directly connected to via external
+    /// branches.
+    ///
+    /// If given, a bus is directly connected only through its sources that are not out of
+    /// service according to [`Graph::source_outages`]. Otherwise, [`Graph::connected`] is used.
+    pub bus_sources: Option<Vec<Vec<usize>>>,
+    /// True for each energy source that is out of service.
+    ///
+    /// Sources without an entry are assumed to be in service.
+    pub source_outages: Vec<bool>,
+    /// Optional weight of each bus, e.g., according to the priority of its loads.
+    ///
+    /// If given, the cost of a state is the sum of the weights of the unenergized buses instead
+    /// of their count, see [`State::get_cost`]. The sum of all weights must be representable as
+    /// [`Cost`], which is checked by [`io::TeamProblem::prepare`].
+    pub weights: Option<Vec<Cost>>,
+    /// Optional precomputed result of [`Graph::get_components_on_way`] that is shared by the
+    /// clones of this graph, see [`PreparedProblem`].
+    ///
+    /// Must be consistent with [`Graph::travel_times`]. Discarded by [`Graph::quantized`].
+    pub components_on_way: Option<Arc<Array2<Vec<BusIndex>>>>,
 }
 
 impl Graph {
+    /// Returns true if the bus at given index can be energized directly from an energy source
+    /// that is in service, i.e., without an energized neighbor.
+    #[inline]
+    pub fn is_connected(&self, bus: usize) -> bool {
+        match &self.bus_sources {
+            Some(bus_sources) => bus_sources[bus]
+                .iter()
+                .any(|&source| !self.source_outages.get(source).cloned().unwrap_or(false)),
+            None => self.connected[bus],
+        }
+    }
+
+    /// Get the amount of time it takes for a team to be able to energize the bus `to` after
+    /// being dispatched from `from`, i.e., the travel time plus the repair time of `to`.
+    ///
+    /// Returns 0 if the team waits, i.e., `from == to`.
+    #[inline]
+    pub fn dispatch_time(&self, from: BusIndex, to: BusIndex) -> Time {
+        if from == to {
+            return 0;
+        }
+        let travel_time = self.travel_times[(from as usize, to as usize)];
+        match &self.repair_times {
+            // Depots have no repair time
+            Some(repair_times) => travel_time + repair_times.get(to as usize).cloned().unwrap_or(0),
+            None => travel_time,
+        }
+    }
+
+    /// Returns true if the team at the given index can be dispatched to the given node according
+    /// to [`Graph::team_bus_allowed`].
+    ///
+    /// Nodes that are not buses (e.g., depots) are always allowed.
+    #[inline]
+    pub fn is_team_allowed(&self, team: usize, node: BusIndex) -> bool {
+        match &self.team_bus_allowed {
+            Some(allowed) => allowed.get((team, node as usize)).cloned().unwrap_or(true),
+            None => true,
+        }
+    }
+
+    /// Get the maximum amount of time a team can be dispatched for, i.e., the maximum of
+    /// [`Graph::dispatch_time`].
+    ///
+    /// This is an upper bound for the `time` field in [`TeamState`].
+    pub fn get_max_dispatch_time(&self) -> Time {
+        let max_travel_time = self
+            .travel_times
+            .iter()
+            .max()
+            .cloned()
+            .expect("Cannot get max travel time");
+        let max_repair_time = self
+            .repair_times
+            .as_ref()
+            .and_then(|repair_times| repair_times.iter().max().cloned())
+            .unwrap_or(0);
+        max_travel_time + max_repair_time
+    }
+
+    /// Get the deadlines of unenergized buses in each given state for policy synthesis.
+    ///
+    /// Returns `None` if there are no deadlines.
+    pub fn get_state_deadlines(&self, bus_states: &Array2<BusState>) -> Option<Vec<Vec<Time>>> {
+        let deadlines = self.deadlines.as_ref()?;
+        Some(
+            bus_states
+                .rows()
+                .into_iter()
+                .map(|buses| {
+                    buses
+                        .iter()
+                        .zip(deadlines.iter())
+                        .filter_map(|(bus, deadline)| {
+                            if *bus == BusState::Energized {
+                                None
+                            } else {
+                                *deadline
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Check whether the given failure probability schedule is valid for this graph, see
+    /// [`Config::pfs_schedule`].
+    ///
+    /// Returns the description of the first problem on failure.
+    pub fn validate_pfs_schedule(
+        &self,
+        schedule: &[(Time, Vec<Probability>)],
+    ) -> Result<(), String> {
+        let certain = |pf: Probability| pf == 0.0 || pf == 1.0;
+        for (index, (time, pfs)) in schedule.iter().enumerate() {
+            if index > 0 && schedule[index - 1].0 >= *time {
+                return Err(format!(
+                    "Failure probability schedule is not in ascending order of time at entry {index}"
+                ));
+            }
+            if pfs.len() != self.pfs.len() {
+                return Err(format!(
+                    "Failure probability schedule entry {index} has {} buses but the graph has {}",
+                    pfs.len(),
+                    self.pfs.len()
+                ));
+            }
+            for (bus, (&pf, &base)) in pfs.iter().zip(self.pfs.iter()).enumerate() {
+                if !(0.0..=1.0).contains(&pf) {
+                    return Err(format!(
+                        "Failure probability of bus {bus} in schedule entry {index} is not in [0, 1]: {pf}"
+                    ));
+                }
+                let changes_outcomes = if certain(base) {
+                    pf != base
+                } else {
+                    certain(pf)
+                };
+                if changes_outcomes {
+                    return Err(format!(
+                        "Failure probability of bus {bus} in schedule entry {index} ({pf}) \
+                        changes the possible outcomes given by {base}"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the probability of each transition in each entry of the given failure probability
+    /// schedule (see [`Config::pfs_schedule`]) for policy synthesis.
+    ///
+    /// The probability of a transition is computed from the buses whose states change, see
+    /// [`State::get_probability`].
+    pub fn get_probability_schedule<T: Transition>(
+        &self,
+        schedule: &[(Time, Vec<Probability>)],
+        bus_states: &Array2<BusState>,
+        transitions: &[Vec<Vec<T>>],
+    ) -> ProbabilitySchedule {
+        let states: Vec<State> = bus_states
+            .rows()
+            .into_iter()
+            .map(|buses| State {
+                buses: buses.to_vec(),
+                teams: Vec::new(),
+            })
+            .collect();
+        schedule
+            .iter()
+            .map(|(time, pfs)| {
+                let probabilities = transitions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, actions)| {
+                        actions
+                            .iter()
+                            .map(|action| {
+                                action
+                                    .iter()
+                                    .map(|t| {
+                                        let successor = &states[t.get_successor() as usize];
+                                        states[i].get_probability(successor, pfs)
+                                    })
+                                    .collect()
+                            })
+                            .collect()
+                    })
+                    .collect();
+                (*time, probabilities)
+            })
+            .collect()
+    }
+
     /// Create a matrix that maps each path (i, j) in this graph to a list of buses on that path,
     /// sorted in ascending order.
     ///
@@ -78,13 +310,199 @@ impl Graph {
         }
         on_way
     }
+
+    /// Get the components on the way like [`Graph::get_components_on_way`], reusing
+    /// [`Graph::components_on_way`] if it's precomputed.
+    pub fn components_on_way(&self) -> Arc<Array2<Vec<BusIndex>>> {
+        match &self.components_on_way {
+            Some(on_way) => on_way.clone(),
+            None => Arc::new(self.get_components_on_way()),
+        }
+    }
+
+    /// Get the triples `(i, k, j)` of team nodes for which the travel time from i to j is longer
+    /// than going through k, i.e., violations of the triangle inequality.
+    ///
+    /// Action sets that filter the buses on the way, e.g., [`FilterEnergizedOnWay`], assume that
+    /// the triangle inequality holds and may eliminate optimal actions otherwise.
+    pub fn check_triangle_inequality(&self) -> Vec<(usize, usize, usize)> {
+        let node_count = self.travel_times.nrows();
+        let mut violations = Vec::new();
+        for i in 0..node_count {
+            for j in 0..node_count {
+                let direct = self.travel_times[[i, j]];
+                for k in 0..node_count {
+                    let through_k =
+                        self.travel_times[[i, k]].saturating_add(self.travel_times[[k, j]]);
+                    if through_k < direct {
+                        violations.push((i, k, j));
+                    }
+                }
+            }
+        }
+        violations
+    }
+
+    /// Replace the travel times with their metric closure, i.e., the shortest travel time between
+    /// each pair of nodes through any sequence of nodes, so that the triangle inequality holds.
+    pub fn repair_triangle_inequality(&mut self) {
+        let node_count = self.travel_times.nrows();
+        for k in 0..node_count {
+            for i in 0..node_count {
+                for j in 0..node_count {
+                    let through_k =
+                        self.travel_times[[i, k]].saturating_add(self.travel_times[[k, j]]);
+                    if through_k < self.travel_times[[i, j]] {
+                        self.travel_times[[i, j]] = through_k;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get a copy of this graph in which all times (travel times, repair times, and deadlines)
+    /// are expressed in units of the given quantum, rounded to the nearest integer.
+    ///
+    /// Nonzero times are at least 1 so that no movement becomes instantaneous.
+    /// Note that rounding may violate the triangle inequality.
+    pub fn quantized(&self, quantum: Time) -> Graph {
+        let quantize = |t: Time| {
+            if t == 0 {
+                0
+            } else {
+                let t = ((t as f64) / (quantum as f64)).round() as Time;
+                t.max(1)
+            }
+        };
+        Graph {
+            travel_times: self.travel_times.mapv(quantize),
+            repair_times: self.repair_times.as_ref().map(|r| r.mapv(quantize)),
+            deadlines: self.deadlines.as_ref().map(|deadlines| {
+                deadlines
+                    .iter()
+                    .map(|deadline| deadline.map(quantize))
+                    .collect()
+            }),
+            components_on_way: None,
+            ..self.clone()
+        }
+    }
 }
 
 /// Represents a field teams restoration problem.
 #[derive(Clone)]
 pub struct Problem {
     pub graph: Graph,
+    /// Initial team states, which are the ones in the most likely start configuration if
+    /// [`Problem::start_distribution`] is given.
     pub initial_teams: Vec<TeamState>,
+    /// If the start of the teams is uncertain, the probability of each start configuration
+    /// together with the initial team states in it, see [`solve_start_distribution`].
+    pub start_distribution: Option<Vec<(f64, Vec<TeamState>)>>,
+}
+
+impl Problem {
+    /// Check whether any bus can be energized starting from the initial state, which is
+    /// determined by [`State::compute_minbeta`].
+    ///
+    /// Returns [`SolveFailure::BadInput`] explaining the cause if the problem is infeasible:
+    /// - All unknown buses are unreachable from the energy sources.
+    /// - There are no teams but some unknown buses are reachable.
+    /// - No team is allowed on any of the buses that can be energized first, see
+    ///   [`Graph::team_bus_allowed`].
+    ///
+    /// A problem without unknown buses is feasible since there's nothing to restore.
+    pub fn check_feasibility(&self) -> Result<(), SolveFailure> {
+        let state = State::start_state(&self.graph, self.initial_teams.clone());
+        let unknown: Vec<usize> = (0..state.buses.len())
+            .filter(|&i| state.buses[i] == BusState::Unknown)
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        let minbeta = state.compute_minbeta(&self.graph);
+        let reachable = unknown
+            .iter()
+            .filter(|&&i| minbeta[i] != BusIndex::MAX)
+            .count();
+        if reachable == 0 {
+            return Err(SolveFailure::BadInput(format!(
+                "Infeasible problem: all {} unknown buses are unreachable from the energy sources",
+                unknown.len()
+            )));
+        }
+        if self.initial_teams.is_empty() {
+            return Err(SolveFailure::BadInput(format!(
+                "Infeasible problem: there are no teams but {reachable} unknown buses are reachable"
+            )));
+        }
+        let energizable: Vec<usize> = unknown.into_iter().filter(|&i| minbeta[i] == 1).collect();
+        let allowed = energizable.iter().any(|&bus| {
+            (0..self.initial_teams.len())
+                .any(|team| self.graph.is_team_allowed(team, bus as BusIndex))
+        });
+        if !allowed {
+            return Err(SolveFailure::BadInput(format!(
+                "Infeasible problem: no team is allowed on any of the energizable buses {energizable:?}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A graph with precomputed structures that don't depend on the teams or the bus states, e.g.,
+/// [`Graph::components_on_way`], for solving many scenarios on the same graph, e.g., sweeps over
+/// the team placements.
+pub struct PreparedProblem {
+    pub graph: Graph,
+}
+
+impl PreparedProblem {
+    /// Prepare the given graph, e.g., from [`io::TeamProblem::prepare`].
+    pub fn new(mut graph: Graph) -> PreparedProblem {
+        if graph.components_on_way.is_none() {
+            graph.components_on_way = Some(Arc::new(graph.get_components_on_way()));
+        }
+        PreparedProblem { graph }
+    }
+
+    /// Solve the scenario with the given teams, which must be located at the nodes of the graph,
+    /// starting from the given bus states or the start state (see [`State::start_state`]) if
+    /// `None`.
+    ///
+    /// The result is the same as solving with [`solve_generic_from_state`] on a newly prepared
+    /// graph. Since the precomputed structures depend on the travel times, the graph is not
+    /// quantized; [`Config::time_quantum`] must be 1.
+    pub fn solve_scenario<'a, TT, E, AA, PS>(
+        &'a self,
+        initial_teams: Vec<TeamState>,
+        initial_bus_states: Option<Vec<BusState>>,
+        config: &Config,
+    ) -> Result<Solution<TT>, SolveFailure>
+    where
+        TT: Transition,
+        E: Explorer<'a, TT>,
+        AA: ActionApplier<TT>,
+        PS: PolicySynthesizer<TT>,
+    {
+        if config.time_quantum != 1 {
+            return Err(SolveFailure::BadInput(
+                "Prepared problems cannot be quantized, quantize the graph before preparing"
+                    .to_string(),
+            ));
+        }
+        let start_state = match initial_bus_states {
+            Some(buses) => State {
+                buses,
+                teams: initial_teams,
+            },
+            None => State::start_state(&self.graph, initial_teams),
+        };
+        start_state
+            .validate(&self.graph)
+            .map_err(SolveFailure::BadInput)?;
+        solve_generic_from_state::<TT, E, AA, PS>(&self.graph, start_state, config)
+    }
 }
 
 impl io::Graph {
@@ -99,24 +517,153 @@ impl io::Graph {
             graph: self,
             teams,
             pfo: None,
-            horizon,
+            horizon: horizon.into(),
             time_func: io::TimeFunc::default(),
+            strict_branches: false,
+            repair_times: None,
+            time_scale: None,
+            depots: Vec::new(),
+            deadlines: None,
+            start_distribution: None,
         };
 
         team_problem.prepare()
     }
 }
 
+/// The quantity whose expectation is minimized by the policy, see [`Config::objective`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Objective {
+    /// Cumulative cost over time, where the cost of a state per time unit is the number of buses
+    /// that are not energized.
+    #[default]
+    Cost,
+    /// Time until a terminal state is reached, i.e., the cost of each state is 1 per time unit
+    /// regardless of the number of buses, and the terminal states cost nothing.
+    Makespan,
+}
+
 /// Configuration struct for teams problem.
+///
+/// The missing fields are set to their values in [`Config::unlimited`] during deserialization.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default = "Config::unlimited")]
 pub struct Config {
     /// State exploration will be cancelled if its memory usage exceeds this limit.
-    /// [`SolveFailure::OutOfMemory`] will be returned.
+    /// [`SolveFailure::OutOfMemory`] will be returned unless [`Config::allow_partial`] is set.
     pub max_memory: usize,
     /// Optimization horizon for policy synthesis.
-    /// Use `None` to automatically determine it based on transitions.
-    /// `Some(value)` allows setting the optimization horizon manually instead of determining it
-    /// automatically from state space.
-    pub horizon: Option<usize>,
+    /// Use [`HorizonSpec::Auto`] to automatically determine it based on transitions.
+    /// Other variants allow setting the optimization horizon manually or relative to the
+    /// automatically determined one.
+    pub horizon: HorizonSpec,
+    /// State exploration will be cancelled if the number of actions in a state exceeds this
+    /// limit, and [`SolveFailure::TooManyActions`] will be returned.
+    /// This protects against pathological states with too many actions.
+    pub max_actions_per_state: usize,
+    /// State exploration will be cancelled if the number of states exceeds this limit.
+    /// [`SolveFailure::TooManyStates`] will be returned.
+    ///
+    /// Unlike [`Config::max_memory`], this is checked each time a state is indexed, which makes
+    /// it more predictable.
+    pub max_states: usize,
+    /// If set, each explored state and the transitions of its actions are written to this file
+    /// as a line during state exploration for debugging, e.g., diffing against a reference run.
+    pub debug_dump: Option<PathBuf>,
+    /// If set, a global wait action that advances time by this amount without moving the teams
+    /// is added to each state where all teams are idle and at least one bus is unknown.
+    ///
+    /// The wait action is a self-transition, so it cannot cause an infinite loop within the
+    /// optimization horizon.
+    pub global_wait: Option<Time>,
+    /// If true, the number of actions generated and eliminated by each action set is counted
+    /// during exploration and reported in [`Solution::action_stats`].
+    pub action_stats: bool,
+    /// Times in the graph are rounded to the nearest multiple of this quantum and expressed in
+    /// its units before solving, see [`Graph::quantized`].
+    ///
+    /// This coarsens the MDP, reducing the number of distinct transition times and the horizon
+    /// at the cost of accuracy. Must be positive; 1 leaves the graph unchanged.
+    /// Only applied by the solve variations, e.g., [`solve_naive`] and [`solve_custom`].
+    pub time_quantum: Time,
+    /// If false, the memory usage is not sampled during exploration, which removes its overhead
+    /// from timing measurements. [`Config::max_memory`] is not enforced and the reported maximum
+    /// memory usage is 0 in this case.
+    ///
+    /// Note that the global allocator keeps track of the allocated memory regardless, which adds
+    /// an atomic operation to each allocation. This is avoided only by disabling the
+    /// `track-memory` feature, which also makes the reported memory usage 0.
+    pub track_memory: bool,
+    /// If set, an operating cost (e.g., fuel or overtime) is added to the cost of each
+    /// transition for each team that is moving during it, per unit time.
+    ///
+    /// Hence, the total operating cost is proportional to the distance traveled by the teams in
+    /// terms of [`Graph::travel_times`], which favors fewer and shorter dispatches over faster
+    /// restoration. Must be non-negative. Rounded to an integer with `minmem` since costs are
+    /// integers.
+    pub team_move_cost: Option<f64>,
+    /// If true, exploration stops when [`Config::max_memory`] is exceeded instead of failing,
+    /// and a partial solution is returned with [`Solution::partial`] set.
+    ///
+    /// The unexplored states become terminal: like the other terminal states, each one costs the
+    /// (weighted) number of its damaged buses per unit time until the horizon, or nothing with
+    /// [`Objective::Makespan`]. Damaged buses are never energized, so this doesn't exceed the
+    /// cost of the state under any policy, and the values of a partial solution are lower bounds
+    /// of the values of the complete one with the same horizon. This doesn't hold with
+    /// [`Graph::deadlines`], which are also charged for the unknown buses of the unexplored
+    /// states as if they were never energized.
+    pub allow_partial: bool,
+    /// If true, the team actions that produce the transitions of each state are stored in
+    /// [`Solution::actions`], which allows decoding the optimal actions into team destinations.
+    pub store_actions: bool,
+    /// If false, the progress condition (at least one team is going to an energizable bus) is
+    /// not enforced by the action sets, so the full action space including the actions in which
+    /// all teams wait is explored. This is useful for verifying that the progress condition
+    /// doesn't change the optimal value.
+    ///
+    /// Without the progress condition, the teams can return to a previous state, so the state
+    /// graph may be cyclic. In that case, [`Config::horizon`] must be absolute.
+    ///
+    /// Not supported by the timed action appliers that determine the time from the arrivals or
+    /// energizations, which require progress.
+    pub enforce_progress: bool,
+    /// If false, only the value of the optimal action is kept for each state in
+    /// [`Solution::values`], which reduces the memory usage of the solution when the values of
+    /// the suboptimal actions are not needed. [`io::TeamSolution::explain_state`] requires them.
+    pub store_all_action_values: bool,
+    /// If set, the outcomes of each action are approximated by sampling at most this many
+    /// outcomes instead of enumerating all energization outcomes, whose number grows
+    /// exponentially with the number of buses energized at once.
+    ///
+    /// The probabilities of the sampled outcomes are their frequencies in the samples, so they
+    /// still add up to 1. This bounds the size of the MDP, but its values are approximate.
+    /// Sampling is reproducible, see [`crate::utils::create_rng`]. Must be positive.
+    pub max_outcomes_per_action: Option<usize>,
+    /// If set, the failure probabilities of the buses change over time according to this
+    /// piecewise-constant schedule, e.g., as the weather forecast is updated.
+    ///
+    /// Each entry contains the time from which the failure probabilities apply and the failure
+    /// probability of each bus. [`Graph::pfs`] apply before the first entry. Times are measured
+    /// from the start state in the same units as the transitions (see [`Config::time_quantum`])
+    /// and must be in ascending order. The probabilities of the energization outcomes are
+    /// determined by the time at which the transition ends.
+    ///
+    /// A bus cannot become certain to fail or succeed (probability 0 or 1) unless it's certain
+    /// in [`Graph::pfs`], and vice versa, since the possible outcomes are determined from
+    /// [`Graph::pfs`]. Only supported by the timed policy synthesizers, and cannot be combined
+    /// with deadlines or [`Config::max_outcomes_per_action`].
+    pub pfs_schedule: Option<Vec<(Time, Vec<Probability>)>>,
+    /// If set, the number of indexed states and the size of the exploration frontier are
+    /// reported to this gauge during exploration, see [`ExplorationGauge`].
+    ///
+    /// Not serialized.
+    #[serde(skip)]
+    pub exploration_gauge: Option<std::sync::Arc<ExplorationGauge>>,
+    /// Objective of the policy synthesis, [`Objective::Cost`] by default.
+    ///
+    /// The costs of the transitions are replaced after the exploration for
+    /// [`Objective::Makespan`], so the values in the solution are the expected times.
+    pub objective: Objective,
 }
 
 impl Config {
@@ -127,7 +674,31 @@ impl Config {
         log::info!("Max memory is set to available RAM ({} bytes)", max_memory);
         Config {
             max_memory: max_memory as usize,
-            horizon: None,
+            ..Config::unlimited()
+        }
+    }
+
+    /// Build a new config struct without any limits.
+    pub fn unlimited() -> Config {
+        Config {
+            max_memory: usize::MAX,
+            horizon: HorizonSpec::Auto,
+            max_actions_per_state: usize::MAX,
+            max_states: usize::MAX,
+            debug_dump: None,
+            global_wait: None,
+            action_stats: false,
+            time_quantum: 1,
+            track_memory: true,
+            team_move_cost: None,
+            allow_partial: false,
+            store_actions: false,
+            enforce_progress: true,
+            store_all_action_values: true,
+            max_outcomes_per_action: None,
+            pfs_schedule: None,
+            exploration_gauge: None,
+            objective: Objective::Cost,
         }
     }
 }
@@ -138,11 +709,47 @@ impl Default for Config {
     }
 }
 
+/// Replace the cost of each transition with 1 per time unit, except for the self-transitions of
+/// the terminal states, which cost nothing. See [`Objective::Makespan`].
+fn assign_makespan_costs<TT: Transition>(transitions: &mut [Vec<Vec<TT>>]) {
+    for (i, actions) in transitions.iter_mut().enumerate() {
+        let terminal = match actions.as_slice() {
+            [action] => matches!(action.as_slice(), [t] if t.get_successor() as usize == i),
+            _ => false,
+        };
+        let cost = Cost::from(if terminal { 0u8 } else { 1u8 });
+        for t in actions.iter_mut().flatten() {
+            *t = TT::from_parts(t.get_successor(), cost, t.get_probability(), t.get_time());
+        }
+    }
+}
+
 pub fn solve_generic<'a, TT, E, AA, PS>(
     graph: &'a Graph,
     initial_teams: Vec<TeamState>,
     config: &Config,
 ) -> Result<Solution<TT>, SolveFailure>
+where
+    TT: Transition,
+    E: Explorer<'a, TT>,
+    AA: ActionApplier<TT>,
+    PS: PolicySynthesizer<TT>,
+{
+    let start_state = State::start_state(graph, initial_teams);
+    solve_generic_from_state::<TT, E, AA, PS>(graph, start_state, config)
+}
+
+/// Solve the field-teams restoration problem starting from the given state, which is not
+/// necessarily the start state of the teams, e.g., when re-solving an ongoing restoration.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(teams = start_state.teams.len()))
+)]
+pub fn solve_generic_from_state<'a, TT, E, AA, PS>(
+    graph: &'a Graph,
+    start_state: State,
+    config: &Config,
+) -> Result<Solution<TT>, SolveFailure>
 where
     TT: Transition,
     E: Explorer<'a, TT>,
@@ -151,26 +758,101 @@ where
 {
     let start_time = Instant::now();
 
+    if let Some(schedule) = &config.pfs_schedule {
+        graph
+            .validate_pfs_schedule(schedule)
+            .map_err(SolveFailure::BadInput)?;
+        if graph.deadlines.is_some() || config.max_outcomes_per_action.is_some() {
+            return Err(SolveFailure::BadInput(
+                "Failure probability schedule cannot be combined with deadlines or outcome sampling"
+                    .to_string(),
+            ));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    let exploration_span = tracing::info_span!(
+        "exploration",
+        states = tracing::field::Empty,
+        transitions = tracing::field::Empty,
+    )
+    .entered();
+
     let ExploreResult {
         bus_states,
         team_states,
-        transitions,
+        mut transitions,
         max_memory,
-    } = E::memory_limited_explore::<AA>(graph, initial_teams, config.max_memory)?;
+        action_stats,
+        partial,
+        actions,
+    } = E::memory_limited_explore_from_state::<AA>(graph, start_state, config)?;
+    if partial {
+        log::warn!("Memory limit is exceeded, the solution is partial");
+    }
+
+    if config.objective == Objective::Makespan {
+        assign_makespan_costs(&mut transitions);
+    }
 
     let generation_time: f64 = start_time.elapsed().as_secs_f64();
 
-    let auto_horizon = determine_horizon(&transitions);
-    log::info!("Automatically determined horizon: {auto_horizon}");
-    let horizon = if let Some(v) = config.horizon {
-        if auto_horizon > v {
-            log::warn!("Given horizon ({v}) is smaller than determined ({auto_horizon})");
+    #[cfg(feature = "tracing")]
+    {
+        exploration_span.record("states", transitions.len());
+        exploration_span.record("transitions", get_transition_count(&transitions));
+        drop(exploration_span);
+    }
+
+    let horizon = match (try_determine_horizon(&transitions), config.horizon) {
+        (Some(auto_horizon), horizon_spec) => {
+            log::info!("Automatically determined horizon: {auto_horizon}");
+            let horizon = horizon_spec.resolve(auto_horizon)?;
+            if auto_horizon > horizon {
+                log::warn!("Given horizon ({horizon}) is smaller than determined ({auto_horizon})");
+            }
+            horizon
+        }
+        (None, HorizonSpec::Absolute(horizon)) => {
+            log::info!("MDP state graph is cyclic, using the given horizon ({horizon})");
+            horizon
         }
-        v
+        (None, _) => {
+            return Err(SolveFailure::BadInput(
+                "MDP state graph is cyclic (e.g., the progress condition is not enforced), so \
+                the horizon must be given as an absolute value"
+                    .to_string(),
+            ))
+        }
+    };
+    let (values, policy) = if let Some(schedule) = &config.pfs_schedule {
+        let schedule = graph.get_probability_schedule(schedule, &bus_states, &transitions);
+        PS::synthesize_policy_with_schedule(&transitions, horizon, &schedule).ok_or_else(|| {
+            SolveFailure::BadInput(
+                "Failure probability schedules are not supported by the given policy synthesizer"
+                    .to_string(),
+            )
+        })?
+    } else if let Some(deadlines) = graph.get_state_deadlines(&bus_states) {
+        PS::synthesize_policy_with_deadlines(&transitions, horizon, &deadlines).ok_or_else(
+            || {
+                SolveFailure::BadInput(
+                    "Deadlines are not supported by the given policy synthesizer".to_string(),
+                )
+            },
+        )?
     } else {
-        auto_horizon
+        PS::synthesize_policy(&transitions, horizon)
+    };
+    let values = if config.store_all_action_values {
+        values
+    } else {
+        values
+            .into_iter()
+            .zip(policy.iter())
+            .map(|(values, &action)| vec![values[action as usize]])
+            .collect()
     };
-    let (values, policy) = PS::synthesize_policy(&transitions, horizon);
 
     let total_time: f64 = start_time.elapsed().as_secs_f64();
 
@@ -184,6 +866,10 @@ where
         values,
         policy,
         horizon,
+        action_stats,
+        time_quantum: config.time_quantum,
+        partial,
+        actions,
     })
 }
 
@@ -206,11 +892,29 @@ pub struct Solution<T: Transition> {
     pub transitions: Vec<Vec<Vec<T>>>,
 
     /// Value function for each action.
+    ///
+    /// If [`Config::store_all_action_values`] is false, each entry contains only the value of
+    /// the optimal action.
     pub values: Vec<Vec<Value>>,
     /// Index of optimal actions in each state.
     pub policy: Vec<ActionIndex>,
     /// Given or computed Optimization horizon.
     pub horizon: usize,
+    /// Number of actions generated and surviving in each action set by name if
+    /// [`Config::action_stats`] is set.
+    pub action_stats: Option<BTreeMap<String, ActionStats>>,
+    /// Quantum of time in which the transition times and the horizon of this solution are
+    /// expressed. See [`Config::time_quantum`].
+    pub time_quantum: Time,
+    /// True if the exploration was stopped due to the memory limit, see
+    /// [`Config::allow_partial`].
+    pub partial: bool,
+    /// Team actions of each state if [`Config::store_actions`] is set, in the same layout as the
+    /// transitions: `actions[i][j]` is the action that produces `transitions[i][j]`.
+    ///
+    /// Terminal states, global wait actions and the energization in the initial state have the
+    /// actions in which no team moves.
+    pub actions: Option<Vec<Vec<Vec<TeamAction>>>>,
 }
 
 pub trait GraphRefOrVal {
@@ -249,6 +953,10 @@ impl<T: Transition> Solution<T> {
             values,
             policy,
             horizon,
+            action_stats: _,
+            time_quantum,
+            partial,
+            actions,
         } = self;
         let (team_nodes, travel_times) = graph.get_info();
         io::TeamSolution {
@@ -263,6 +971,11 @@ impl<T: Transition> Solution<T> {
             values,
             policy,
             horizon,
+            team_names: Vec::new(),
+            node_names: Vec::new(),
+            time_quantum,
+            partial,
+            actions,
         }
     }
 
@@ -276,6 +989,7 @@ impl<T: Transition> Solution<T> {
             transitions: get_transition_count(&self.transitions),
             value: self.get_min_value(),
             horizon: self.horizon,
+            action_stats: self.action_stats.clone(),
         }
     }
 
@@ -1,6 +1,9 @@
 use super::*;
 use crate::utils::{are_indices_sorted, get_repeating_indices, sorted_intersects};
 use itertools::structs::CombinationsWithReplacement;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::sync::Arc;
 
 /// Stores action-related information for a state.
 pub struct ActionState {
@@ -12,7 +15,7 @@ pub struct ActionState {
     /// `usize::MAX` if unreachable.
     pub minbeta: Vec<BusIndex>,
     /// This vector contains the elements in the set of reachable buses with Unknown
-    /// status, beta(s), in ascending order.
+    /// status, beta(s), in ascending order, followed by the depots in [`Graph::depots`].
     target_buses: Vec<BusIndex>,
     /// Each element of this list at position i will give the smallest j for which
     /// `target_buses[i]` is an element of beta_j(s). j=0 is there's no such j.
@@ -24,13 +27,23 @@ pub struct ActionState {
     energizable_buses: Vec<BusIndex>,
     /// True if the progress condition is satisfied by an en-route team.
     progress_satisfied: bool,
+    /// False if the progress condition is disabled, see [`Config::enforce_progress`], or if it
+    /// cannot be satisfied by the ready teams due to [`Graph::team_bus_allowed`] while another
+    /// team is en-route.
+    pub(crate) progress_required: bool,
+    /// If [`Graph::team_bus_allowed`] is given, `allowed_targets[i][j]` is true if team `i` can
+    /// be dispatched to `target_buses[j]`.
+    allowed_targets: Option<Vec<Vec<bool>>>,
+    /// True for each ready team that is not allowed to be dispatched to any of the target buses
+    /// according to [`Graph::team_bus_allowed`]. Such teams wait in place.
+    waiting_teams: Vec<bool>,
 }
 
 impl State {
     /// Construct ActionState from a state and graph.
     pub fn to_action_state(self, graph: &Graph) -> ActionState {
         let minbeta = self.compute_minbeta(graph);
-        let (target_buses, target_minbeta): (Vec<BusIndex>, Vec<BusIndex>) = minbeta
+        let (mut target_buses, mut target_minbeta): (Vec<BusIndex>, Vec<BusIndex>) = minbeta
             .iter()
             .enumerate()
             .filter_map(|(i, &beta)| {
@@ -41,6 +54,9 @@ impl State {
                 }
             })
             .unzip();
+        // Depots are always reachable but never energizable.
+        target_buses.extend_from_slice(&graph.depots);
+        target_minbeta.resize(target_buses.len(), 0);
         let team_nodes = self
             .teams
             .iter()
@@ -64,6 +80,43 @@ impl State {
                 false
             }
         });
+        let allowed_targets: Option<Vec<Vec<bool>>> = graph.team_bus_allowed.as_ref().map(|_| {
+            (0..self.teams.len())
+                .map(|i| {
+                    target_buses
+                        .iter()
+                        .map(|&bus| graph.is_team_allowed(i, bus))
+                        .collect()
+                })
+                .collect()
+        });
+        let waiting_teams = match &allowed_targets {
+            Some(allowed_targets) => self
+                .teams
+                .iter()
+                .zip(allowed_targets.iter())
+                .map(|(team, allowed)| team.time == 0 && !allowed.iter().any(|&a| a))
+                .collect(),
+            None => vec![false; self.teams.len()],
+        };
+        // If no ready team is allowed on an energizable bus, the progress condition cannot be
+        // satisfied until an en-route team arrives.
+        let progress_required = match &allowed_targets {
+            Some(allowed_targets) => {
+                self.teams
+                    .iter()
+                    .zip(allowed_targets.iter())
+                    .any(|(team, allowed)| {
+                        team.time == 0
+                            && target_minbeta
+                                .iter()
+                                .zip(allowed.iter())
+                                .any(|(&beta, &is_allowed)| beta == 1 && is_allowed)
+                    })
+                    || self.teams.iter().all(|team| team.time == 0)
+            }
+            None => true,
+        };
         ActionState {
             state: self,
             minbeta,
@@ -72,10 +125,65 @@ impl State {
             team_nodes,
             energizable_buses,
             progress_satisfied,
+            progress_required,
+            allowed_targets,
+            waiting_teams,
+        }
+    }
+}
+
+impl ActionState {
+    /// Returns true if the team at the given index is ready to receive orders, i.e., it's neither
+    /// en-route nor waiting due to [`Graph::team_bus_allowed`].
+    #[inline]
+    fn is_team_ready(&self, i: usize) -> bool {
+        self.state.teams[i].time == 0 && !self.waiting_teams[i]
+    }
+
+    /// Disable the progress condition in the action sets for this state.
+    /// See [`Config::enforce_progress`].
+    pub fn disable_progress_condition(&mut self) {
+        self.progress_required = false;
+    }
+}
+
+/// Number of actions generated and surviving in an action set, counted during exploration if
+/// [`Config::action_stats`] is set.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionStats {
+    /// Number of actions received from the wrapped action set.
+    /// For the base action sets, this is the number of actions in [`NaiveActions`].
+    pub generated: usize,
+    /// Number of actions that are not eliminated.
+    pub survived: usize,
+}
+
+impl ActionStats {
+    /// Get the ratio of eliminated actions to generated actions.
+    pub fn reduction_ratio(&self) -> f64 {
+        if self.generated == 0 {
+            0.0
+        } else {
+            1.0 - (self.survived as f64 / self.generated as f64)
         }
     }
 }
 
+/// Add the given counts to the action stats if they're enabled.
+#[inline]
+fn tally(stats: Option<&Cell<ActionStats>>, generated: usize, survived: usize) {
+    if let Some(stats) = stats {
+        let ActionStats {
+            generated: g,
+            survived: s,
+        } = stats.get();
+        stats.set(ActionStats {
+            generated: g + generated,
+            survived: s + survived,
+        });
+    }
+}
+
 /// Trait that represents an iterator for feasible action set.
 /// A(s) in paper.
 pub trait ActionSet<'a> {
@@ -90,6 +198,16 @@ pub trait ActionSet<'a> {
     /// Prepare an iterator from state action info.
     fn prepare<'b>(&'b self, action_state: &'b ActionState) -> Self::IT<'b>;
 
+    /// Enable counting the generated and surviving actions in this action set and the wrapped
+    /// ones. See [`ActionStats`].
+    ///
+    /// [`NaiveActions`] doesn't eliminate any actions, so it doesn't count them.
+    fn enable_stats(&mut self) {}
+
+    /// Append the action counts of the wrapped action sets followed by this one to the given list
+    /// together with their names. Nothing is appended unless [`ActionSet::enable_stats`] is called.
+    fn collect_stats(&self, _stats: &mut Vec<(String, ActionStats)>) {}
+
     /// Return all actions in a state as a `Vec`.
     #[inline]
     fn all_actions_in_state(&self, state: &State, graph: &Graph) -> Vec<Vec<TeamAction>> {
@@ -111,12 +229,9 @@ impl<'a> NaiveIterator<'a> {
     /// Reset the iterator
     fn reset(&mut self) {
         let mut next: Option<Vec<TeamAction>> = Some(
-            self.action_state
-                .state
-                .teams
-                .iter()
-                .map(|team_state| {
-                    if team_state.time == 0 {
+            (0..self.action_state.state.teams.len())
+                .map(|i| {
+                    if self.action_state.is_team_ready(i) {
                         0
                     } else {
                         BusIndex::MAX
@@ -125,7 +240,7 @@ impl<'a> NaiveIterator<'a> {
                 .collect(),
         );
         // Ensure progress condition.
-        while next.is_some() && !self.progress_condition(next.as_ref().unwrap()) {
+        while next.is_some() && !self.is_feasible(next.as_ref().unwrap()) {
             next = self.next_action(next.unwrap());
         }
         self.next = next;
@@ -135,8 +250,8 @@ impl<'a> NaiveIterator<'a> {
     /// Returns True if actions wrapped around.
     fn next_action(&self, mut action: Vec<TeamAction>) -> Option<Vec<TeamAction>> {
         for i in 0..action.len() {
-            if self.action_state.state.teams[i].time > 0 {
-                // En-route
+            if !self.action_state.is_team_ready(i) {
+                // En-route or waiting
                 continue;
             }
             action[i] += 1;
@@ -153,11 +268,29 @@ impl<'a> NaiveIterator<'a> {
     /// Returns true if the progress condition is satisfied.
     /// Progress condition assures that at least one team is going to an energizable bus.
     fn progress_condition(&self, action: &[TeamAction]) -> bool {
-        self.action_state.progress_satisfied
+        !self.action_state.progress_required
+            || self.action_state.progress_satisfied
             || action
                 .iter()
                 .any(|&i| i != BusIndex::MAX && self.action_state.target_minbeta[i as usize] == 1)
     }
+
+    /// Returns true if each ready team is dispatched to a bus it's allowed to.
+    fn skill_condition(&self, action: &[TeamAction]) -> bool {
+        if let Some(allowed_targets) = &self.action_state.allowed_targets {
+            action
+                .iter()
+                .zip(allowed_targets.iter())
+                .all(|(&i, allowed)| i == BusIndex::MAX || allowed[i as usize])
+        } else {
+            true
+        }
+    }
+
+    /// Returns true if the given action is feasible.
+    fn is_feasible(&self, action: &[TeamAction]) -> bool {
+        self.progress_condition(action) && self.skill_condition(action)
+    }
 }
 
 impl Iterator for NaiveIterator<'_> {
@@ -173,7 +306,7 @@ impl Iterator for NaiveIterator<'_> {
                 .iter()
                 .zip(action.iter())
                 .map(|(team, &target)| {
-                    if team.time > 0 {
+                    if target == BusIndex::MAX {
                         team.index
                     } else {
                         self.action_state.target_buses[target as usize]
@@ -181,7 +314,7 @@ impl Iterator for NaiveIterator<'_> {
                 })
                 .collect();
             let mut next = self.next_action(action);
-            while next.is_some() && !self.progress_condition(next.as_ref().unwrap()) {
+            while next.is_some() && !self.is_feasible(next.as_ref().unwrap()) {
                 next = self.next_action(next.unwrap());
             }
             self.next = next;
@@ -226,6 +359,8 @@ pub struct PermutationalIterator<'a> {
     bus_combination_iter: CombinationsWithReplacement<std::vec::IntoIter<BusIndex>>,
     /// Stack of next actions from the permutations of last team-bus combination.
     next_actions: Vec<Vec<TeamAction>>,
+    /// Action counts if enabled.
+    stats: Option<&'a Cell<ActionStats>>,
 }
 
 impl<'a> PermutationalIterator<'a> {
@@ -236,10 +371,11 @@ impl<'a> PermutationalIterator<'a> {
     fn next_bus_combination(&mut self) -> bool {
         if let Some(bus_combination) = self.bus_combination_iter.next() {
             // Check progress condition
-            if !self.action_state.progress_satisfied
+            if self.action_state.progress_required
+                && !self.action_state.progress_satisfied
                 && bus_combination
                     .iter()
-                    .all(|&i| self.action_state.minbeta[i as usize] > 1)
+                    .all(|&i| self.action_state.minbeta.get(i as usize) != Some(&1))
             {
                 return self.next_bus_combination();
             }
@@ -254,10 +390,36 @@ impl<'a> PermutationalIterator<'a> {
                     if !are_indices_sorted(permutation, &repeating_indices) {
                         return false;
                     }
+                    // Remove the permutations that send a team to a bus it's not allowed to.
+                    // This must happen before comparing the permutations.
+                    if let Some(allowed_targets) = &self.action_state.allowed_targets {
+                        for (&perm_i, bus) in permutation.iter().zip(bus_combination.iter()) {
+                            let target = self
+                                .action_state
+                                .target_buses
+                                .binary_search(bus)
+                                .expect("Bus in combination is not a target");
+                            if !allowed_targets[self.ready_teams[perm_i]][target] {
+                                return false;
+                            }
+                        }
+                    }
                     true
                 })
                 .collect_vec();
 
+            // Bus assigned to each ready team in each permutation, used for breaking ties.
+            let assignments = permutations
+                .iter()
+                .map(|permutation| {
+                    let mut assignment = vec![0 as BusIndex; permutation.len()];
+                    for (&perm_i, &bus) in permutation.iter().zip(bus_combination.iter()) {
+                        assignment[perm_i] = bus;
+                    }
+                    assignment
+                })
+                .collect_vec();
+
             // Compare each permutation
             // Whether each permutation is eliminated
             let mut eliminated: Vec<bool> = vec![false; permutations.len()];
@@ -296,7 +458,15 @@ impl<'a> PermutationalIterator<'a> {
                             }
                         }
                     }
-                    if all_smaller_eq {
+                    if all_smaller_eq && all_greater_eq {
+                        // Travel times are equal, keep the lexicographically smallest assignment
+                        if assignments[i] < assignments[j] {
+                            eliminated[j] = true;
+                        } else {
+                            eliminated[i] = true;
+                            break;
+                        }
+                    } else if all_smaller_eq {
                         // All travel times in a is smaller, eliminate b
                         eliminated[j] = true;
                     } else if all_greater_eq {
@@ -313,7 +483,14 @@ impl<'a> PermutationalIterator<'a> {
                 .state
                 .teams
                 .iter()
-                .map(|s| if s.time == 0 { BusIndex::MAX } else { s.index })
+                .enumerate()
+                .map(|(i, s)| {
+                    if self.action_state.is_team_ready(i) {
+                        BusIndex::MAX
+                    } else {
+                        s.index
+                    }
+                })
                 .collect_vec();
             self.next_actions = eliminated
                 .into_iter()
@@ -344,6 +521,7 @@ impl<'a> Iterator for PermutationalIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(action) = self.next_actions.pop() {
+            tally(self.stats, 0, 1);
             Some(action)
         } else if self.next_bus_combination() {
             self.next()
@@ -359,16 +537,30 @@ impl<'a> Iterator for PermutationalIterator<'a> {
 /// the first action is eliminated. Likewise, an action with time (2, 2) would be eliminated in
 /// favor of actions with time (1, 2) or (2, 1).
 ///
-/// If both take the same amount of time, e.g., time (2, 2) and (2, 2),  then one of them is
-/// eliminated.
+/// If both take the same amount of time, e.g., time (2, 2) and (2, 2),  then the one that assigns
+/// lexicographically smaller buses to the teams (in the order of teams) is kept, e.g., (1, 2) is
+/// kept and (2, 1) is eliminated.
 pub struct PermutationalActions<'a> {
     travel_times: &'a Array2<Time>,
+    /// Action counts if enabled.
+    stats: Option<Cell<ActionStats>>,
 }
 
 impl<'a> ActionSet<'a> for PermutationalActions<'a> {
     fn setup(graph: &'a Graph) -> Self {
         Self {
             travel_times: &graph.travel_times,
+            stats: None,
+        }
+    }
+
+    fn enable_stats(&mut self) {
+        self.stats = Some(Cell::default());
+    }
+
+    fn collect_stats(&self, stats: &mut Vec<(String, ActionStats)>) {
+        if let Some(s) = &self.stats {
+            stats.push(("PermutationalActions".to_string(), s.get()));
         }
     }
 
@@ -381,7 +573,7 @@ impl<'a> ActionSet<'a> for PermutationalActions<'a> {
             .iter()
             .enumerate()
             .filter_map(|(i, t)| {
-                if t.time == 0 {
+                if action_state.is_team_ready(i) {
                     Some((i, t.index))
                 } else {
                     None
@@ -393,6 +585,11 @@ impl<'a> ActionSet<'a> for PermutationalActions<'a> {
             .clone()
             .into_iter()
             .combinations_with_replacement(ready_teams.len());
+        if self.stats.is_some() {
+            // Compare with the actions that would be generated without eliminating permutations.
+            let naive_actions = NaiveActions.prepare(action_state).count();
+            tally(self.stats.as_ref(), naive_actions, 0);
+        }
         let mut it = PermutationalIterator {
             action_state,
             travel_times: self.travel_times,
@@ -400,6 +597,7 @@ impl<'a> ActionSet<'a> for PermutationalActions<'a> {
             ready_team_nodes,
             bus_combination_iter,
             next_actions: Vec::new(),
+            stats: self.stats.as_ref(),
         };
         it.next_bus_combination();
         it
@@ -410,7 +608,7 @@ impl<'a> ActionSet<'a> for PermutationalActions<'a> {
 /// teams" condition during initialization. If the condition is met, only wait action will be
 /// issued. Otherwise, the underlying iterator will be initialized and used.
 ///
-/// NOTE: This doesn't work correctly under all conditions.
+/// See [`WaitMovingActions`] for the condition.
 pub struct WaitMovingIterator<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> {
     /// Underlying iterator.
     iter: T,
@@ -418,9 +616,8 @@ pub struct WaitMovingIterator<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> {
     waiting_state: bool,
     /// The wait action for this state if the "wait for moving teams" condition is satisfied.
     wait_action: Option<Vec<TeamAction>>,
-    /// This struct semantically stores a reference with `'a` lifetime due to wrapped
-    /// ActionSet.
-    _phantom: std::marker::PhantomData<&'a ()>,
+    /// Action counts if enabled.
+    stats: Option<&'a Cell<ActionStats>>,
 }
 
 impl<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> Iterator for WaitMovingIterator<'a, T> {
@@ -430,14 +627,30 @@ impl<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> Iterator for WaitMovingIte
         if self.waiting_state {
             self.wait_action.take()
         } else {
-            self.iter.next()
+            let action = self.iter.next();
+            if action.is_some() {
+                tally(self.stats, 1, 1);
+            }
+            action
         }
     }
 }
 
-/// NOTE: This doesn't work correctly under all conditions.
+/// A struct that wraps another action set definition and issues only the wait action if the
+/// "wait for moving teams" condition is satisfied:
+/// - Progress condition is satisfied by an en-route team.
+/// - Each team is either en-route or waiting on a reachable bus with Unknown status.
+/// - Each energizable bus (i.e., in `beta_1` set) is targeted by an en-route team.
+///
+/// The last condition ensures that no team can energize a bus before the en-route teams arrive
+/// by moving instead of waiting.
+///
+/// NOTE: This is a heuristic. Waiting may still be suboptimal, e.g., when the bus on which a team
+/// waits is likely to be damaged.
 pub struct WaitMovingActions<'a, T: ActionSet<'a>> {
     base: T,
+    /// Action counts if enabled.
+    stats: Option<Cell<ActionStats>>,
     /// This struct semantically stores a reference with `'a` lifetime due to wrapped
     /// ActionSet.
     _phantom: std::marker::PhantomData<&'a ()>,
@@ -447,10 +660,23 @@ impl<'a, T: ActionSet<'a>> ActionSet<'a> for WaitMovingActions<'a, T> {
     fn setup(graph: &'a Graph) -> Self {
         Self {
             base: T::setup(graph),
+            stats: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    fn enable_stats(&mut self) {
+        self.base.enable_stats();
+        self.stats = Some(Cell::default());
+    }
+
+    fn collect_stats(&self, stats: &mut Vec<(String, ActionStats)>) {
+        self.base.collect_stats(stats);
+        if let Some(s) = &self.stats {
+            stats.push(("WaitMovingActions".to_string(), s.get()));
+        }
+    }
+
     type IT<'b> = WaitMovingIterator<'b, T::IT<'b>> where Self: 'b, T: 'b;
 
     fn prepare<'b>(&'b self, action_state: &'b ActionState) -> Self::IT<'b> {
@@ -460,27 +686,40 @@ impl<'a, T: ActionSet<'a>> ActionSet<'a> for WaitMovingActions<'a, T> {
             .iter()
             .filter_map(|t| {
                 if t.time == 0 {
-                    if (t.index as usize) >= action_state.state.buses.len()
-                        || action_state.state.buses[t.index as usize] != BusState::Unknown
-                    {
-                        None
-                    } else {
-                        Some(t.index)
+                    // Waiting on an unreachable bus is not a feasible action.
+                    match action_state.minbeta.get(t.index as usize) {
+                        Some(&beta) if beta != 0 && beta != BusIndex::MAX => Some(t.index),
+                        _ => None,
                     }
                 } else {
                     Some(t.index)
                 }
             })
             .collect_vec();
-        let waiting_state =
-            action_state.progress_satisfied && action.len() == action_state.state.teams.len();
+        let waiting_state = action_state.progress_satisfied
+            && action.len() == action_state.state.teams.len()
+            && action_state.energizable_buses.iter().all(|bus| {
+                action_state
+                    .state
+                    .teams
+                    .iter()
+                    .any(|t| t.time > 0 && t.index == *bus)
+            });
         let iter = self.base.prepare(action_state);
+        if waiting_state && self.stats.is_some() {
+            // The underlying iterator won't be used, count the actions that it would generate.
+            tally(
+                self.stats.as_ref(),
+                self.base.prepare(action_state).count(),
+                1,
+            );
+        }
         let wait_action = if waiting_state { Some(action) } else { None };
         WaitMovingIterator {
             iter,
             waiting_state,
             wait_action,
-            _phantom: std::marker::PhantomData,
+            stats: self.stats.as_ref(),
         }
     }
 }
@@ -498,6 +737,8 @@ pub struct EnergizedOnWayIterator<'a, T: Iterator<Item = Vec<TeamAction>> + Size
     /// order.
     on_way: &'a Array2<Vec<BusIndex>>,
     action_state: &'a ActionState,
+    /// Action counts if enabled.
+    stats: Option<&'a Cell<ActionStats>>,
 }
 
 impl<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> Iterator for EnergizedOnWayIterator<'a, T> {
@@ -505,6 +746,7 @@ impl<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> Iterator for EnergizedOnWa
 
     fn next(&mut self) -> Option<Self::Item> {
         while let Some(action) = self.iter.next() {
+            tally(self.stats, 1, 0);
             let on_way: bool =
                 self.action_state
                     .team_nodes
@@ -523,6 +765,7 @@ impl<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> Iterator for EnergizedOnWa
             if on_way {
                 continue;
             }
+            tally(self.stats, 0, 1);
             return Some(action);
         }
         None
@@ -535,7 +778,9 @@ pub struct FilterEnergizedOnWay<'a, T: ActionSet<'a>> {
     base: T,
     /// For each path i to j, there's an entry for the list of components on that path in ascending
     /// order.
-    on_way: Array2<Vec<BusIndex>>,
+    on_way: Arc<Array2<Vec<BusIndex>>>,
+    /// Action counts if enabled.
+    stats: Option<Cell<ActionStats>>,
     /// This struct semantically stores a reference with `'a` lifetime due to wrapped
     /// ActionSet.
     _phantom: std::marker::PhantomData<&'a ()>,
@@ -543,14 +788,27 @@ pub struct FilterEnergizedOnWay<'a, T: ActionSet<'a>> {
 
 impl<'a, T: ActionSet<'a>> ActionSet<'a> for FilterEnergizedOnWay<'a, T> {
     fn setup(graph: &'a Graph) -> Self {
-        let on_way = graph.get_components_on_way();
+        let on_way = graph.components_on_way();
         Self {
             base: T::setup(graph),
             on_way,
+            stats: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    fn enable_stats(&mut self) {
+        self.base.enable_stats();
+        self.stats = Some(Cell::default());
+    }
+
+    fn collect_stats(&self, stats: &mut Vec<(String, ActionStats)>) {
+        self.base.collect_stats(stats);
+        if let Some(s) = &self.stats {
+            stats.push(("FilterEnergizedOnWay".to_string(), s.get()));
+        }
+    }
+
     type IT<'b> = EnergizedOnWayIterator<'b, T::IT<'b>> where T: 'b, Self: 'b;
 
     fn prepare<'b>(&'b self, action_state: &'b ActionState) -> Self::IT<'b> {
@@ -558,6 +816,7 @@ impl<'a, T: ActionSet<'a>> ActionSet<'a> for FilterEnergizedOnWay<'a, T> {
             iter: self.base.prepare(action_state),
             action_state,
             on_way: &self.on_way,
+            stats: self.stats.as_ref(),
         }
     }
 }
@@ -572,27 +831,43 @@ pub struct FilterOnWay<'a, T: ActionSet<'a>> {
     base: T,
     /// For each path i to j, there's an entry for the list of components on that path in ascending
     /// order.
-    on_way: Array2<Vec<BusIndex>>,
+    on_way: Arc<Array2<Vec<BusIndex>>>,
+    /// Action counts if enabled.
+    stats: Option<Cell<ActionStats>>,
     /// This struct semantically stores a reference with `'a` lifetime due to wrapped ActionSet.
     _phantom: std::marker::PhantomData<&'a ()>,
 }
 
 impl<'a, T: ActionSet<'a>> ActionSet<'a> for FilterOnWay<'a, T> {
     fn setup(graph: &'a Graph) -> Self {
-        let on_way = graph.get_components_on_way();
+        let on_way = graph.components_on_way();
         Self {
             base: T::setup(graph),
             on_way,
+            stats: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    fn enable_stats(&mut self) {
+        self.base.enable_stats();
+        self.stats = Some(Cell::default());
+    }
+
+    fn collect_stats(&self, stats: &mut Vec<(String, ActionStats)>) {
+        self.base.collect_stats(stats);
+        if let Some(s) = &self.stats {
+            stats.push(("FilterOnWay".to_string(), s.get()));
+        }
+    }
+
     type IT<'b> = std::vec::IntoIter<Vec<TeamAction>>
     where
         Self: 'b;
 
     fn prepare<'b>(&'b self, action_state: &'b ActionState) -> Self::IT<'b> {
         let actions = self.base.prepare(action_state).collect_vec();
+        let generated = actions.len();
         let mut eliminated = vec![false; actions.len()];
         let team_nodes = &action_state.team_nodes;
 
@@ -640,11 +915,158 @@ impl<'a, T: ActionSet<'a>> ActionSet<'a> for FilterOnWay<'a, T> {
             }
         }
 
-        actions
+        let actions = actions
             .into_iter()
             .zip(eliminated)
             .filter_map(|(action, e)| if e { None } else { Some(action) })
-            .collect_vec()
-            .into_iter()
+            .collect_vec();
+        tally(self.stats.as_ref(), generated, actions.len());
+        actions.into_iter()
+    }
+}
+
+/// An action iterator that wraps around another action iterator and eliminates the actions that
+/// dispatch a team to a bus it's not allowed to.
+///
+/// See [`FilterTeamSkill`].
+pub struct TeamSkillIterator<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> {
+    /// Underlying iterator.
+    iter: T,
+    action_state: &'a ActionState,
+    graph: &'a Graph,
+    /// Action counts if enabled.
+    stats: Option<&'a Cell<ActionStats>>,
+}
+
+impl<'a, T: Iterator<Item = Vec<TeamAction>> + Sized> Iterator for TeamSkillIterator<'a, T> {
+    type Item = Vec<TeamAction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for action in self.iter.by_ref() {
+            tally(self.stats, 1, 0);
+            let allowed = self
+                .action_state
+                .state
+                .teams
+                .iter()
+                .zip(action.iter())
+                .enumerate()
+                .all(|(i, (team, &target))| {
+                    team.time > 0 || target == team.index || self.graph.is_team_allowed(i, target)
+                });
+            if allowed {
+                tally(self.stats, 0, 1);
+                return Some(action);
+            }
+        }
+        None
+    }
+}
+
+/// A struct that wraps another action set definition and eliminates the actions that dispatch a
+/// team to a bus it's not allowed to according to [`Graph::team_bus_allowed`].
+///
+/// [`NaiveActions`] and [`PermutationalActions`] already respect [`Graph::team_bus_allowed`].
+/// This is intended for the action sets that don't.
+pub struct FilterTeamSkill<'a, T: ActionSet<'a>> {
+    base: T,
+    graph: &'a Graph,
+    /// Action counts if enabled.
+    stats: Option<Cell<ActionStats>>,
+}
+
+impl<'a, T: ActionSet<'a>> ActionSet<'a> for FilterTeamSkill<'a, T> {
+    fn setup(graph: &'a Graph) -> Self {
+        Self {
+            base: T::setup(graph),
+            graph,
+            stats: None,
+        }
+    }
+
+    fn enable_stats(&mut self) {
+        self.base.enable_stats();
+        self.stats = Some(Cell::default());
+    }
+
+    fn collect_stats(&self, stats: &mut Vec<(String, ActionStats)>) {
+        self.base.collect_stats(stats);
+        if let Some(s) = &self.stats {
+            stats.push(("FilterTeamSkill".to_string(), s.get()));
+        }
+    }
+
+    type IT<'b> = TeamSkillIterator<'b, T::IT<'b>> where T: 'b, Self: 'b;
+
+    fn prepare<'b>(&'b self, action_state: &'b ActionState) -> Self::IT<'b> {
+        TeamSkillIterator {
+            iter: self.base.prepare(action_state),
+            action_state,
+            graph: self.graph,
+            stats: self.stats.as_ref(),
+        }
+    }
+}
+
+/// A struct that wraps another action set definition and sorts its actions so that the ones that
+/// are likely to be optimal come first. It doesn't eliminate any actions.
+///
+/// Actions are sorted by the number of ready teams that are not dispatched to an energizable bus
+/// (i.e., in `beta_1` set), then by the total dispatch time of the ready teams. The order of the
+/// actions with equal keys is preserved.
+///
+/// This doesn't change the optimal value, but the states reached by the better actions are
+/// explored first, which improves the partial solutions when the exploration is truncated, e.g.,
+/// by [`Config::max_states`].
+pub struct SortedActions<'a, T: ActionSet<'a>> {
+    base: T,
+    graph: &'a Graph,
+}
+
+impl<'a, T: ActionSet<'a>> SortedActions<'a, T> {
+    /// Get the key by which the given action is sorted, smaller is better.
+    fn sort_key(&self, action_state: &ActionState, action: &[TeamAction]) -> (usize, Time) {
+        let mut idle_teams = 0;
+        let mut total_time = 0;
+        for (team, &target) in action_state.state.teams.iter().zip(action.iter()) {
+            if team.time > 0 {
+                continue;
+            }
+            if action_state.minbeta.get(target as usize) != Some(&1) {
+                idle_teams += 1;
+            }
+            total_time = self
+                .graph
+                .dispatch_time(team.index, target)
+                .saturating_add(total_time);
+        }
+        (idle_teams, total_time)
+    }
+}
+
+impl<'a, T: ActionSet<'a>> ActionSet<'a> for SortedActions<'a, T> {
+    fn setup(graph: &'a Graph) -> Self {
+        Self {
+            base: T::setup(graph),
+            graph,
+        }
+    }
+
+    fn enable_stats(&mut self) {
+        self.base.enable_stats();
+    }
+
+    fn collect_stats(&self, stats: &mut Vec<(String, ActionStats)>) {
+        self.base.collect_stats(stats);
+    }
+
+    type IT<'b> = std::vec::IntoIter<Vec<TeamAction>>
+    where
+        Self: 'b;
+
+    fn prepare<'b>(&'b self, action_state: &'b ActionState) -> Self::IT<'b> {
+        let mut actions = self.base.prepare(action_state).collect_vec();
+        actions.sort_by_cached_key(|action| self.sort_key(action_state, action));
+        actions.into_iter()
     }
 }
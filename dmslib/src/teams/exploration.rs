@@ -1,12 +1,65 @@
-use crate::ALLOCATOR;
-
 use super::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Gauge of the exploration progress that can be polled cheaply from other threads, e.g., to
+/// report how the state space grows while a problem is being solved.
+///
+/// Updated by the explorer after each explored state if it's given in
+/// [`Config::exploration_gauge`]. If the same gauge is shared by concurrent explorations, it
+/// reflects the latest update.
+#[derive(Debug, Default)]
+pub struct ExplorationGauge {
+    /// Number of states indexed so far.
+    indexed_states: AtomicUsize,
+    /// Number of indexed states that are not explored yet.
+    frontier_size: AtomicUsize,
+}
+
+impl ExplorationGauge {
+    /// Create a gauge with zero states.
+    pub fn new() -> ExplorationGauge {
+        ExplorationGauge::default()
+    }
+
+    /// Get the number of states indexed so far. At the end of the exploration, this is the
+    /// number of states in the solution.
+    pub fn indexed_states(&self) -> usize {
+        self.indexed_states.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of indexed states that are waiting to be explored.
+    pub fn frontier_size(&self) -> usize {
+        self.frontier_size.load(Ordering::Relaxed)
+    }
+
+    /// Update the gauge given the number of indexed and explored states.
+    #[inline]
+    pub(crate) fn update(&self, indexed_states: usize, explored_states: usize) {
+        self.indexed_states.store(indexed_states, Ordering::Relaxed);
+        self.frontier_size
+            .store(indexed_states - explored_states, Ordering::Relaxed);
+    }
+}
+
+/// Gauges are compared by identity so that [`Config`] can be compared.
+impl PartialEq for ExplorationGauge {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
 
 pub struct ExploreResult<TT: Transition> {
     pub bus_states: Array2<BusState>,
     pub team_states: Array2<TeamState>,
     pub transitions: Vec<Vec<Vec<TT>>>,
     pub max_memory: usize,
+    /// See [`Config::action_stats`].
+    pub action_stats: Option<BTreeMap<String, ActionStats>>,
+    /// True if the exploration was stopped due to the memory limit, see
+    /// [`Config::allow_partial`].
+    pub partial: bool,
+    /// See [`Solution::actions`].
+    pub actions: Option<Vec<Vec<Vec<TeamAction>>>>,
 }
 
 /// Generic trait for the functions that explore the actions of a given state.
@@ -16,19 +69,67 @@ pub trait Explorer<'a, TT: Transition> {
         graph: &'a Graph,
         teams: Vec<TeamState>,
     ) -> ExploreResult<TT> {
-        Self::memory_limited_explore::<AA>(graph, teams, usize::MAX).unwrap()
+        Self::memory_limited_explore::<AA>(graph, teams, &Config::unlimited()).unwrap()
     }
 
     /// Explore the possible states starting from the given team state.
     ///
-    /// When the memory usage reported by global allocator exceeds the limit,
+    /// When the memory usage reported by global allocator exceeds [`Config::max_memory`],
     /// [`SolveFailure::OutOfMemory`] will be returned;
+    /// When the number of actions in a state exceeds [`Config::max_actions_per_state`] or the
+    /// number of states exceeds [`Config::max_states`], [`SolveFailure::TooManyActions`] or
+    /// [`SolveFailure::TooManyStates`] will be returned, respectively.
     fn memory_limited_explore<AA: ActionApplier<TT>>(
         graph: &'a Graph,
         teams: Vec<TeamState>,
-        memory_limit: usize,
+        config: &Config,
+    ) -> Result<ExploreResult<TT>, SolveFailure> {
+        Self::memory_limited_explore_from_state::<AA>(
+            graph,
+            State::start_state(graph, teams),
+            config,
+        )
+    }
+
+    /// Explore the possible states starting from the given state, which is not necessarily the
+    /// start state of the teams, e.g., when re-solving an ongoing restoration.
+    ///
+    /// Limits are handled in the same way as [`Explorer::memory_limited_explore`].
+    fn memory_limited_explore_from_state<AA: ActionApplier<TT>>(
+        graph: &'a Graph,
+        start_state: State,
+        config: &Config,
     ) -> Result<ExploreResult<TT>, SolveFailure>;
+
+    /// Explore the possible states starting from the given team state and return the number of
+    /// states without storing the transitions.
+    ///
+    /// Limits are handled in the same way as [`Explorer::memory_limited_explore`].
+    fn memory_limited_count_states<AA: ActionApplier<TT>>(
+        graph: &'a Graph,
+        teams: Vec<TeamState>,
+        config: &Config,
+    ) -> Result<usize, SolveFailure>;
+}
+
+/// Get an iterator over the states reachable from the start state of given teams without storing
+/// the transitions.
+///
+/// The states are explored with the given action set `AI` and action applier `AA`, yielding the
+/// same states as [`Explorer::explore`] in the same order, or the error that stops the
+/// exploration, see [`ReachableStates`].
+pub fn reachable_states<'a, TT, AI, SI, AA>(
+    graph: &'a Graph,
+    initial_teams: Vec<TeamState>,
+) -> Result<ReachableStates<'a, TT, AI, SI, AA>, SolveFailure>
+where
+    TT: Transition,
+    AI: ActionSet<'a>,
+    SI: StateIndexer,
+    AA: ActionApplier<TT>,
+{
+    ReachableStates::new(graph, initial_teams)
 }
 
 mod naive;
-pub use naive::NaiveExplorer;
+pub use naive::{NaiveExplorer, ReachableStates};
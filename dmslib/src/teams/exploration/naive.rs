@@ -1,4 +1,22 @@
 use super::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::Arc;
+
+/// Add the operating cost of the given number of moving teams to the cost of a state.
+///
+/// Costs are integers with `minmem`, so the result is rounded in that case.
+#[inline]
+#[allow(clippy::unnecessary_cast)]
+fn add_move_cost(cost: Cost, move_cost: f64, moving_teams: usize) -> Cost {
+    let total = (cost as f64) + move_cost * (moving_teams as f64);
+    #[cfg(feature = "minmem")]
+    let total = total.round();
+    total as Cost
+}
+
+/// Transitions of each action in a state, and the actions themselves if they are stored.
+type ExploredActions<TT> = (Vec<Vec<TT>>, Vec<Vec<TeamAction>>);
 
 /// Naive action explorer.
 pub struct NaiveExplorer<'a, TT: Transition, AI: ActionSet<'a>, SI: StateIndexer> {
@@ -12,42 +30,193 @@ pub struct NaiveExplorer<'a, TT: Transition, AI: ActionSet<'a>, SI: StateIndexer
     /// - `transitions[i]`: Actions of state i
     /// - `transitions[i][j]`: Transitions of action j in state i
     transitions: Vec<Vec<Vec<TT>>>,
+    /// If false, transitions are discarded after the successor states are indexed.
+    store_transitions: bool,
+    /// Maximum number of actions in a state.
+    max_actions: usize,
+    /// Maximum number of states.
+    max_states: usize,
+    /// If set, explored states and their transitions are written to this.
+    debug_dump: Option<BufWriter<File>>,
+    /// Duration of the global wait action if enabled.
+    global_wait: Option<Time>,
+    /// Operating cost of each moving team per unit time if enabled.
+    team_move_cost: Option<f64>,
+    /// Team actions of each state in the same layout as `transitions` if
+    /// [`Config::store_actions`] is set.
+    actions: Option<Vec<Vec<Vec<TeamAction>>>>,
+    /// See [`Config::enforce_progress`].
+    enforce_progress: bool,
+    /// Maximum number of outcomes per action and the random number generator for sampling
+    /// them if [`Config::max_outcomes_per_action`] is set.
+    outcome_sampling: Option<(usize, rand::rngs::StdRng)>,
+    /// See [`Config::exploration_gauge`].
+    gauge: Option<Arc<ExplorationGauge>>,
 }
 
 impl<'a, TT: Transition, AI: ActionSet<'a>, SI: StateIndexer> NaiveExplorer<'a, TT, AI, SI> {
     /// Explore the actions and transitions of a state at the given index in the state indexer.
     #[inline]
-    fn explore_state<AA: ActionApplier<TT>>(&mut self, input: (usize, State)) {
+    fn explore_state<AA: ActionApplier<TT>>(
+        &mut self,
+        input: (usize, State),
+    ) -> Result<(), SolveFailure> {
         let (index, state) = input;
-        let cost = state.get_cost();
+        let cost = state.get_cost(self.graph);
         debug_assert_eq!(
             state.energize(self.graph),
             None,
             "Energization succeeded at the start of a non-initial state"
         );
-        let action_transitions: Vec<Vec<TT>> = if state.is_terminal(self.graph) {
-            vec![vec![TT::terminal_transition(index as StateIndex, cost)]]
+        let state_string = self.debug_dump.as_ref().map(|_| state.to_string());
+        let (action_transitions, team_actions) = if state.is_terminal(self.graph) {
+            (
+                vec![vec![TT::terminal_transition(index as StateIndex, cost)]],
+                self.no_move_actions(&state),
+            )
         } else {
-            let state = state.to_action_state(self.graph);
-            self.iterator
-                .prepare(&state)
-                .map(|action: Vec<TeamAction>| -> Vec<TT> {
-                    AA::apply(&state, cost, self.graph, &action)
-                        .into_iter()
-                        .map(|(mut transition, successor_state)| {
-                            // Index the successor states
-                            let successor_index = self.states.index_state(successor_state);
-                            transition.set_successor(successor_index as StateIndex);
-                            transition
-                        })
-                        .collect()
-                })
-                .collect()
+            self.explore_actions::<AA>(index, state, cost)?
         };
+        if let Some(state_string) = state_string {
+            self.dump(index, state_string, &action_transitions)?;
+        }
+        self.store(index, action_transitions, team_actions);
+        Ok(())
+    }
+
+    /// Get the single action in which no team moves, i.e., each team continues to its current
+    /// destination, if [`Config::store_actions`] is set. Otherwise, returns an empty vector.
+    #[inline]
+    fn no_move_actions(&self, state: &State) -> Vec<Vec<TeamAction>> {
+        if self.actions.is_some() {
+            vec![state.teams.iter().map(|team| team.index).collect()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Write the given state and the transitions of its actions to the debug dump as a line.
+    fn dump(
+        &mut self,
+        index: usize,
+        state_string: String,
+        action_transitions: &[Vec<TT>],
+    ) -> Result<(), SolveFailure> {
+        let file = self.debug_dump.as_mut().expect("Debug dump is not set");
+        let transitions = serde_json::to_string(action_transitions)
+            .map_err(|e| SolveFailure::Io(format!("Cannot serialize transitions: {e}")))?;
+        writeln!(file, "{index} {state_string} {transitions}")
+            .map_err(|e| SolveFailure::Io(format!("Cannot write debug dump: {e}")))
+    }
+
+    /// Apply each action of the given non-terminal state and index the successor states.
+    ///
+    /// The global wait action is added last if it's enabled and available in this state.
+    ///
+    /// Returns the transitions of each action, and the actions themselves if
+    /// [`Config::store_actions`] is set (empty otherwise).
+    /// Returns [`SolveFailure::TooManyActions`] or [`SolveFailure::TooManyStates`] if the number
+    /// of actions or states exceeds the limit, and [`SolveFailure::BadInput`] if no action is
+    /// feasible in the state.
+    #[inline]
+    fn explore_actions<AA: ActionApplier<TT>>(
+        &mut self,
+        index: usize,
+        state: State,
+        cost: Cost,
+    ) -> Result<ExploredActions<TT>, SolveFailure> {
+        let wait = self.global_wait.filter(|_| state.is_wait_available());
+        let store_actions = self.actions.is_some();
+        let mut team_actions: Vec<Vec<TeamAction>> = Vec::new();
+        let mut state = state.to_action_state(self.graph);
+        if !self.enforce_progress {
+            state.disable_progress_condition();
+        }
+        let max_states = self.max_states;
+        let mut action_transitions: Vec<Vec<TT>> = Vec::new();
+        for action in self.iterator.prepare(&state) {
+            if action_transitions.len() >= self.max_actions {
+                return Err(SolveFailure::TooManyActions {
+                    limit: self.max_actions,
+                });
+            }
+            let action_cost = match self.team_move_cost {
+                Some(move_cost) => add_move_cost(
+                    cost,
+                    move_cost,
+                    moving_team_count(self.graph, &state.state.teams, &action),
+                ),
+                None => cost,
+            };
+            let mut outcomes = AA::apply(&state, action_cost, self.graph, &action)?;
+            if let Some((max_outcomes, rng)) = self.outcome_sampling.as_mut() {
+                outcomes = sample_outcomes(outcomes, *max_outcomes, rng);
+            }
+            action_transitions.push(
+                outcomes
+                    .into_iter()
+                    .map(|(mut transition, successor_state)| {
+                        // Index the successor states
+                        let successor_index = self.states.index_state(successor_state);
+                        if successor_index >= max_states {
+                            return Err(SolveFailure::TooManyStates {
+                                count: successor_index + 1,
+                                limit: max_states,
+                            });
+                        }
+                        transition.set_successor(successor_index as StateIndex);
+                        Ok(transition)
+                    })
+                    .collect::<Result<_, _>>()?,
+            );
+            if store_actions {
+                team_actions.push(action);
+            }
+        }
+        if let Some(time) = wait {
+            action_transitions.push(vec![TT::wait_transition(index as StateIndex, cost, time)]);
+            team_actions.extend(self.no_move_actions(&state.state));
+        }
+        if action_transitions.is_empty() {
+            return Err(SolveFailure::BadInput(format!(
+                "No feasible actions in non-terminal state {}",
+                state.state
+            )));
+        }
+        Ok((action_transitions, team_actions))
+    }
+
+    /// Report the number of indexed states and the given number of explored states to the gauge
+    /// if [`Config::exploration_gauge`] is set.
+    #[inline]
+    fn update_gauge(&self, explored_states: usize) {
+        if let Some(gauge) = &self.gauge {
+            gauge.update(self.states.get_state_count(), explored_states);
+        }
+    }
+
+    /// Store the transitions of the state at the given index if `store_transitions` is set, and
+    /// its team actions if [`Config::store_actions`] is set.
+    #[inline]
+    fn store(
+        &mut self,
+        index: usize,
+        action_transitions: Vec<Vec<TT>>,
+        team_actions: Vec<Vec<TeamAction>>,
+    ) {
+        if !self.store_transitions {
+            return;
+        }
         if self.transitions.len() <= index {
             self.transitions.resize_with(index + 1, Default::default);
         }
         self.transitions[index] = action_transitions;
+        if let Some(actions) = self.actions.as_mut() {
+            if actions.len() <= index {
+                actions.resize_with(index + 1, Default::default);
+            }
+            actions[index] = team_actions;
+        }
     }
 
     /// Explore the actions and transitions of the initial state.
@@ -56,103 +225,310 @@ impl<'a, TT: Transition, AI: ActionSet<'a>, SI: StateIndexer> NaiveExplorer<'a,
     /// state without team movement. Normally, this is not the case since all energizations are
     /// attempted after each transition.
     #[inline]
-    fn explore_initial<AA: ActionApplier<TT>>(&mut self, input: (usize, State)) {
+    fn explore_initial<AA: ActionApplier<TT>>(
+        &mut self,
+        input: (usize, State),
+    ) -> Result<(), SolveFailure> {
         let (index, state) = input;
-        let cost = state.get_cost();
-        let action_transitions: Vec<Vec<TT>> = if state.is_terminal(self.graph) {
-            vec![vec![TT::terminal_transition(index as StateIndex, cost)]]
+        let cost = state.get_cost(self.graph);
+        let state_string = self.debug_dump.as_ref().map(|_| state.to_string());
+        let (action_transitions, team_actions) = if state.is_terminal(self.graph) {
+            (
+                vec![vec![TT::terminal_transition(index as StateIndex, cost)]],
+                self.no_move_actions(&state),
+            )
         } else if let Some(bus_outcomes) = state.energize(self.graph) {
-            vec![bus_outcomes
+            let mut outcomes: Vec<(TT, State)> = bus_outcomes
                 .into_iter()
                 .map(|(p, bus_state)| {
                     let successor_state = State {
                         teams: state.teams.clone(),
                         buses: bus_state,
                     };
+                    (
+                        TT::time1_transition(StateIndex::MAX, cost, p),
+                        successor_state,
+                    )
+                })
+                .collect();
+            if let Some((max_outcomes, rng)) = self.outcome_sampling.as_mut() {
+                outcomes = sample_outcomes(outcomes, *max_outcomes, rng);
+            }
+            let transitions = vec![outcomes
+                .into_iter()
+                .map(|(mut transition, successor_state)| {
                     let successor_index = self.states.index_state(successor_state);
-                    TT::time1_transition(successor_index as StateIndex, cost, p)
+                    if successor_index >= self.max_states {
+                        return Err(SolveFailure::TooManyStates {
+                            count: successor_index + 1,
+                            limit: self.max_states,
+                        });
+                    }
+                    transition.set_successor(successor_index as StateIndex);
+                    Ok(transition)
                 })
-                .collect()]
+                .collect::<Result<_, _>>()?];
+            (transitions, self.no_move_actions(&state))
         } else {
-            let state = state.to_action_state(self.graph);
-            self.iterator
-                .prepare(&state)
-                .map(|action| {
-                    AA::apply(&state, cost, self.graph, &action)
-                        .into_iter()
-                        .map(|(mut transition, successor_state)| {
-                            // Index the successor states
-                            let successor_index = self.states.index_state(successor_state);
-                            transition.set_successor(successor_index as StateIndex);
-                            transition
-                        })
-                        .collect()
-                })
-                .collect()
+            self.explore_actions::<AA>(index, state, cost)?
         };
-        if self.transitions.len() <= index {
-            self.transitions.resize_with(index + 1, Default::default);
+        if let Some(state_string) = state_string {
+            self.dump(index, state_string, &action_transitions)?;
         }
-        self.transitions[index] = action_transitions;
+        self.store(index, action_transitions, team_actions);
+        Ok(())
     }
 }
 
-impl<'a, TT: Transition, AI: ActionSet<'a>, SI: StateIndexer> Explorer<'a, TT>
-    for NaiveExplorer<'a, TT, AI, SI>
-{
-    fn memory_limited_explore<AA: ActionApplier<TT>>(
+impl<'a, TT: Transition, AI: ActionSet<'a>, SI: StateIndexer> NaiveExplorer<'a, TT, AI, SI> {
+    /// Construct an explorer with the given start state indexed.
+    fn new(
         graph: &'a Graph,
-        teams: Vec<TeamState>,
-        memory_limit: usize,
-    ) -> Result<ExploreResult<TT>, SolveFailure> {
-        const MEMORY_SAMPLE_PERIOD: usize = 2_usize.pow(15);
-        // NOTE: Previously, initail memory usage was subtracted from the currently allocated.
-        // However, in some cases it caused underflow due to memory usage approximation errors.
-        let mut max_memory: usize = 0;
+        start_state: State,
+        store_transitions: bool,
+        config: &Config,
+    ) -> Result<Self, SolveFailure> {
+        start_state
+            .validate(graph)
+            .map_err(SolveFailure::BadInput)?;
+        if let Some(move_cost) = config.team_move_cost {
+            if !(move_cost.is_finite() && move_cost >= 0.0) {
+                return Err(SolveFailure::BadInput(format!(
+                    "Team move cost must be a non-negative number, got {move_cost}"
+                )));
+            }
+        }
+        if config.max_outcomes_per_action == Some(0) {
+            return Err(SolveFailure::BadInput(
+                "Maximum number of outcomes per action must be positive".to_string(),
+            ));
+        }
+        let debug_dump = match &config.debug_dump {
+            Some(path) => Some(BufWriter::new(File::create(path).map_err(|e| {
+                SolveFailure::Io(format!("Cannot create debug dump file: {e}"))
+            })?)),
+            None => None,
+        };
 
+        let mut iterator = AI::setup(graph);
+        if config.action_stats {
+            iterator.enable_stats();
+        }
         let mut explorer = NaiveExplorer {
-            iterator: AI::setup(graph),
+            iterator,
             graph,
-            states: SI::new(graph, &teams),
+            states: SI::new(graph, &start_state.teams),
             transitions: Vec::new(),
+            store_transitions,
+            max_actions: config.max_actions_per_state,
+            max_states: config.max_states,
+            debug_dump,
+            global_wait: config.global_wait,
+            team_move_cost: config.team_move_cost,
+            actions: (store_transitions && config.store_actions).then(Vec::new),
+            enforce_progress: config.enforce_progress,
+            outcome_sampling: config
+                .max_outcomes_per_action
+                .map(|max_outcomes| (max_outcomes, crate::utils::create_rng(0))),
+            gauge: config.exploration_gauge.clone(),
         };
-        explorer
-            .states
-            .index_state(State::start_state(graph, teams));
+        explorer.states.index_state(start_state);
+        Ok(explorer)
+    }
+
+    /// Construct an explorer and explore all states reachable from the given start state.
+    ///
+    /// Returns the explorer, the maximum memory usage and whether the exploration is partial,
+    /// see [`Config::allow_partial`].
+    fn run<AA: ActionApplier<TT>>(
+        graph: &'a Graph,
+        start_state: State,
+        config: &Config,
+        store_transitions: bool,
+    ) -> Result<(Self, usize, bool), SolveFailure> {
+        const MEMORY_SAMPLE_PERIOD: usize = 2_usize.pow(15);
+        // NOTE: Previously, initail memory usage was subtracted from the currently allocated.
+        // However, in some cases it caused underflow due to memory usage approximation errors.
+        let mut max_memory: usize = 0;
+
+        let mut explorer = Self::new(graph, start_state, store_transitions, config)?;
+        let memory_limit = config.max_memory;
 
         {
             let initial = explorer.states.next();
             explorer.explore_initial::<AA>(
                 initial.expect("No initial exploration state in StateIndexer"),
-            );
+            )?;
+            explorer.update_gauge(1);
         }
         let mut index = 1; // First one indexed
-        while let Some(i) = explorer.states.next() {
-            explorer.explore_state::<AA>(i);
-
-            index += 1;
-            if index % MEMORY_SAMPLE_PERIOD == 0 {
-                let allocated = ALLOCATOR.allocated();
+        let mut partial = false;
+        loop {
+            // Sampled after the initial state too, so that the limit applies to small problems.
+            if config.track_memory && index % MEMORY_SAMPLE_PERIOD == 1 {
+                let allocated = crate::allocated_memory();
                 max_memory = std::cmp::max(max_memory, allocated);
                 if allocated > memory_limit {
-                    return Err(SolveFailure::OutOfMemory {
-                        used: max_memory,
-                        limit: memory_limit,
-                    });
+                    if !config.allow_partial {
+                        return Err(SolveFailure::OutOfMemory {
+                            used: max_memory,
+                            limit: memory_limit,
+                        });
+                    }
+                    partial = true;
+                    break;
                 }
             }
+            let Some(i) = explorer.states.next() else {
+                break;
+            };
+            explorer.explore_state::<AA>(i)?;
+            index += 1;
+            explorer.update_gauge(index);
+        }
+
+        if partial {
+            // The teams cannot reduce the number of damaged buses, so the remaining cost of an
+            // unexplored state is at least that per unit time until the horizon, which is what a
+            // terminal state costs. See Config::allow_partial.
+            while let Some((i, state)) = explorer.states.next() {
+                let damaged =
+                    state.weighted_bus_count(explorer.graph, |bus| bus == BusState::Damaged);
+                let team_actions = explorer.no_move_actions(&state);
+                explorer.store(
+                    i,
+                    vec![vec![TT::terminal_transition(i as StateIndex, damaged)]],
+                    team_actions,
+                );
+            }
+            explorer.update_gauge(explorer.states.get_state_count());
+        }
+
+        if config.track_memory {
+            max_memory = std::cmp::max(max_memory, crate::allocated_memory());
         }
 
-        let allocated = ALLOCATOR.allocated();
-        max_memory = std::cmp::max(max_memory, allocated);
+        if let Some(file) = explorer.debug_dump.as_mut() {
+            file.flush()
+                .map_err(|e| SolveFailure::Io(format!("Cannot write debug dump: {e}")))?;
+        }
+
+        Ok((explorer, max_memory, partial))
+    }
+}
+
+impl<'a, TT: Transition, AI: ActionSet<'a>, SI: StateIndexer> Explorer<'a, TT>
+    for NaiveExplorer<'a, TT, AI, SI>
+{
+    fn memory_limited_explore_from_state<AA: ActionApplier<TT>>(
+        graph: &'a Graph,
+        start_state: State,
+        config: &Config,
+    ) -> Result<ExploreResult<TT>, SolveFailure> {
+        let (explorer, max_memory, partial) = Self::run::<AA>(graph, start_state, config, true)?;
 
+        let action_stats = if config.action_stats {
+            let mut stats = Vec::new();
+            explorer.iterator.collect_stats(&mut stats);
+            Some(stats.into_iter().collect())
+        } else {
+            None
+        };
         let (bus_states, team_states) = explorer.states.deconstruct();
         let transitions = explorer.transitions;
+        let actions = explorer.actions;
         Ok(ExploreResult {
             bus_states,
             team_states,
             transitions,
             max_memory,
+            action_stats,
+            partial,
+            actions,
         })
     }
+
+    fn memory_limited_count_states<AA: ActionApplier<TT>>(
+        graph: &'a Graph,
+        teams: Vec<TeamState>,
+        config: &Config,
+    ) -> Result<usize, SolveFailure> {
+        let start_state = State::start_state(graph, teams);
+        let (explorer, _, _) = Self::run::<AA>(graph, start_state, config, false)?;
+        Ok(explorer.states.get_state_count())
+    }
+}
+
+/// Iterator over the states reachable from the start state, in the order of exploration.
+///
+/// States are explored lazily, i.e., the successors of a state are indexed when that state is
+/// yielded. Transitions are not stored.
+///
+/// If the exploration of a state fails, e.g., with [`SolveFailure::BadInput`] if no action is
+/// feasible in it, the error is yielded instead of the state and the iteration ends.
+pub struct ReachableStates<'a, TT, AI, SI, AA>
+where
+    TT: Transition,
+    AI: ActionSet<'a>,
+    SI: StateIndexer,
+    AA: ActionApplier<TT>,
+{
+    explorer: NaiveExplorer<'a, TT, AI, SI>,
+    /// True if the initial state is not yielded yet.
+    initial: bool,
+    /// True if the exploration has failed, after which no states are yielded.
+    failed: bool,
+    _phantom: std::marker::PhantomData<AA>,
+}
+
+impl<'a, TT, AI, SI, AA> ReachableStates<'a, TT, AI, SI, AA>
+where
+    TT: Transition,
+    AI: ActionSet<'a>,
+    SI: StateIndexer,
+    AA: ActionApplier<TT>,
+{
+    /// Construct an iterator over the states reachable from the start state of given teams.
+    pub fn new(graph: &'a Graph, teams: Vec<TeamState>) -> Result<Self, SolveFailure> {
+        Ok(ReachableStates {
+            explorer: NaiveExplorer::new(
+                graph,
+                State::start_state(graph, teams),
+                false,
+                &Config::unlimited(),
+            )?,
+            initial: true,
+            failed: false,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<'a, TT, AI, SI, AA> Iterator for ReachableStates<'a, TT, AI, SI, AA>
+where
+    TT: Transition,
+    AI: ActionSet<'a>,
+    SI: StateIndexer,
+    AA: ActionApplier<TT>,
+{
+    type Item = Result<State, SolveFailure>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let (index, state) = self.explorer.states.next()?;
+        let output = state.clone();
+        let result = if self.initial {
+            self.initial = false;
+            self.explorer.explore_initial::<AA>((index, state))
+        } else {
+            self.explorer.explore_state::<AA>((index, state))
+        };
+        if let Err(e) = result {
+            self.failed = true;
+            return Some(Err(e));
+        }
+        Some(Ok(output))
+    }
 }
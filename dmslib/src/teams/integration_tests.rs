@@ -15,6 +15,8 @@ fn pe0_1_team() {
             vec![io::Team {
                 index: Some(0),
                 latlng: None,
+                name: None,
+                node: None,
             }],
             Some(30),
         )
@@ -27,6 +29,7 @@ fn pe0_1_team() {
         &config,
         "NaiveStateIndexer",
         "NaiveActions",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -39,6 +42,7 @@ fn pe0_1_team() {
         &config,
         "SortedStateIndexer<NaiveStateIndexer>",
         "NaiveActions",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -50,6 +54,7 @@ fn pe0_1_team() {
         &config,
         "NaiveStateIndexer",
         "PermutationalActions",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -61,6 +66,7 @@ fn pe0_1_team() {
         &config,
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<NaiveActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -73,6 +79,7 @@ fn pe0_1_team() {
         &config,
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<PermutationalActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -84,6 +91,7 @@ fn pe0_1_team() {
         &config,
         "NaiveStateIndexer",
         "FilterOnWay<NaiveActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -95,6 +103,7 @@ fn pe0_1_team() {
         &config,
         "NaiveStateIndexer",
         "FilterOnWay<PermutationalActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -109,6 +118,7 @@ fn pe0_1_team() {
         "NaiveStateIndexer",
         "NaiveActions",
         "TimedActionApplier<ConstantTime>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -121,6 +131,7 @@ fn pe0_1_team() {
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<PermutationalActions>",
         "TimedActionApplier<ConstantTime>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -133,6 +144,7 @@ fn pe0_1_team() {
         "NaiveStateIndexer",
         "FilterOnWay<PermutationalActions>",
         "TimedActionApplier<ConstantTime>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -147,6 +159,7 @@ fn pe0_1_team() {
         "NaiveStateIndexer",
         "NaiveActions",
         "TimedActionApplier<TimeUntilArrival>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -159,6 +172,7 @@ fn pe0_1_team() {
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilArrival>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -171,6 +185,7 @@ fn pe0_1_team() {
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -183,6 +198,7 @@ fn pe0_1_team() {
         "NaiveStateIndexer",
         "FilterOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -195,6 +211,7 @@ fn pe0_1_team() {
         "SortedStateIndexer<NaiveStateIndexer>",
         "FilterOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -211,10 +228,14 @@ fn pe0_2_team() {
                 io::Team {
                     index: Some(1),
                     latlng: None,
+                    name: None,
+                    node: None,
                 },
                 io::Team {
                     index: Some(6),
                     latlng: None,
+                    name: None,
+                    node: None,
                 },
             ],
             Some(30),
@@ -228,6 +249,7 @@ fn pe0_2_team() {
         &config,
         "NaiveStateIndexer",
         "NaiveActions",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -240,6 +262,7 @@ fn pe0_2_team() {
         &config,
         "SortedStateIndexer<NaiveStateIndexer>",
         "NaiveActions",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -252,6 +275,7 @@ fn pe0_2_team() {
         &config,
         "NaiveStateIndexer",
         "PermutationalActions",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -262,6 +286,7 @@ fn pe0_2_team() {
         &config,
         "SortedStateIndexer<NaiveStateIndexer>",
         "PermutationalActions",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -273,6 +298,7 @@ fn pe0_2_team() {
         &config,
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<NaiveActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -284,6 +310,7 @@ fn pe0_2_team() {
         &config,
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<PermutationalActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -295,6 +322,7 @@ fn pe0_2_team() {
         &config,
         "NaiveStateIndexer",
         "FilterOnWay<NaiveActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -306,6 +334,7 @@ fn pe0_2_team() {
         &config,
         "SortedStateIndexer<NaiveStateIndexer>",
         "FilterOnWay<NaiveActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -318,6 +347,7 @@ fn pe0_2_team() {
         &config,
         "NaiveStateIndexer",
         "FilterOnWay<PermutationalActions>",
+        "NaivePolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -332,6 +362,7 @@ fn pe0_2_team() {
         "NaiveStateIndexer",
         "NaiveActions",
         "TimedActionApplier<ConstantTime>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -344,6 +375,7 @@ fn pe0_2_team() {
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<PermutationalActions>",
         "TimedActionApplier<ConstantTime>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -356,6 +388,7 @@ fn pe0_2_team() {
         "NaiveStateIndexer",
         "FilterOnWay<PermutationalActions>",
         "TimedActionApplier<ConstantTime>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -368,6 +401,7 @@ fn pe0_2_team() {
         "SortedStateIndexer<NaiveStateIndexer>",
         "FilterOnWay<PermutationalActions>",
         "TimedActionApplier<ConstantTime>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -382,6 +416,7 @@ fn pe0_2_team() {
         "NaiveStateIndexer",
         "NaiveActions",
         "TimedActionApplier<TimeUntilArrival>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -394,6 +429,7 @@ fn pe0_2_team() {
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilArrival>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -406,6 +442,7 @@ fn pe0_2_team() {
         "NaiveStateIndexer",
         "FilterEnergizedOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -418,6 +455,7 @@ fn pe0_2_team() {
         "NaiveStateIndexer",
         "FilterOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilArrival>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -430,6 +468,7 @@ fn pe0_2_team() {
         "NaiveStateIndexer",
         "FilterOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -442,6 +481,7 @@ fn pe0_2_team() {
         "SortedStateIndexer<NaiveStateIndexer>",
         "FilterOnWay<PermutationalActions>",
         "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
     )
     .unwrap();
     assert_eq!(solution.get_min_value(), OPTIMAL_VALUE);
@@ -459,22 +499,32 @@ fn save_test_pe0_1_team() {
         teams: vec![io::Team {
             index: Some(0),
             latlng: None,
+            name: None,
+            node: None,
         }],
-        horizon: Some(10),
+        horizon: HorizonSpec::Absolute(10),
         pfo: None,
         time_func: Default::default(),
+        strict_branches: false,
+        repair_times: None,
+        time_scale: None,
+        depots: Vec::new(),
+        deadlines: None,
+        start_distribution: None,
     };
 
     let solution = problem.clone().solve_naive().unwrap();
 
     let mut path: std::path::PathBuf = std::env::temp_dir();
     path.push("dmslib-test.pe0-1-team.bin");
-    io::fs::save_solution(problem.clone(), solution.clone(), &path).unwrap();
+    io::fs::save_solution(problem.clone(), solution.clone(), None, &path).unwrap();
 
     let io::fs::SaveFile {
         problem: saved_problem,
         solution: saved_solution,
+        settings: saved_settings,
     } = io::fs::load_solution(&path).unwrap();
+    assert!(saved_settings.is_none());
 
     let saved_solution = if let io::GenericTeamSolution::Regular(s) = saved_solution {
         s
@@ -495,10 +545,18 @@ fn simulation_test_pf0_pe0_1_team() {
         teams: vec![io::Team {
             index: Some(0),
             latlng: None,
+            name: None,
+            node: None,
         }],
-        horizon: Some(10),
+        horizon: HorizonSpec::Absolute(10),
         pfo: Some(0.0),
         time_func: Default::default(),
+        strict_branches: false,
+        repair_times: None,
+        time_scale: None,
+        depots: Vec::new(),
+        deadlines: None,
+        start_distribution: None,
     };
 
     let solution = problem.solve_naive().unwrap();
@@ -517,6 +575,10 @@ fn simulation_test_pf0_pe0_1_team() {
         avg_time as Value,
         get_min_value(&solution.values) / (bus_count as Value),
     );
+
+    // Outcomes are deterministic, there's no variance.
+    assert!(simulation_result.time_variance.iter().all(|&v| v == 0.0));
+    assert!(simulation_result.time_std.iter().all(|&v| v == 0.0));
 }
 
 /// Test whether the policy from our MDP is actually stationary.
@@ -529,10 +591,14 @@ fn stationary_policy_test() {
                 io::Team {
                     index: Some(1),
                     latlng: None,
+                    name: None,
+                    node: None,
                 },
                 io::Team {
                     index: Some(6),
                     latlng: None,
+                    name: None,
+                    node: None,
                 },
             ],
             Some(30),
@@ -544,6 +610,9 @@ fn stationary_policy_test() {
         team_states: _,
         transitions,
         max_memory: _,
+        action_stats: _,
+        partial: _,
+        actions: _,
     } = NaiveExplorer::<
         RegularTransition,
         FilterOnWay<PermutationalActions>,
@@ -551,7 +620,7 @@ fn stationary_policy_test() {
     >::memory_limited_explore::<NaiveActionApplier>(
         &problem.graph,
         problem.initial_teams.clone(),
-        config.max_memory,
+        &config,
     )
     .unwrap();
     // After team representations were updated, this reduced from 3489 to 2662
@@ -580,3 +649,1923 @@ fn stationary_policy_test() {
     let predicted_checks: usize = lengths.into_iter().map(|length| max_horizon - length).sum();
     assert_eq!(checks, predicted_checks);
 }
+
+type RegularSynthesizer =
+    fn(&[Vec<Vec<RegularTransition>>], usize) -> (Vec<Vec<Value>>, Vec<ActionIndex>);
+
+/// Regular policy synthesizers with the relative tolerance of their values compared to
+/// [`NaivePolicySynthesizer`], which are computed differently by the iterative ones.
+const REGULAR_SYNTHESIZERS: [(&str, RegularSynthesizer, Value); 3] = [
+    (
+        "NaivePolicySynthesizer",
+        NaivePolicySynthesizer::synthesize_policy,
+        0.0,
+    ),
+    (
+        "GaussSeidelPolicySynthesizer",
+        GaussSeidelPolicySynthesizer::synthesize_policy,
+        1e-4,
+    ),
+    (
+        "PolicyIterationSynthesizer",
+        PolicyIterationSynthesizer::synthesize_policy,
+        1e-4,
+    ),
+];
+
+#[test]
+fn value_sweep_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let (problem, config) = input_graph
+        .to_teams_problem(
+            vec![
+                io::Team {
+                    index: Some(1),
+                    latlng: None,
+                    name: None,
+                    node: None,
+                },
+                io::Team {
+                    index: Some(6),
+                    latlng: None,
+                    name: None,
+                    node: None,
+                },
+            ],
+            None,
+        )
+        .unwrap();
+    let ExploreResult { transitions, .. } = NaiveExplorer::<
+        RegularTransition,
+        NaiveActions,
+        NaiveStateIndexer,
+    >::memory_limited_explore::<NaiveActionApplier>(
+        &problem.graph, problem.initial_teams, &config
+    )
+    .unwrap();
+    let max_horizon = determine_horizon(&transitions) + 2;
+
+    let sweep = NaivePolicySynthesizer::synthesize_value_sweep(&transitions, max_horizon);
+    assert_eq!(sweep.len(), max_horizon);
+    for (name, synthesize_policy, tolerance) in REGULAR_SYNTHESIZERS {
+        for (horizon, &value) in (1..=max_horizon).zip(sweep.iter()) {
+            let (values, _) = synthesize_policy(&transitions, horizon);
+            let min_value = get_min_value(&values);
+            assert!(
+                (value - min_value).abs() <= tolerance * value.abs().max(1.0),
+                "{name}, horizon {horizon}: {value} != {min_value}"
+            );
+        }
+    }
+    // Costs are non-negative, so longer horizons can't decrease the value.
+    assert!(sweep.windows(2).all(|w| w[0] <= w[1]));
+}
+
+/// The iterative synthesizers must find optimal policies with the same values as
+/// [`NaivePolicySynthesizer`], and fall back to it for short horizons.
+#[test]
+fn iterative_synthesizers_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let (problem, config) = input_graph
+        .to_teams_problem(
+            vec![
+                io::Team {
+                    index: Some(1),
+                    latlng: None,
+                    name: None,
+                    node: None,
+                },
+                io::Team {
+                    index: Some(6),
+                    latlng: None,
+                    name: None,
+                    node: None,
+                },
+            ],
+            None,
+        )
+        .unwrap();
+    let close = |a: Value, b: Value| (a - b).abs() <= 1e-4 * a.abs().max(1.0);
+
+    for (indexer, action_set) in [
+        ("NaiveStateIndexer", "NaiveActions"),
+        (
+            "SortedStateIndexer<NaiveStateIndexer>",
+            "PermutationalActions",
+        ),
+        ("BitStackStateIndexer", "FilterOnWay<NaiveActions>"),
+    ] {
+        let solve = |synthesizer: &str| {
+            solve_custom_regular(
+                &problem.graph,
+                problem.initial_teams.clone(),
+                &config,
+                indexer,
+                action_set,
+                synthesizer,
+            )
+            .unwrap()
+        };
+        let naive = solve("NaivePolicySynthesizer");
+        for synthesizer in ["GaussSeidelPolicySynthesizer", "PolicyIterationSynthesizer"] {
+            let solution = solve(synthesizer);
+            assert_eq!(naive.transitions.len(), solution.transitions.len());
+            assert!(close(naive.get_min_value(), solution.get_min_value()));
+            for (naive_values, values, &action) in itertools::izip!(
+                naive.values.iter(),
+                solution.values.iter(),
+                solution.policy.iter()
+            ) {
+                for (&a, &b) in naive_values.iter().zip(values.iter()) {
+                    assert!(close(a, b), "{synthesizer}: {a} != {b}");
+                }
+                // The chosen action must be optimal, but ties may be broken differently.
+                let optimal = get_state_values(std::slice::from_ref(naive_values))[0];
+                assert!(close(naive_values[action as usize], optimal));
+            }
+        }
+    }
+
+    let ExploreResult { transitions, .. } = NaiveExplorer::<
+        RegularTransition,
+        NaiveActions,
+        NaiveStateIndexer,
+    >::memory_limited_explore::<NaiveActionApplier>(
+        &problem.graph, problem.initial_teams, &config
+    )
+    .unwrap();
+    let horizon = determine_horizon(&transitions);
+    type SynthesizeWithCount = fn(
+        &[Vec<Vec<RegularTransition>>],
+        usize,
+    ) -> (Vec<Vec<Value>>, Vec<ActionIndex>, Option<usize>);
+    let synthesizers: [(&str, SynthesizeWithCount, usize); 2] = [
+        // NaivePolicySynthesizer iterates over the whole state space once per horizon.
+        (
+            "GaussSeidelPolicySynthesizer",
+            GaussSeidelPolicySynthesizer::synthesize_with_sweeps,
+            horizon - 1,
+        ),
+        // States at depth k have their optimal actions after k improvements.
+        (
+            "PolicyIterationSynthesizer",
+            PolicyIterationSynthesizer::synthesize_with_iterations,
+            horizon + 1,
+        ),
+    ];
+    for (name, synthesize_with_count, max_count) in synthesizers {
+        let (_, _, count) = synthesize_with_count(&transitions, horizon);
+        let count = count.expect("Fell back to NaivePolicySynthesizer");
+        assert!(
+            count <= max_count,
+            "{name}: {count} iterations, horizon {horizon}"
+        );
+
+        // Shorter horizons fall back to NaivePolicySynthesizer.
+        let (values, policy, count) = synthesize_with_count(&transitions, horizon - 1);
+        assert_eq!(count, None, "{name}");
+        assert_eq!(
+            (values, policy),
+            NaivePolicySynthesizer::synthesize_policy(&transitions, horizon - 1)
+        );
+    }
+}
+
+#[test]
+fn parallel_value_iteration_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let (problem, config) = input_graph
+        .to_teams_problem(
+            vec![
+                io::Team {
+                    index: Some(1),
+                    latlng: None,
+                    name: None,
+                    node: None,
+                },
+                io::Team {
+                    index: Some(6),
+                    latlng: None,
+                    name: None,
+                    node: None,
+                },
+            ],
+            None,
+        )
+        .unwrap();
+    let ExploreResult { transitions, .. } = NaiveExplorer::<
+        RegularTransition,
+        NaiveActions,
+        NaiveStateIndexer,
+    >::memory_limited_explore::<NaiveActionApplier>(
+        &problem.graph, problem.initial_teams, &config
+    )
+    .unwrap();
+    let horizon = determine_horizon(&transitions);
+
+    let serial =
+        NaivePolicySynthesizer::synthesize_with_threshold(&transitions, horizon, usize::MAX);
+    let parallel = NaivePolicySynthesizer::synthesize_with_threshold(&transitions, horizon, 0);
+    assert_eq!(serial, parallel);
+    assert_eq!(
+        serial,
+        NaivePolicySynthesizer::synthesize_policy(&transitions, horizon)
+    );
+}
+
+#[test]
+fn bus_weight_test() {
+    // A single team starts at node 2, and the buses are on a line at -3 and 1 relative to it.
+    // Each bus is directly connected to a source and certain to be energized.
+    let positions: [i32; 3] = [-3, 1, 0];
+    let mut graph = Graph {
+        travel_times: Array2::from_shape_fn((3, 3), |(i, j)| {
+            (positions[i] - positions[j]).unsigned_abs() as Time
+        }),
+        branches: vec![vec![]; 2],
+        connected: vec![true; 2],
+        pfs: ndarray::arr1(&[0.0, 0.0]),
+        team_nodes: Array2::zeros((3, 2)),
+        ..Default::default()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let solve = |graph: &Graph| {
+        solve_custom_timed(
+            graph,
+            initial_teams.clone(),
+            &Config::default(),
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap()
+    };
+    let first_target = |solution: &Solution<TimedTransition>| {
+        let successor = solution.transitions[0][solution.policy[0] as usize][0].successor;
+        solution.teams[[successor as usize, 0]].index
+    };
+
+    // Visiting the closer bus first costs 2 * 1 + 1 * 4 = 6 instead of 2 * 3 + 1 * 4 = 10.
+    let solution = solve(&graph);
+    assert_eq!(solution.get_min_value(), 6.0);
+    assert_eq!(first_target(&solution), 1);
+
+    // Same with explicit unit weights.
+    graph.weights = Some(vec![1 as Cost, 1 as Cost]);
+    let solution = solve(&graph);
+    assert_eq!(solution.get_min_value(), 6.0);
+    assert_eq!(first_target(&solution), 1);
+
+    // With a weight of 10, energizing bus 0 first costs 11 * 3 + 1 * 4 = 37 instead of
+    // 11 * 1 + 10 * 4 = 51.
+    graph.weights = Some(vec![10 as Cost, 1 as Cost]);
+    let solution = solve(&graph);
+    assert_eq!(solution.get_min_value(), 37.0);
+    assert_eq!(first_target(&solution), 0);
+}
+
+#[test]
+fn repair_time_test() {
+    // Two buses directly connected to the source, team starts at node 2.
+    let mut graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 1, 1], [1, 0, 2], [1, 2, 0]]),
+        branches: vec![vec![], vec![]],
+        connected: vec![true, true],
+        pfs: ndarray::arr1(&[0.0, 0.0]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let config = Config::default();
+
+    // Returns the minimum value and the bus that is visited first in the optimal policy.
+    let solve = |graph: &Graph| {
+        let solution = solve_custom_timed(
+            graph,
+            initial_teams.clone(),
+            &config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap();
+        let action = &solution.transitions[0][solution.policy[0] as usize];
+        let successor = action[0].successor as usize;
+        (
+            solution.get_min_value(),
+            solution.teams[(successor, 0)].index,
+        )
+    };
+
+    // Without repair times, the closest bus is visited first.
+    assert_eq!(solve(&graph), (3.0, 0));
+
+    // Repairing bus 0 takes a long time, so it's better to energize bus 1 first.
+    graph.repair_times = Some(ndarray::arr1(&[5, 0]));
+    assert_eq!(solve(&graph), (10.0, 1));
+}
+
+#[test]
+fn pfs_schedule_test() {
+    // Two buses directly connected to the source, team starts at node 2.
+    let graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 2, 1], [2, 0, 2], [1, 2, 0]]),
+        branches: vec![vec![], vec![]],
+        connected: vec![true, true],
+        pfs: ndarray::arr1(&[0.5, 0.5]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let mut config = Config {
+        horizon: HorizonSpec::Absolute(20),
+        ..Config::default()
+    };
+
+    // Returns the bus that is visited first in the optimal policy.
+    let solve = |config: &Config| {
+        let solution = solve_custom_timed(
+            &graph,
+            initial_teams.clone(),
+            config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap();
+        let action = &solution.transitions[0][solution.policy[0] as usize];
+        let successor = action[0].successor as usize;
+        solution.teams[(successor, 0)].index
+    };
+
+    // The closest bus is visited first.
+    assert_eq!(solve(&config), 0);
+
+    // Bus 1 is likely to fail from time 3, which is when it would be energized otherwise.
+    config.pfs_schedule = Some(vec![(3, vec![0.5, 0.99])]);
+    assert_eq!(solve(&config), 1);
+
+    // Same pfs as the graph don't change the values.
+    let solve_value = |config: &Config| {
+        solve_custom_timed(
+            &graph,
+            initial_teams.clone(),
+            config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap()
+        .get_min_value()
+    };
+    config.pfs_schedule = Some(vec![(2, vec![0.5, 0.5])]);
+    let scheduled = solve_value(&config);
+    config.pfs_schedule = None;
+    assert!((scheduled - solve_value(&config)).abs() < 1e-4);
+
+    // The possible outcomes cannot change.
+    config.pfs_schedule = Some(vec![(2, vec![0.5, 1.0])]);
+    let result = solve_custom_timed(
+        &graph,
+        initial_teams.clone(),
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
+    );
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+
+    // Not supported by the regular policy synthesizer.
+    config.pfs_schedule = Some(vec![(2, vec![0.5, 0.9])]);
+    let result = solve_naive(&graph, initial_teams, &config);
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+#[test]
+fn undefined_synthesizer_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let (problem, config) = input_graph
+        .to_teams_problem(
+            vec![io::Team {
+                index: Some(0),
+                latlng: None,
+                name: None,
+                node: None,
+            }],
+            None,
+        )
+        .unwrap();
+
+    let result = solve_custom_regular(
+        &problem.graph,
+        problem.initial_teams.clone(),
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "NaiveTimedPolicySynthesizer",
+    );
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+
+    let result = solve_custom_timed(
+        &problem.graph,
+        problem.initial_teams,
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "TimedActionApplier<TimeUntilEnergization>",
+        "UndefinedPolicySynthesizer",
+    );
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+/// The automatic horizon of unscaled problems must be the longest path length from the initial
+/// state, both with regular and timed transitions.
+#[test]
+fn unscaled_auto_horizon_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let (problem, config) = input_graph
+        .to_teams_problem(
+            vec![io::Team {
+                index: Some(0),
+                latlng: None,
+                name: None,
+                node: None,
+            }],
+            None,
+        )
+        .unwrap();
+
+    let solution = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    assert_eq!(get_time_step(&solution.transitions), 1);
+    assert_eq!(
+        solution.horizon,
+        longest_path_lengths(&solution.transitions)[0]
+    );
+
+    let solution = solve_custom_timed(
+        &problem.graph,
+        problem.initial_teams,
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
+    )
+    .unwrap();
+    assert_eq!(get_time_step(&solution.transitions), 1);
+    assert_eq!(
+        solution.horizon,
+        longest_path_lengths(&solution.transitions)[0]
+    );
+}
+
+/// Scaling all times in the problem must preserve the optimal policy.
+#[test]
+fn time_scale_test() {
+    let mut graph = Graph {
+        travel_times: ndarray::arr2(&[
+            [0, 1, 2, 1, 2, 2, 1],
+            [1, 0, 1, 2, 2, 2, 2],
+            [2, 1, 0, 2, 2, 1, 3],
+            [1, 2, 2, 0, 1, 2, 1],
+            [2, 2, 2, 1, 0, 1, 2],
+            [2, 2, 1, 2, 1, 0, 3],
+            [1, 2, 3, 1, 2, 3, 0],
+        ]),
+        branches: vec![vec![1], vec![0, 2], vec![1], vec![4], vec![3, 5], vec![4]],
+        connected: vec![true, false, false, true, false, false],
+        pfs: ndarray::arr1(&[0.5, 0.5, 0.25, 0.25, 0.25, 0.25]),
+        team_nodes: Array2::default((0, 0)),
+        repair_times: Some(ndarray::arr1(&[1, 0, 2, 0, 1, 0])),
+        ..Default::default()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 6 }];
+    let config = Config::default();
+    let solve = |graph: &Graph| {
+        solve_custom_timed(
+            graph,
+            initial_teams.clone(),
+            &config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap()
+    };
+
+    let solution = solve(&graph);
+    graph.travel_times *= 10;
+    graph.repair_times = graph.repair_times.map(|times| times * 10);
+    let scaled_solution = solve(&graph);
+
+    assert_eq!(
+        scaled_solution.transitions.len(),
+        solution.transitions.len()
+    );
+    assert_eq!(scaled_solution.policy, solution.policy);
+    let value = solution.get_min_value();
+    assert!((scaled_solution.get_min_value() - value * 10.0).abs() < value * 1e-4);
+}
+
+#[test]
+fn count_states_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    for team_count in 1..=2 {
+        let teams = vec![
+            io::Team {
+                index: Some(0),
+                latlng: None,
+                name: None,
+                node: None,
+            };
+            team_count
+        ];
+        let (problem, config) = input_graph.clone().to_teams_problem(teams, None).unwrap();
+        let state_count =
+            count_states(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+        let solution = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+        assert_eq!(state_count, solution.transitions.len());
+    }
+}
+
+#[test]
+fn resolve_with_teams_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let team = |index: usize| io::Team {
+        index: Some(index),
+        latlng: None,
+        name: None,
+        node: None,
+    };
+    let problem = io::TeamProblem {
+        name: None,
+        graph: input_graph,
+        teams: vec![team(0)],
+        horizon: HorizonSpec::Auto,
+        pfo: None,
+        time_func: Default::default(),
+        strict_branches: false,
+        repair_times: None,
+        time_scale: None,
+        depots: Vec::new(),
+        deadlines: None,
+        start_distribution: None,
+    };
+    let solution = problem.clone().solve_naive().unwrap();
+
+    // Re-solving from the start state with the same teams must yield the same solution.
+    let start_state = solution.get_state(0);
+    let resolved = problem
+        .clone()
+        .resolve_with_teams(start_state.buses, vec![team(0)])
+        .unwrap();
+    assert_eq!(resolved.values, solution.values);
+    assert_eq!(resolved.policy, solution.policy);
+
+    // Find a state in the middle of restoration with an idle team.
+    let state = (1..solution.transitions.len())
+        .map(|i| solution.get_state(i))
+        .find(|state| {
+            state.teams[0].time == 0
+                && state.buses.contains(&BusState::Energized)
+                && state.buses.contains(&BusState::Unknown)
+        })
+        .unwrap();
+    let position = state.teams[0].index as usize;
+
+    // A reinforcement crew arrives at node 3.
+    let resolved = problem
+        .resolve_with_teams(state.buses.clone(), vec![team(position), team(3)])
+        .unwrap();
+    assert_eq!(resolved.teams.ncols(), 2);
+    let resolved_state = resolved.get_state(0);
+    assert_eq!(resolved_state.buses, state.buses);
+    assert_eq!(
+        resolved_state.teams,
+        vec![
+            TeamState {
+                time: 0,
+                index: position as BusIndex
+            },
+            TeamState { time: 0, index: 3 },
+        ]
+    );
+    assert!(resolved.transitions.len() > 1);
+}
+
+#[test]
+fn symmetric_teams_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (mut problem, config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let solve = |graph: &Graph, indexer: &str| {
+        solve_custom_regular(
+            graph,
+            problem.initial_teams.clone(),
+            &config,
+            indexer,
+            "NaiveActions",
+            "NaivePolicySynthesizer",
+        )
+        .unwrap()
+    };
+    let naive = solve(&problem.graph, "NaiveStateIndexer");
+
+    // No reduction if the teams are not interchangeable.
+    problem.graph.team_classes = Some(vec![0, 1]);
+    let solution = solve(&problem.graph, "SymmetricStateIndexer<NaiveStateIndexer>");
+    assert_eq!(solution.transitions.len(), naive.transitions.len());
+
+    problem.graph.team_classes = Some(vec![0, 0]);
+    let solution = solve(&problem.graph, "SymmetricStateIndexer<NaiveStateIndexer>");
+    assert!(solution.transitions.len() < naive.transitions.len());
+    assert_eq!(solution.get_min_value(), naive.get_min_value());
+    // All teams are interchangeable, hence it's equivalent to sorting the teams.
+    let sorted = solve(&problem.graph, "SortedStateIndexer<NaiveStateIndexer>");
+    assert_eq!(solution.transitions.len(), sorted.transitions.len());
+}
+
+#[test]
+fn max_actions_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let solution = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    let max_actions = solution.transitions.iter().map(Vec::len).max().unwrap();
+    assert!(max_actions > 1);
+
+    config.max_actions_per_state = max_actions;
+    assert!(solve_naive(&problem.graph, problem.initial_teams.clone(), &config).is_ok());
+
+    config.max_actions_per_state = max_actions - 1;
+    let result = solve_naive(&problem.graph, problem.initial_teams.clone(), &config);
+    assert!(matches!(result, Err(SolveFailure::TooManyActions { .. })));
+    let result = count_states(&problem.graph, problem.initial_teams, &config);
+    assert!(matches!(result, Err(SolveFailure::TooManyActions { .. })));
+}
+
+#[test]
+fn max_states_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let states = count_states(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+
+    config.max_states = states;
+    assert!(solve_naive(&problem.graph, problem.initial_teams.clone(), &config).is_ok());
+
+    config.max_states = 100;
+    let result = solve_naive(&problem.graph, problem.initial_teams.clone(), &config);
+    assert!(matches!(
+        result,
+        Err(SolveFailure::TooManyStates {
+            count: 101,
+            limit: 100
+        })
+    ));
+    let result = count_states(&problem.graph, problem.initial_teams, &config);
+    assert!(matches!(result, Err(SolveFailure::TooManyStates { .. })));
+}
+
+#[test]
+fn debug_dump_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let mut path: std::path::PathBuf = std::env::temp_dir();
+    path.push("dmslib-test.debug-dump.txt");
+    config.debug_dump = Some(path.clone());
+    let solution = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+
+    let dump = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(dump.lines().count(), solution.transitions.len());
+    assert!(dump.lines().next().unwrap().starts_with("0 ["));
+}
+
+#[test]
+fn global_wait_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let explore = |config: &Config| {
+        NaiveExplorer::<TimedTransition, NaiveActions, NaiveStateIndexer>::memory_limited_explore::<
+            TimedActionApplier<TimeUntilArrival>,
+        >(&problem.graph, problem.initial_teams.clone(), config)
+        .unwrap()
+    };
+    let base = explore(&config);
+    config.global_wait = Some(2);
+    let result = explore(&config);
+    // The wait action is a self-transition, it doesn't lead to new states.
+    assert_eq!(result.transitions.len(), base.transitions.len());
+
+    let mut wait_states = 0;
+    for (i, (actions, base_actions)) in result
+        .transitions
+        .iter()
+        .zip(base.transitions.iter())
+        .enumerate()
+    {
+        let state = State {
+            buses: result.bus_states.row(i).to_vec(),
+            teams: result.team_states.row(i).to_vec(),
+        };
+        if actions.len() != base_actions.len() {
+            assert!(!state.is_terminal(&problem.graph) && state.is_wait_available());
+            assert_eq!(actions.len(), base_actions.len() + 1);
+            assert_eq!(actions[..base_actions.len()], base_actions[..]);
+            let wait = &actions.last().unwrap()[..];
+            assert_eq!(
+                wait,
+                &[TimedTransition::wait_transition(
+                    i as StateIndex,
+                    wait[0].cost,
+                    2
+                )]
+            );
+            wait_states += 1;
+        } else {
+            // Only the initial state can skip the wait action due to initial energization.
+            assert!(i == 0 || state.is_terminal(&problem.graph) || !state.is_wait_available());
+            assert_eq!(actions, base_actions);
+        }
+    }
+    assert!(wait_states > 0);
+
+    let solution = solve_custom_timed(
+        &problem.graph,
+        problem.initial_teams,
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "TimedActionApplier<TimeUntilArrival>",
+        "NaiveTimedPolicySynthesizer",
+    )
+    .unwrap();
+    assert_eq!(solution.transitions, result.transitions);
+}
+
+#[test]
+fn action_stats_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let solve = |config: &Config| {
+        solve_custom_regular(
+            &problem.graph,
+            problem.initial_teams.clone(),
+            config,
+            "NaiveStateIndexer",
+            "FilterOnWay<PermutationalActions>",
+            "NaivePolicySynthesizer",
+        )
+        .unwrap()
+    };
+    assert!(solve(&config).action_stats.is_none());
+    config.action_stats = true;
+    let solution = solve(&config);
+    let stats = solution.action_stats.as_ref().unwrap();
+    assert_eq!(stats.len(), 2);
+    let permutational = stats["PermutationalActions"];
+    let on_way = stats["FilterOnWay"];
+
+    // Count the actions in the states in which the action set was used.
+    let mut naive_actions = 0;
+    let mut actions = 0;
+    for (i, state_actions) in solution.transitions.iter().enumerate() {
+        let state = State {
+            buses: solution.states.row(i).to_vec(),
+            teams: solution.teams.row(i).to_vec(),
+        };
+        if state.is_terminal(&problem.graph) || (i == 0 && state.energize(&problem.graph).is_some())
+        {
+            continue;
+        }
+        naive_actions += NaiveActions
+            .all_actions_in_state(&state, &problem.graph)
+            .len();
+        actions += state_actions.len();
+    }
+    assert_eq!(permutational.generated, naive_actions);
+    assert_eq!(permutational.survived, on_way.generated);
+    assert_eq!(on_way.survived, actions);
+    assert!(permutational.survived < permutational.generated);
+    assert!(on_way.survived <= on_way.generated);
+
+    let benchmark = solution.get_benchmark_result();
+    assert_eq!(benchmark.action_stats.as_ref(), Some(stats));
+}
+
+/// "Wait for moving teams" condition must not prevent a team from energizing another bus before
+/// the en-route team arrives.
+#[test]
+fn wait_moving_regression_test() {
+    // Bus 2 can be energized after bus 1.
+    let graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 4, 1], [4, 0, 4], [1, 4, 0]]),
+        branches: vec![vec![], vec![2], vec![1]],
+        connected: vec![true, true, false],
+        pfs: ndarray::arr1(&[0.0, 0.0, 0.0]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    // The first team waits on bus 2 while the second one is moving to bus 1.
+    // Instead of waiting, the first team should energize bus 0 in the meantime.
+    let initial_teams = vec![
+        TeamState { time: 0, index: 2 },
+        TeamState { time: 4, index: 1 },
+    ];
+    let config = Config::default();
+
+    let state = State::start_state(&graph, initial_teams.clone());
+    let actions =
+        WaitMovingActions::<NaiveActions>::setup(&graph).all_actions_in_state(&state, &graph);
+    assert!(actions.contains(&vec![0, 1]));
+
+    let naive = solve_generic::<
+        TimedTransition,
+        NaiveExplorer<TimedTransition, NaiveActions, NaiveStateIndexer>,
+        TimedActionApplier<TimeUntilEnergization>,
+        NaiveTimedPolicySynthesizer,
+    >(&graph, initial_teams.clone(), &config)
+    .unwrap();
+    let wait_moving = solve_generic::<
+        TimedTransition,
+        NaiveExplorer<TimedTransition, WaitMovingActions<NaiveActions>, NaiveStateIndexer>,
+        TimedActionApplier<TimeUntilEnergization>,
+        NaiveTimedPolicySynthesizer,
+    >(&graph, initial_teams, &config)
+    .unwrap();
+    assert_eq!(naive.get_min_value(), wait_moving.get_min_value());
+}
+
+#[test]
+fn time_quantum_test() {
+    let graph = Graph {
+        travel_times: ndarray::arr2(&[
+            [0, 10, 21, 9],
+            [10, 0, 11, 19],
+            [21, 11, 0, 29],
+            [9, 19, 29, 0],
+        ]),
+        branches: vec![vec![1], vec![0, 2], vec![1]],
+        connected: vec![true, false, false],
+        pfs: ndarray::arr1(&[0.5, 0.25, 0.5]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 3 }];
+    let mut config = Config::default();
+    let solve = |config: &Config| {
+        solve_custom_timed(
+            &graph,
+            initial_teams.clone(),
+            config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilArrival>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap()
+    };
+    let base = solve(&config);
+    assert_eq!(base.time_quantum, 1);
+
+    // Quantum of 1 doesn't change anything.
+    let quantized = graph.quantized(1);
+    assert_eq!(quantized.travel_times, graph.travel_times);
+    config.time_quantum = 1;
+    let solution = solve(&config);
+    assert_eq!(solution.transitions, base.transitions);
+    assert_eq!(solution.values, base.values);
+    assert_eq!(solution.horizon, base.horizon);
+
+    let quantized = graph.quantized(10);
+    assert_eq!(
+        quantized.travel_times,
+        ndarray::arr2(&[[0, 1, 2, 1], [1, 0, 1, 2], [2, 1, 0, 3], [1, 2, 3, 0]])
+    );
+    config.time_quantum = 10;
+    let solution = solve(&config);
+    assert_eq!(solution.time_quantum, 10);
+    assert!(solution.horizon < base.horizon);
+    assert_eq!(solution.into_io(&graph).time_quantum, 10);
+
+    config.time_quantum = 0;
+    let result = solve_naive(&graph, initial_teams.clone(), &config);
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+#[test]
+fn team_skill_test() {
+    // Two buses directly connected to the source, teams start at nodes 2 and 3.
+    let mut graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 4, 2, 1], [4, 0, 3, 1], [2, 3, 0, 5], [1, 1, 5, 0]]),
+        branches: vec![vec![], vec![]],
+        connected: vec![true, true],
+        pfs: ndarray::arr1(&[0.0, 0.0]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    let initial_teams = vec![
+        TeamState { time: 0, index: 2 },
+        TeamState { time: 0, index: 3 },
+    ];
+    let config = Config::default();
+
+    // Returns the minimum value and the bus that the second team visits first.
+    let solve = |graph: &Graph, action_set: &str| {
+        let solution = solve_custom_timed(
+            graph,
+            initial_teams.clone(),
+            &config,
+            "NaiveStateIndexer",
+            action_set,
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap();
+        let action = &solution.transitions[0][solution.policy[0] as usize];
+        let successor = action[0].successor as usize;
+        (
+            solution.get_min_value(),
+            solution.teams[(successor, 1)].index,
+        )
+    };
+
+    let (unrestricted_value, bus) = solve(&graph, "NaiveActions");
+    assert_eq!(bus, 1);
+
+    // Second team cannot be dispatched to bus 1.
+    graph.team_bus_allowed = Some(ndarray::arr2(&[[true, true], [true, false]]));
+    let (value, bus) = solve(&graph, "NaiveActions");
+    assert_eq!(bus, 0);
+    assert!(value > unrestricted_value);
+    assert_eq!(solve(&graph, "PermutationalActions"), (value, bus));
+}
+
+#[test]
+fn deadline_test() {
+    // Two buses directly connected to the source, team starts at node 2.
+    let mut graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 4, 2], [4, 0, 3], [2, 3, 0]]),
+        branches: vec![vec![], vec![]],
+        connected: vec![true, true],
+        pfs: ndarray::arr1(&[0.0, 0.0]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 2 }];
+    let config = Config::default();
+
+    // Returns the minimum value and the bus that is visited first in the optimal policy.
+    let solve = |graph: &Graph| {
+        let solution = solve_custom_timed(
+            graph,
+            initial_teams.clone(),
+            &config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap();
+        let action = &solution.transitions[0][solution.policy[0] as usize];
+        let successor = action[0].successor as usize;
+        (
+            solution.get_min_value(),
+            solution.teams[(successor, 0)].index,
+        )
+    };
+
+    // Without deadlines, the closest bus is visited first.
+    assert_eq!(solve(&graph), (8.0, 0));
+
+    // Bus 1 is already past its deadline, so it's better to energize it first.
+    graph.deadlines = Some(vec![None, Some(0)]);
+    assert_eq!(solve(&graph), (13.0, 1));
+
+    // Regular policy synthesizer doesn't support deadlines.
+    let result = solve_custom_regular(
+        &graph,
+        initial_teams.clone(),
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "NaivePolicySynthesizer",
+    );
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+#[test]
+fn reachable_states_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, config) = input_graph.to_teams_problem(teams, None).unwrap();
+
+    let mut states: Vec<State> =
+        reachable_states::<RegularTransition, NaiveActions, NaiveStateIndexer, NaiveActionApplier>(
+            &problem.graph,
+            problem.initial_teams.clone(),
+        )
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let solution = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+    let mut expected: Vec<State> = (0..solution.transitions.len())
+        .map(|i| State {
+            buses: solution.states.row(i).to_vec(),
+            teams: solution.teams.row(i).to_vec(),
+        })
+        .collect();
+
+    states.sort();
+    expected.sort();
+    assert_eq!(states, expected);
+}
+
+/// Reducing the precision of transition probabilities to `f32` (see `f32-transitions` feature)
+/// must not change the optimal policy.
+#[test]
+// Probability is already f32 with minmem
+#[allow(clippy::unnecessary_cast)]
+fn reduced_precision_policy_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let solution = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+
+    let reduced: Vec<Vec<Vec<RegularTransition>>> = solution
+        .transitions
+        .iter()
+        .map(|actions| {
+            actions
+                .iter()
+                .map(|transitions| {
+                    transitions
+                        .iter()
+                        .map(|t| RegularTransition {
+                            p: (t.p as f32) as Probability,
+                            ..t.clone()
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+    let (values, policy) = NaivePolicySynthesizer::synthesize_policy(&reduced, solution.horizon);
+
+    assert_eq!(policy, solution.policy);
+    for (a, b) in values
+        .iter()
+        .flatten()
+        .zip(solution.values.iter().flatten())
+    {
+        assert!((a - b).abs() <= 1e-4 * b.abs().max(1.0));
+    }
+}
+
+/// Disabling memory tracking must not change the solution, but the memory usage is reported as
+/// 0 and the memory limit is not enforced.
+#[test]
+fn track_memory_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![io::Team {
+        index: Some(0),
+        latlng: None,
+        name: None,
+        node: None,
+    }];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let solution = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    if cfg!(feature = "track-memory") {
+        assert!(solution.max_memory > 0);
+    }
+
+    config.track_memory = false;
+    // The memory limit is not enforced without tracking.
+    config.max_memory = 0;
+    let untracked = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+    assert_eq!(untracked.max_memory, 0);
+    assert_eq!(untracked.policy, solution.policy);
+}
+
+#[test]
+#[cfg(feature = "track-memory")]
+fn allow_partial_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![io::Team {
+        index: Some(0),
+        latlng: None,
+        name: None,
+        node: None,
+    }];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let complete = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    assert!(!complete.partial);
+
+    // The limit is exceeded right after the initial state.
+    config.max_memory = 1;
+    let result = solve_naive(&problem.graph, problem.initial_teams.clone(), &config);
+    assert!(matches!(result, Err(SolveFailure::OutOfMemory { .. })));
+
+    config.allow_partial = true;
+    let partial = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+    assert!(partial.partial);
+    assert!(partial.transitions.len() > 1);
+    assert!(partial.transitions.len() < complete.transitions.len());
+    // Only the initial state is explored.
+    for (i, actions) in partial.transitions.iter().enumerate().skip(1) {
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].len(), 1);
+        assert_eq!(actions[0][0].successor as usize, i);
+    }
+    assert!(partial.get_min_value() <= complete.get_min_value());
+
+    let json = serde_json::to_value(partial.into_io(&problem.graph)).unwrap();
+    assert_eq!(json["partial"], true);
+    let json = serde_json::to_value(complete.into_io(&problem.graph)).unwrap();
+    assert_eq!(json["partial"], false);
+}
+
+/// The unexplored states of a partial solution cost their damaged buses per unit time until the
+/// horizon, which is a lower bound of their values in the complete solution with the same
+/// horizon.
+#[test]
+#[cfg(feature = "track-memory")]
+fn partial_values_test() {
+    fn check<T: Transition>(partial: &Solution<T>, complete: &Solution<T>) {
+        assert!(partial.partial);
+        assert_eq!(partial.horizon, complete.horizon);
+        let complete_values = get_state_values(&complete.values);
+        let partial_values = get_state_values(&partial.values);
+        // Only the initial state is explored.
+        for (i, &value) in partial_values.iter().enumerate().skip(1) {
+            let buses = partial.states.row(i);
+            let teams = partial.teams.row(i);
+            let damaged = buses
+                .iter()
+                .filter(|&&bus| bus == BusState::Damaged)
+                .count();
+            assert_eq!(
+                value,
+                (damaged * partial.horizon) as Value,
+                "Value of unexplored state {i}"
+            );
+            let j = (0..complete.transitions.len())
+                .find(|&j| complete.states.row(j) == buses && complete.teams.row(j) == teams)
+                .expect("Unexplored state is not in the complete solution");
+            assert!(value <= complete_values[j]);
+        }
+        assert!(partial_values[0] <= complete_values[0]);
+    }
+
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![io::Team {
+        index: Some(0),
+        latlng: None,
+        name: None,
+        node: None,
+    }];
+    let (problem, config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let partial_config = |horizon: usize| Config {
+        max_memory: 1,
+        allow_partial: true,
+        horizon: HorizonSpec::Absolute(horizon),
+        ..config.clone()
+    };
+
+    let complete = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    let partial = solve_naive(
+        &problem.graph,
+        problem.initial_teams.clone(),
+        &partial_config(complete.horizon),
+    )
+    .unwrap();
+    check(&partial, &complete);
+
+    let solve_timed = |config: &Config| {
+        solve_custom_timed(
+            &problem.graph,
+            problem.initial_teams.clone(),
+            config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap()
+    };
+    let complete = solve_timed(&config);
+    let partial = solve_timed(&partial_config(complete.horizon));
+    check(&partial, &complete);
+}
+
+#[test]
+fn policy_only_export_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let problem = io::TeamProblem {
+        name: None,
+        graph: input_graph,
+        teams: vec![
+            io::Team {
+                index: Some(0),
+                latlng: None,
+                name: None,
+                node: None,
+            };
+            2
+        ],
+        horizon: HorizonSpec::Auto,
+        pfo: None,
+        time_func: io::TimeFunc::default(),
+        strict_branches: false,
+        repair_times: None,
+        time_scale: None,
+        depots: Vec::new(),
+        deadlines: None,
+        start_distribution: None,
+    };
+    let solution = problem.clone().solve_naive().unwrap();
+
+    let mut policy_path: std::path::PathBuf = std::env::temp_dir();
+    policy_path.push("dmslib-test.pe0-2-team.policy.bin");
+    solution.export_policy_only(&policy_path).unwrap();
+    let mut save_path: std::path::PathBuf = std::env::temp_dir();
+    save_path.push("dmslib-test.pe0-2-team.bin");
+    io::fs::save_solution(problem, solution.clone(), None, &save_path).unwrap();
+    assert!(
+        std::fs::metadata(&policy_path).unwrap().len()
+            < std::fs::metadata(&save_path).unwrap().len()
+    );
+
+    let policy = io::fs::PolicyOnly::load(&policy_path).unwrap();
+    assert_eq!(policy.travel_times, solution.travel_times);
+    assert_eq!(policy.team_nodes, solution.team_nodes);
+    let mut replay = io::SolutionReplay::new(solution.clone());
+    for i in 0..solution.transitions.len() {
+        let state = solution.get_state(i);
+        let action = policy.get_action(&state).unwrap();
+        assert_eq!(action.index, solution.policy[i] as usize);
+        replay.set_state(&state).unwrap();
+        if let Some(optimal) = replay.optimal_action() {
+            assert_eq!(action, optimal);
+        } else {
+            assert_eq!(
+                action.targets,
+                state
+                    .teams
+                    .iter()
+                    .map(|team| team.index)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
+#[test]
+fn team_move_cost_test() {
+    // Three directly connected buses on a line. Team 0 starts next to bus 0 and can energize all
+    // buses alone, team 1 starts at a depot farther away but near bus 2, where it can stay.
+    let graph = Graph {
+        travel_times: ndarray::arr2(&[
+            [0, 1, 2, 1, 4],
+            [1, 0, 1, 2, 3],
+            [2, 1, 0, 3, 2],
+            [1, 2, 3, 0, 5],
+            [4, 3, 2, 5, 0],
+        ]),
+        branches: vec![vec![], vec![], vec![]],
+        connected: vec![true, true, true],
+        pfs: ndarray::arr1(&[0.0, 0.0, 0.0]),
+        team_nodes: Array2::default((0, 0)),
+        depots: vec![4],
+        ..Default::default()
+    };
+    let initial_teams = vec![
+        TeamState { time: 0, index: 3 },
+        TeamState { time: 0, index: 4 },
+    ];
+    // Number of teams that are dispatched in the initial state.
+    let dispatched_teams = |solution: &Solution<RegularTransition>| {
+        let successor = solution.transitions[0][solution.policy[0] as usize][0].successor;
+        solution
+            .teams
+            .row(successor as usize)
+            .iter()
+            .zip(initial_teams.iter())
+            .filter(|(next, initial)| next.index != initial.index)
+            .count()
+    };
+
+    let mut config = Config::default();
+    let solution = solve_naive(&graph, initial_teams.clone(), &config).unwrap();
+    // Both teams are dispatched: 3 + 2 buses remain unenergized.
+    assert_eq!(get_min_value(&solution.values), 5.0);
+    assert_eq!(dispatched_teams(&solution), 2);
+
+    config.team_move_cost = Some(5.0);
+    let solution = solve_naive(&graph, initial_teams.clone(), &config).unwrap();
+    // A single team does 3 moves instead of 4 moves of both teams: 3 + 2 + 1 + 3 * 5
+    assert_eq!(get_min_value(&solution.values), 21.0);
+    assert_eq!(dispatched_teams(&solution), 1);
+
+    config.team_move_cost = Some(-1.0);
+    assert!(matches!(
+        solve_naive(&graph, initial_teams, &config),
+        Err(SolveFailure::BadInput(_))
+    ));
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn parallel_benchmark_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![io::Team {
+        index: Some(0),
+        latlng: None,
+        name: None,
+        node: None,
+    }];
+    let (problem, config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let summarize = |results: Vec<io::OptimizationBenchmarkResult>| {
+        results
+            .into_iter()
+            .map(|result| {
+                let io::OptimizationInfo {
+                    indexer,
+                    actions,
+                    transitions,
+                    ..
+                } = result.optimizations;
+                let result = result.result.unwrap();
+                (
+                    indexer,
+                    actions,
+                    transitions,
+                    result.states,
+                    result.transitions,
+                    result.value,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let serial = summarize(benchmark_all(
+        &problem.graph,
+        problem.initial_teams.clone(),
+        &config,
+    ));
+    assert_eq!(serial.len(), all_optimizations().len());
+    let parallel = summarize(
+        benchmark_all_parallel(&problem.graph, problem.initial_teams, &config, 4).unwrap(),
+    );
+    assert_eq!(serial, parallel);
+}
+
+#[test]
+fn store_actions_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let solution = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    assert!(solution.actions.is_none());
+
+    config.store_actions = true;
+    config.global_wait = Some(1);
+    let solution = solve_custom_timed(
+        &problem.graph,
+        problem.initial_teams,
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
+    )
+    .unwrap();
+    let actions = solution.actions.as_ref().unwrap();
+    assert_eq!(actions.len(), solution.transitions.len());
+    for (i, (state_actions, state_transitions)) in
+        actions.iter().zip(solution.transitions.iter()).enumerate()
+    {
+        assert_eq!(state_actions.len(), state_transitions.len());
+        // The destinations of the teams in the successors are the targets of the actions.
+        for (action, transitions) in state_actions.iter().zip(state_transitions.iter()) {
+            let successor = transitions[0].successor as usize;
+            let destinations: Vec<BusIndex> = solution
+                .teams
+                .row(successor)
+                .iter()
+                .map(|t| t.index)
+                .collect();
+            assert_eq!(action, &destinations, "State {i}");
+        }
+    }
+    // Some optimal actions dispatch teams.
+    assert!((0..actions.len()).any(|i| {
+        let positions: Vec<BusIndex> = solution.teams.row(i).iter().map(|t| t.index).collect();
+        actions[i][solution.policy[i] as usize] != positions
+    }));
+
+    let io_solution = solution.into_io(&problem.graph);
+    let json = serde_json::to_string(&io_solution).unwrap();
+    let decoded: io::TeamSolution<TimedTransition> = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.actions, io_solution.actions);
+}
+
+#[test]
+fn enforce_progress_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let solve = |config: &Config, action_set: &str| {
+        solve_custom_regular(
+            &problem.graph,
+            problem.initial_teams.clone(),
+            config,
+            "NaiveStateIndexer",
+            action_set,
+            "NaivePolicySynthesizer",
+        )
+        .unwrap()
+    };
+    let enforced = solve(&config, "NaiveActions");
+    config.enforce_progress = false;
+    // Teams may move back and forth without progress, so the state graph is cyclic and the
+    // horizon cannot be determined automatically.
+    let result = solve_custom_regular(
+        &problem.graph,
+        problem.initial_teams.clone(),
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "NaivePolicySynthesizer",
+    );
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+    config.horizon = HorizonSpec::Absolute(enforced.horizon);
+    for action_set in ["NaiveActions", "PermutationalActions"] {
+        let exhaustive = solve(&config, action_set);
+        assert_eq!(exhaustive.get_min_value(), enforced.get_min_value());
+        assert!(
+            get_transition_count(&exhaustive.transitions)
+                > get_transition_count(&enforced.transitions)
+        );
+    }
+
+    let result = solve_custom_timed(
+        &problem.graph,
+        problem.initial_teams.clone(),
+        &config,
+        "NaiveStateIndexer",
+        "NaiveActions",
+        "TimedActionApplier<TimeUntilEnergization>",
+        "NaiveTimedPolicySynthesizer",
+    );
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+#[test]
+fn store_all_action_values_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let full = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+
+    config.store_all_action_values = false;
+    let reduced = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+    assert_eq!(reduced.get_min_value(), full.get_min_value());
+    assert_eq!(reduced.policy, full.policy);
+    assert_eq!(reduced.values.len(), full.values.len());
+    for (i, values) in reduced.values.iter().enumerate() {
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0], full.values[i][full.policy[i] as usize]);
+    }
+
+    // The values of the other actions are required for explaining a state.
+    let index = (0..full.transitions.len())
+        .find(|&i| full.transitions[i].len() > 1)
+        .unwrap();
+    assert!(reduced
+        .into_io(&problem.graph)
+        .explain_state(index)
+        .is_err());
+    assert!(full.into_io(&problem.graph).explain_state(index).is_ok());
+}
+
+#[test]
+fn max_outcomes_per_action_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, mut config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let exact = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    let max_outcomes = exact
+        .transitions
+        .iter()
+        .flatten()
+        .map(|transitions| transitions.len())
+        .max()
+        .unwrap();
+    assert!(max_outcomes > 2);
+
+    // No action has more outcomes than the cap, so nothing is sampled.
+    config.max_outcomes_per_action = Some(max_outcomes);
+    let capped = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    assert_eq!(capped.get_min_value(), exact.get_min_value());
+    assert_eq!(capped.transitions, exact.transitions);
+
+    config.max_outcomes_per_action = Some(2);
+    let sampled = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    assert!(get_transition_count(&sampled.transitions) < get_transition_count(&exact.transitions));
+    for transitions in sampled.transitions.iter().flatten() {
+        assert!(transitions.len() <= 2);
+        let total: f64 = transitions.iter().map(|t| t.p as f64).sum();
+        assert!(
+            (total - 1.0).abs() < 1e-6,
+            "Probabilities add up to {total}"
+        );
+    }
+    // Sampling is reproducible.
+    let resampled = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+    assert_eq!(resampled.transitions, sampled.transitions);
+
+    config.max_outcomes_per_action = Some(0);
+    let result = solve_naive(&problem.graph, problem.initial_teams, &config);
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+}
+
+#[test]
+fn rolling_solve_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(1),
+            latlng: None,
+            name: None,
+            node: None,
+        },
+        io::Team {
+            index: Some(6),
+            latlng: None,
+            name: None,
+            node: None,
+        },
+    ];
+    let (problem, config) = input_graph.to_teams_problem(teams, None).unwrap();
+
+    // Follow the optimal policy of a single solve along the most likely outcomes.
+    let solution = solve_naive(
+        &problem.graph,
+        problem.initial_teams.clone(),
+        &Config {
+            store_actions: true,
+            ..config.clone()
+        },
+    )
+    .unwrap();
+    let actions = solution.actions.as_ref().unwrap();
+    let mut expected_actions = Vec::new();
+    let mut index = 0;
+    loop {
+        let action = solution.policy[index] as usize;
+        let transitions = &solution.transitions[index][action];
+        if transitions.len() == 1 && transitions[0].successor as usize == index {
+            break;
+        }
+        expected_actions.push(actions[index][action].clone());
+        index = transitions[most_likely_outcome(transitions)].successor as usize;
+    }
+    assert!(expected_actions.len() > 1);
+
+    let rolling = rolling_solve(&problem, &config, expected_actions.len()).unwrap();
+    assert_eq!(rolling.solves, 1);
+    assert_eq!(rolling.actions, expected_actions);
+    assert_eq!(rolling.states.len(), expected_actions.len() + 1);
+    let state_at = |i: usize| State {
+        buses: solution.states.row(i).to_vec(),
+        teams: solution.teams.row(i).to_vec(),
+    };
+    assert_eq!(rolling.states[0], state_at(0));
+    assert_eq!(rolling.states.last().unwrap(), &state_at(index));
+
+    // Re-solve after each step.
+    let rolling = rolling_solve(&problem, &config, 1).unwrap();
+    assert_eq!(rolling.solves, rolling.actions.len());
+    assert_eq!(rolling.states.len(), rolling.actions.len() + 1);
+    assert!(rolling.states.last().unwrap().is_terminal(&problem.graph));
+
+    assert!(matches!(
+        rolling_solve(&problem, &config, 0),
+        Err(SolveFailure::BadInput(_))
+    ));
+}
+
+#[test]
+fn exploration_gauge_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let gauge = std::sync::Arc::new(ExplorationGauge::new());
+    assert_eq!(gauge.indexed_states(), 0);
+    let config = Config {
+        exploration_gauge: Some(gauge.clone()),
+        ..config
+    };
+    let solution = solve_naive(&problem.graph, problem.initial_teams, &config).unwrap();
+    assert_eq!(gauge.indexed_states(), solution.transitions.len());
+    assert_eq!(gauge.frontier_size(), 0);
+}
+
+#[test]
+fn prepared_problem_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let team_at = |index: usize| io::Team {
+        index: Some(index),
+        latlng: None,
+        name: None,
+        node: None,
+    };
+    let (problem, config) = input_graph
+        .clone()
+        .to_teams_problem(vec![team_at(0), team_at(0)], None)
+        .unwrap();
+    let prepared = PreparedProblem::new(problem.graph);
+
+    type E<'a> = NaiveExplorer<
+        'a,
+        TimedTransition,
+        FilterEnergizedOnWay<'a, PermutationalActions<'a>>,
+        BitStackStateIndexer,
+    >;
+    type AA = TimedActionApplier<TimeUntilEnergization>;
+    for (a, b) in [(0, 0), (1, 6), (3, 7)] {
+        let (problem, config) = input_graph
+            .clone()
+            .to_teams_problem(vec![team_at(a), team_at(b)], None)
+            .unwrap();
+        let expected = solve_generic::<TimedTransition, E, AA, NaiveTimedPolicySynthesizer>(
+            &problem.graph,
+            problem.initial_teams.clone(),
+            &config,
+        )
+        .unwrap();
+        let solution = prepared
+            .solve_scenario::<TimedTransition, E, AA, NaiveTimedPolicySynthesizer>(
+                problem.initial_teams,
+                None,
+                &config,
+            )
+            .unwrap();
+        assert_eq!(solution.transitions, expected.transitions);
+        assert_eq!(solution.values, expected.values);
+        assert_eq!(solution.policy, expected.policy);
+    }
+
+    // Starting from given bus states
+    let teams = vec![TeamState { time: 0, index: 0 }; 2];
+    let mut buses = State::start_state(&prepared.graph, teams.clone()).buses;
+    buses[0] = BusState::Energized;
+    let start_state = State {
+        buses: buses.clone(),
+        teams: teams.clone(),
+    };
+    let expected = solve_generic_from_state::<TimedTransition, E, AA, NaiveTimedPolicySynthesizer>(
+        &prepared.graph,
+        start_state,
+        &config,
+    )
+    .unwrap();
+    let solution = prepared
+        .solve_scenario::<TimedTransition, E, AA, NaiveTimedPolicySynthesizer>(
+            teams,
+            Some(buses),
+            &config,
+        )
+        .unwrap();
+    assert_eq!(solution.transitions, expected.transitions);
+    assert_eq!(solution.values, expected.values);
+
+    // Mismatching bus states are rejected.
+    assert!(matches!(
+        prepared.solve_scenario::<TimedTransition, E, AA, NaiveTimedPolicySynthesizer>(
+            vec![TeamState { time: 0, index: 0 }],
+            Some(vec![BusState::Unknown]),
+            &config,
+        ),
+        Err(SolveFailure::BadInput(_))
+    ));
+}
+
+#[test]
+fn makespan_objective_test() {
+    // A single team starts at node 3, and the buses are on a line at -3, 1, and 4 relative to
+    // it. Each bus is directly connected to a source and certain to be energized.
+    let positions: [i32; 4] = [-3, 1, 4, 0];
+    let graph = Graph {
+        travel_times: Array2::from_shape_fn((4, 4), |(i, j)| {
+            (positions[i] - positions[j]).unsigned_abs() as Time
+        }),
+        branches: vec![vec![]; 3],
+        connected: vec![true; 3],
+        pfs: ndarray::arr1(&[0.0, 0.0, 0.0]),
+        team_nodes: Array2::zeros((4, 2)),
+        ..Default::default()
+    };
+    let initial_teams = vec![TeamState { time: 0, index: 3 }];
+    let solve = |objective: Objective| {
+        let config = Config {
+            objective,
+            ..Default::default()
+        };
+        solve_custom_timed(
+            &graph,
+            initial_teams.clone(),
+            &config,
+            "NaiveStateIndexer",
+            "NaiveActions",
+            "TimedActionApplier<TimeUntilEnergization>",
+            "NaiveTimedPolicySynthesizer",
+        )
+        .unwrap()
+    };
+    let first_target = |solution: &Solution<TimedTransition>| {
+        let successor = solution.transitions[0][solution.policy[0] as usize][0].successor;
+        solution.teams[[successor as usize, 0]].index
+    };
+
+    // Visiting the buses at 1, 4, -3 minimizes the cumulative cost 3 * 1 + 2 * 3 + 1 * 7 = 16,
+    // but it takes 11 time units.
+    let solution = solve(Objective::Cost);
+    assert_eq!(solution.get_min_value(), 16.0);
+    assert_eq!(first_target(&solution), 1);
+
+    // Visiting the buses at -3, 1, 4 takes 10 time units, the minimum makespan.
+    let solution = solve(Objective::Makespan);
+    assert_eq!(solution.get_min_value(), 10.0);
+    assert_eq!(first_target(&solution), 0);
+}
+
+/// Warm-starting the Gauss-Seidel synthesizer for a perturbed problem with the values of the
+/// original solution must yield the same values and policy as solving it from scratch.
+#[test]
+fn warm_start_policy_test() {
+    let input_graph: io::Graph = serde_json::from_str(SYSTEM_PAPER_EXAMPLE_0).unwrap();
+    let teams = vec![
+        io::Team {
+            index: Some(0),
+            latlng: None,
+            name: None,
+            node: None,
+        };
+        2
+    ];
+    let (problem, config) = input_graph.to_teams_problem(teams, None).unwrap();
+    let solution = solve_naive(&problem.graph, problem.initial_teams.clone(), &config).unwrap();
+
+    let mut graph = problem.graph.clone();
+    graph.pfs.mapv_inplace(|pf| pf * 0.9);
+    let perturbed = solve_naive(&graph, problem.initial_teams, &config).unwrap();
+    assert_eq!(perturbed.transitions.len(), solution.transitions.len());
+    assert_eq!(perturbed.horizon, solution.horizon);
+    let initial_values = get_state_values(&solution.values);
+    assert_ne!(initial_values, get_state_values(&perturbed.values));
+
+    let (values, policy, sweeps) = GaussSeidelPolicySynthesizer::synthesize_with_sweeps(
+        &perturbed.transitions,
+        perturbed.horizon,
+    );
+    let (warm_values, warm_policy, warm_sweeps) =
+        GaussSeidelPolicySynthesizer::synthesize_warm_with_sweeps(
+            &perturbed.transitions,
+            perturbed.horizon,
+            &initial_values,
+        );
+    assert_eq!(warm_values, values);
+    assert_eq!(warm_policy, policy);
+    // The rates are exact from the start, so fewer sweeps are needed.
+    assert!(warm_sweeps.unwrap() < sweeps.unwrap());
+
+    assert_eq!(
+        GaussSeidelPolicySynthesizer::synthesize_policy_warm(
+            &perturbed.transitions,
+            perturbed.horizon,
+            &initial_values,
+        ),
+        Some((values, policy))
+    );
+    assert!(NaivePolicySynthesizer::synthesize_policy_warm(
+        &perturbed.transitions,
+        perturbed.horizon,
+        &initial_values,
+    )
+    .is_none());
+}
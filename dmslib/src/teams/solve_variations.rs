@@ -2,6 +2,18 @@
 use crate::io::OptimizationInfo;
 
 use super::*;
+use std::borrow::Cow;
+
+/// Apply [`Config::time_quantum`] to the given graph, borrowing it if it's not quantized.
+fn quantize_graph<'a>(graph: &'a Graph, config: &Config) -> Result<Cow<'a, Graph>, SolveFailure> {
+    match config.time_quantum {
+        0 => Err(SolveFailure::BadInput(
+            "Time quantum must be positive".to_string(),
+        )),
+        1 => Ok(Cow::Borrowed(graph)),
+        quantum => Ok(Cow::Owned(graph.quantized(quantum))),
+    }
+}
 
 /// Solve a field-team restoration problem on this graph with the given teams without any
 /// action elimination or optimizations.
@@ -10,12 +22,84 @@ pub fn solve_naive(
     initial_teams: Vec<TeamState>,
     config: &Config,
 ) -> Result<Solution<RegularTransition>, SolveFailure> {
+    let graph = quantize_graph(graph, config)?;
     solve_generic::<
         RegularTransition,
         NaiveExplorer<RegularTransition, NaiveActions, NaiveStateIndexer>,
         NaiveActionApplier,
         NaivePolicySynthesizer,
-    >(graph, initial_teams, config)
+    >(&graph, initial_teams, config)
+}
+
+/// Solve a field-team restoration problem on this graph starting from the given state without
+/// any action elimination or optimizations.
+///
+/// See [`solve_naive`].
+pub fn solve_naive_from_state(
+    graph: &Graph,
+    start_state: State,
+    config: &Config,
+) -> Result<Solution<RegularTransition>, SolveFailure> {
+    let graph = quantize_graph(graph, config)?;
+    solve_generic_from_state::<
+        RegularTransition,
+        NaiveExplorer<RegularTransition, NaiveActions, NaiveStateIndexer>,
+        NaiveActionApplier,
+        NaivePolicySynthesizer,
+    >(&graph, start_state, config)
+}
+
+/// Count the states reachable from the given teams on this graph without storing the transitions.
+///
+/// Uses the same exploration as [`solve_naive`], hence the result is equal to the number of
+/// states in its solution. This is much cheaper than solving and can be used to estimate the
+/// problem size.
+pub fn count_states(
+    graph: &Graph,
+    initial_teams: Vec<TeamState>,
+    config: &Config,
+) -> Result<usize, SolveFailure> {
+    type E<'a> = NaiveExplorer<'a, RegularTransition, NaiveActions, NaiveStateIndexer>;
+    let graph = quantize_graph(graph, config)?;
+    E::memory_limited_count_states::<NaiveActionApplier>(&graph, initial_teams, config)
+}
+
+/// Get all actions in the given state with the action set that has the given name.
+///
+/// Accepts the action set names in [`solve_custom_regular`] and [`solve_custom_timed`] together
+/// with the remaining action set wrappers.
+pub fn actions_in_state(
+    graph: &Graph,
+    state: &State,
+    action_set: &str,
+) -> Result<Vec<Vec<TeamAction>>, SolveFailure> {
+    state.validate(graph).map_err(SolveFailure::BadInput)?;
+    macro_rules! try_action_sets {
+        ($($act:ty),+ $(,)?) => {
+            $(
+                if action_set == stringify!($act) {
+                    return Ok(<$act>::setup(graph).all_actions_in_state(state, graph));
+                }
+            )+
+        };
+    }
+    try_action_sets!(
+        NaiveActions,
+        PermutationalActions,
+        FilterOnWay<NaiveActions>,
+        FilterOnWay<PermutationalActions>,
+        FilterEnergizedOnWay<NaiveActions>,
+        FilterEnergizedOnWay<PermutationalActions>,
+        WaitMovingActions<NaiveActions>,
+        WaitMovingActions<PermutationalActions>,
+        FilterTeamSkill<NaiveActions>,
+        FilterTeamSkill<PermutationalActions>,
+        SortedActions<NaiveActions>,
+        SortedActions<PermutationalActions>,
+    );
+    Err(SolveFailure::BadInput(format!(
+        "Undefined action set: {action_set}"
+    )))
 }
 
 /// Macro for generating solve code that reads class names from variables and constructs a code
@@ -134,7 +218,7 @@ macro_rules! generate_solve_code {
                 solve($g, $it, $oh)
             )
         } else {
-            Err(SolveFailure::BadInput(format!("Undefined action applier: {}", $actstr)))
+            Err(SolveFailure::BadInput(format!("Undefined action applier: {}", $appstr)))
         }
     };
     (
@@ -165,26 +249,87 @@ macro_rules! generate_solve_code {
             )
         }
     };
+    // Iterate through policy synthesizer
+    (
+        transition = $tt:ty,
+        policy($psstr:ident) = [$ps:ty $(,)?],
+        $($rest:tt)*
+    ) => {
+        if $psstr == stringify!($ps) {
+            generate_solve_code!(
+                transition = $tt,
+                policy = $ps,
+                $($rest)*
+            )
+        } else {
+            Err(SolveFailure::BadInput(format!("Undefined policy synthesizer: {}", $psstr)))
+        }
+    };
+    (
+        transition = $tt:ty,
+        policy($psstr:ident) = [$ps:ty, $($psrem:ty),+ $(,)?],
+        $($rest:tt)*
+    ) => {
+        if $psstr == stringify!($ps) {
+            generate_solve_code!(
+                transition = $tt,
+                policy = $ps,
+                $($rest)*
+            )
+        } else {
+            generate_solve_code!(
+                transition = $tt,
+                policy($psstr) = [$($psrem),+],
+                $($rest)*
+            )
+        }
+    };
+}
+
+/// Get the name of the default policy synthesizer for the given action applier.
+///
+/// - [`NaivePolicySynthesizer`] for [`NaiveActionApplier`] ([`RegularTransition`]s).
+/// - [`NaiveTimedPolicySynthesizer`] for others ([`TimedTransition`]s).
+pub fn default_synthesizer(action_applier: &str) -> &'static str {
+    if action_applier == stringify!(NaiveActionApplier) {
+        stringify!(NaivePolicySynthesizer)
+    } else {
+        stringify!(NaiveTimedPolicySynthesizer)
+    }
 }
 
 /// Solve the field-teams restoration problem with [`RegularTransition`]s (classic MDP
 /// transitions without time) and the given action set class.
+///
+/// Available policy synthesizers:
+/// - `NaivePolicySynthesizer`
+/// - `GaussSeidelPolicySynthesizer`
+/// - `PolicyIterationSynthesizer`
 pub fn solve_custom_regular(
     graph: &Graph,
     initial_teams: Vec<TeamState>,
     config: &Config,
     indexer: &str,
     action_set: &str,
+    synthesizer: &str,
 ) -> Result<Solution<RegularTransition>, SolveFailure> {
+    let graph = quantize_graph(graph, config)?;
+    let graph: &Graph = &graph;
     generate_solve_code! {
         transition = RegularTransition,
-        policy = NaivePolicySynthesizer,
+        policy(synthesizer) = [
+            NaivePolicySynthesizer,
+            GaussSeidelPolicySynthesizer,
+            PolicyIterationSynthesizer,
+        ],
         action_applier = NaiveActionApplier,
         indexer(indexer) = [
             NaiveStateIndexer,
             BitStackStateIndexer,
             SortedStateIndexer<NaiveStateIndexer>,
             SortedStateIndexer<BitStackStateIndexer>,
+            SymmetricStateIndexer<NaiveStateIndexer>,
+            SymmetricStateIndexer<BitStackStateIndexer>,
         ],
         action_set(action_set) = [
             NaiveActions,
@@ -193,6 +338,8 @@ pub fn solve_custom_regular(
             FilterOnWay<PermutationalActions>,
             FilterEnergizedOnWay<NaiveActions>,
             FilterEnergizedOnWay<PermutationalActions>,
+            SortedActions<NaiveActions>,
+            SortedActions<PermutationalActions>,
         ],
         solve(graph, initial_teams, config)
     }
@@ -201,6 +348,10 @@ pub fn solve_custom_regular(
 /// Solve the field-teams restoration problem with [`TimedTransition`]s and the given:
 /// - action applier class (variations of `TimedActionApplier<T>` where `T` determines time)
 /// - action set class
+/// - policy synthesizer class
+///
+/// Available policy synthesizers:
+/// - `NaiveTimedPolicySynthesizer`
 pub fn solve_custom_timed(
     graph: &Graph,
     initial_teams: Vec<TeamState>,
@@ -208,10 +359,20 @@ pub fn solve_custom_timed(
     indexer: &str,
     action_set: &str,
     action_applier: &str,
+    synthesizer: &str,
 ) -> Result<Solution<TimedTransition>, SolveFailure> {
+    if !config.enforce_progress && action_applier != "TimedActionApplier<ConstantTime>" {
+        return Err(SolveFailure::BadInput(format!(
+            "{action_applier} requires the progress condition to be enforced"
+        )));
+    }
+    let graph = quantize_graph(graph, config)?;
+    let graph: &Graph = &graph;
     generate_solve_code! {
         transition = TimedTransition,
-        policy = NaiveTimedPolicySynthesizer,
+        policy(synthesizer) = [
+            NaiveTimedPolicySynthesizer,
+        ],
         action_applier(action_applier) = [
             TimedActionApplier<ConstantTime>,
             TimedActionApplier<TimeUntilArrival>,
@@ -222,6 +383,8 @@ pub fn solve_custom_timed(
             BitStackStateIndexer,
             SortedStateIndexer<NaiveStateIndexer>,
             SortedStateIndexer<BitStackStateIndexer>,
+            SymmetricStateIndexer<NaiveStateIndexer>,
+            SymmetricStateIndexer<BitStackStateIndexer>,
         ],
         action_set(action_set) = [
             NaiveActions,
@@ -230,6 +393,8 @@ pub fn solve_custom_timed(
             FilterOnWay<PermutationalActions>,
             FilterEnergizedOnWay<NaiveActions>,
             FilterEnergizedOnWay<PermutationalActions>,
+            SortedActions<NaiveActions>,
+            SortedActions<PermutationalActions>,
         ],
         solve(graph, initial_teams, config)
     }
@@ -238,6 +403,7 @@ pub fn solve_custom_timed(
 /// Solve the field-teams restoration problem with the given:
 /// - action applier class
 /// - action set class
+/// - policy synthesizer class (see [`default_synthesizer`])
 ///
 /// Returns a [`io::GenericTeamSolution`] on success.
 pub fn solve_custom(
@@ -247,9 +413,17 @@ pub fn solve_custom(
     indexer: &str,
     action_set: &str,
     action_applier: &str,
+    synthesizer: &str,
 ) -> Result<io::GenericTeamSolution, SolveFailure> {
     if action_applier == stringify!(NaiveActionApplier) {
-        let solution = solve_custom_regular(graph, initial_teams, config, indexer, action_set)?;
+        let solution = solve_custom_regular(
+            graph,
+            initial_teams,
+            config,
+            indexer,
+            action_set,
+            synthesizer,
+        )?;
         Ok(io::GenericTeamSolution::Regular(solution.into_io(graph)))
     } else {
         let solution = solve_custom_timed(
@@ -259,14 +433,173 @@ pub fn solve_custom(
             indexer,
             action_set,
             action_applier,
+            synthesizer,
         )?;
         Ok(io::GenericTeamSolution::Timed(solution.into_io(graph)))
     }
 }
 
+/// Solution of a field-teams restoration problem with uncertain team starts, see
+/// [`solve_start_distribution`].
+pub struct StartDistributionSolution {
+    /// Probability of each start configuration.
+    pub probabilities: Vec<f64>,
+    /// Solution for each start configuration in the same order.
+    pub solutions: Vec<io::GenericTeamSolution>,
+}
+
+impl StartDistributionSolution {
+    /// Get the expected optimal value at the root, i.e., the optimal value of each start
+    /// configuration weighted by its probability.
+    pub fn get_min_value(&self) -> f64 {
+        self.probabilities
+            .iter()
+            .zip(self.solutions.iter())
+            .map(|(&p, solution)| p * solution.get_benchmark_result().value as f64)
+            .sum()
+    }
+}
+
+/// Solve the field-teams restoration problem in which the start of the teams is given by a
+/// probability distribution over the start configurations, e.g., [`Problem::start_distribution`].
+///
+/// The root is a chance node that branches into the start state of each configuration with its
+/// probability without any cost or time. The configuration is revealed before the teams are
+/// dispatched, so the optimal policy of each branch is the optimal policy of that start, and the
+/// value of the root is the expectation of their values.
+/// Hence, each branch is solved with [`solve_custom`] with the given settings.
+pub fn solve_start_distribution(
+    graph: &Graph,
+    starts: &[(f64, Vec<TeamState>)],
+    config: &Config,
+    indexer: &str,
+    action_set: &str,
+    action_applier: &str,
+    synthesizer: &str,
+) -> Result<StartDistributionSolution, SolveFailure> {
+    let solutions = starts
+        .iter()
+        .map(|(_, teams)| {
+            solve_custom(
+                graph,
+                teams.clone(),
+                config,
+                indexer,
+                action_set,
+                action_applier,
+                synthesizer,
+            )
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(StartDistributionSolution {
+        probabilities: starts.iter().map(|(p, _)| *p).collect(),
+        solutions,
+    })
+}
+
+/// Result of [`rolling_solve`].
+pub struct RollingSolution {
+    /// Committed team actions in order, one for each transition taken.
+    pub actions: Vec<Vec<TeamAction>>,
+    /// States visited, starting with the start state and ending with a terminal state.
+    /// `actions[i]` is taken in `states[i]`, so this has one more element.
+    pub states: Vec<State>,
+    /// Number of times the problem is solved.
+    pub solves: usize,
+}
+
+/// Get the index of the outcome with the highest probability, the first one if tied.
+///
+/// This is the default outcome selection of [`rolling_solve`].
+pub fn most_likely_outcome(transitions: &[RegularTransition]) -> usize {
+    transitions
+        .iter()
+        .enumerate()
+        .reduce(|best, t| if t.1.p > best.1.p { t } else { best })
+        .expect("No transitions in an action")
+        .0
+}
+
+/// Solve the field-teams restoration problem in a rolling-horizon manner, following the most
+/// likely outcome of each action.
+///
+/// See [`rolling_solve_with`].
+pub fn rolling_solve(
+    problem: &Problem,
+    config: &Config,
+    commit_steps: usize,
+) -> Result<RollingSolution, SolveFailure> {
+    rolling_solve_with(problem, config, commit_steps, most_likely_outcome)
+}
+
+/// Solve the field-teams restoration problem in a rolling-horizon manner: solve with
+/// [`solve_naive_from_state`], commit to the optimal actions for `commit_steps` transitions,
+/// then re-solve from the reached state, and repeat until a terminal state is reached.
+///
+/// The outcome of each committed action is chosen by `select_outcome`, which is given the
+/// transitions of the action and returns the index of the one that happens.
+///
+/// If `commit_steps` is at least the number of transitions until a terminal state, the problem
+/// is solved once and the result is the same as following the optimal policy of a single solve.
+pub fn rolling_solve_with<F>(
+    problem: &Problem,
+    config: &Config,
+    commit_steps: usize,
+    mut select_outcome: F,
+) -> Result<RollingSolution, SolveFailure>
+where
+    F: FnMut(&[RegularTransition]) -> usize,
+{
+    if commit_steps == 0 {
+        return Err(SolveFailure::BadInput(
+            "Number of committed steps must be positive".to_string(),
+        ));
+    }
+    // Team times in the solutions are quantized, so quantize the graph only once.
+    let graph = quantize_graph(&problem.graph, config)?;
+    let config = Config {
+        time_quantum: 1,
+        store_actions: true,
+        ..config.clone()
+    };
+
+    let mut result = RollingSolution {
+        actions: Vec::new(),
+        states: vec![State::start_state(&graph, problem.initial_teams.clone())],
+        solves: 0,
+    };
+    loop {
+        let start_state = result.states.last().expect("No states").clone();
+        if start_state.is_terminal(&graph) {
+            return Ok(result);
+        }
+        let solution = solve_naive_from_state(&graph, start_state, &config)?;
+        result.solves += 1;
+        let actions = solution
+            .actions
+            .expect("Actions are not stored in the solution");
+        let mut index: usize = 0;
+        for _ in 0..commit_steps {
+            let action = solution.policy[index] as usize;
+            let transitions = &solution.transitions[index][action];
+            if transitions.len() == 1 && transitions[0].successor as usize == index {
+                // Terminal state
+                return Ok(result);
+            }
+            result.actions.push(actions[index][action].clone());
+            index = transitions[select_outcome(transitions)].successor as usize;
+            result.states.push(State {
+                buses: solution.states.row(index).to_vec(),
+                teams: solution.teams.row(index).to_vec(),
+            });
+        }
+    }
+}
+
 /// Solve the field-teams restoration problem with the given:
 /// - action applier class
 /// - action set class
+/// - policy synthesizer class (see [`default_synthesizer`])
 ///
 /// Returns a [`io::BenchmarkResult`] on success.
 pub fn benchmark_custom(
@@ -276,12 +609,18 @@ pub fn benchmark_custom(
     indexer: &str,
     action_set: &str,
     action_applier: &str,
+    synthesizer: &str,
 ) -> Result<io::BenchmarkResult, SolveFailure> {
     if action_applier == stringify!(NaiveActionApplier) {
-        Ok(
-            solve_custom_regular(graph, initial_teams, config, indexer, action_set)?
-                .to_benchmark_result(),
-        )
+        Ok(solve_custom_regular(
+            graph,
+            initial_teams,
+            config,
+            indexer,
+            action_set,
+            synthesizer,
+        )?
+        .to_benchmark_result())
     } else {
         Ok(solve_custom_timed(
             graph,
@@ -290,6 +629,7 @@ pub fn benchmark_custom(
             indexer,
             action_set,
             action_applier,
+            synthesizer,
         )?
         .to_benchmark_result())
     }
@@ -327,39 +667,104 @@ pub fn all_optimizations() -> Vec<OptimizationInfo> {
         indexer: indexer.to_string(),
         actions: actions.to_string(),
         transitions: transitions.to_string(),
+        synthesizer: None,
     })
     .collect()
 }
 
+/// Run the given optimization combination on this field-teams restoration problem.
+fn benchmark_optimization(
+    graph: &Graph,
+    initial_teams: &[TeamState],
+    config: &Config,
+    optimizations: OptimizationInfo,
+) -> io::OptimizationBenchmarkResult {
+    let result = benchmark_custom(
+        graph,
+        initial_teams.to_vec(),
+        config,
+        &optimizations.indexer,
+        &optimizations.actions,
+        &optimizations.transitions,
+        optimizations.get_synthesizer(),
+    );
+    io::OptimizationBenchmarkResult {
+        optimizations,
+        result,
+    }
+}
+
 /// Run all optimization combination possibilities on this field-teams restoration problem.
+///
+/// The combinations are benchmarked one by one in the order of [`all_optimizations`].
 pub fn benchmark_all(
     graph: &Graph,
     initial_teams: Vec<TeamState>,
     config: &Config,
 ) -> Vec<io::OptimizationBenchmarkResult> {
-    itertools::iproduct!(
-        BENCHMARK_STATE_INDEXERS,
-        BENCHMARK_ACTION_SETS,
-        BENCHMARK_ACTION_APPLIERS
-    )
-    .map(|(indexer, action_applier, action_set)| {
-        let result = benchmark_custom(
-            graph,
-            initial_teams.clone(),
-            config,
-            indexer,
-            action_set,
-            action_applier,
-        );
-        let optimizations = io::OptimizationInfo {
-            indexer: indexer.to_string(),
-            actions: action_set.to_string(),
-            transitions: action_applier.to_string(),
-        };
-        io::OptimizationBenchmarkResult {
-            optimizations,
-            result,
-        }
-    })
-    .collect()
+    all_optimizations()
+        .into_iter()
+        .map(|optimizations| benchmark_optimization(graph, &initial_teams, config, optimizations))
+        .collect()
+}
+
+/// Benchmark each state indexer in [`all_optimizations`] with the given action set and action
+/// applier on this field-teams restoration problem, one by one.
+///
+/// The indexers trade memory for speed, e.g., `BitStackStateIndexer` compresses the states. Only
+/// the state exploration is affected by the indexer, so its cost is in
+/// [`io::BenchmarkResult::generation_time`] and [`io::BenchmarkResult::max_memory`], which is
+/// sampled during the exploration.
+pub fn benchmark_indexers(
+    graph: &Graph,
+    initial_teams: Vec<TeamState>,
+    config: &Config,
+    action_set: &str,
+    action_applier: &str,
+) -> Vec<io::OptimizationBenchmarkResult> {
+    BENCHMARK_STATE_INDEXERS
+        .iter()
+        .map(|indexer| {
+            let optimizations = OptimizationInfo {
+                indexer: indexer.to_string(),
+                actions: action_set.to_string(),
+                transitions: action_applier.to_string(),
+                synthesizer: None,
+            };
+            benchmark_optimization(graph, &initial_teams, config, optimizations)
+        })
+        .collect()
+}
+
+/// Run all optimization combination possibilities like [`benchmark_all`], but benchmark up to
+/// `jobs` combinations in parallel, each solved in a single thread. Uses one thread per CPU if
+/// `jobs` is 0.
+///
+/// The results are in the same order as [`benchmark_all`], and the numbers of states and
+/// transitions and the values are the same. However, the timings are wall-clock times under
+/// contention for CPU and memory bandwidth, so they are not comparable to those of serial runs;
+/// use [`benchmark_all`] for timing. Similarly, the memory usage is tracked globally, so the
+/// reported maximum memory usage and [`Config::max_memory`] cover all concurrent solutions.
+#[cfg(feature = "parallel")]
+pub fn benchmark_all_parallel(
+    graph: &Graph,
+    initial_teams: Vec<TeamState>,
+    config: &Config,
+    jobs: usize,
+) -> Result<Vec<io::OptimizationBenchmarkResult>, SolveFailure> {
+    use rayon::prelude::*;
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|e| SolveFailure::BadInput(format!("Cannot create thread pool: {e}")))?;
+    let optimizations = all_optimizations();
+    Ok(pool.install(|| {
+        optimizations
+            .into_par_iter()
+            .map(|optimizations| {
+                benchmark_optimization(graph, &initial_teams, config, optimizations)
+            })
+            .collect()
+    }))
 }
@@ -1,9 +1,12 @@
 use super::*;
 use num_derive::FromPrimitive;
-use serde::{Serialize, Serializer};
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 /// State of a single team. Use a `Vec` to represent multiple teams.
-#[derive(PartialEq, Eq, Clone, Debug, PartialOrd, Ord, Serialize)]
+#[derive(PartialEq, Eq, Clone, Debug, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TeamState {
     /// Remaining time
     pub time: Time,
@@ -44,6 +47,13 @@ pub struct State {
 }
 
 impl State {
+    /// Returns true if the global wait action can be taken in this state, i.e., all teams are
+    /// idle and at least one bus is unknown.
+    pub fn is_wait_available(&self) -> bool {
+        self.teams.iter().all(|team| team.time == 0)
+            && self.buses.contains(&BusState::Unknown)
+    }
+
     /// Creates the starting state from given team configuration.
     pub fn start_state(graph: &Graph, teams: Vec<TeamState>) -> State {
         State {
@@ -62,12 +72,57 @@ impl State {
         }
     }
 
-    /// Cost function: the count of unenergized (damaged or unknown) buses.
-    pub fn get_cost(&self) -> Cost {
-        self.buses
+    /// Check whether this state is consistent with the given graph:
+    /// - The number of buses must match.
+    /// - Each team must be located at (or moving to) a node in the team graph.
+    /// - Remaining time of each team cannot exceed [`Graph::get_max_dispatch_time`].
+    ///
+    /// Returns the description of the first inconsistency on failure.
+    pub fn validate(&self, graph: &Graph) -> Result<(), String> {
+        let bus_count = graph.branches.len();
+        if self.buses.len() != bus_count {
+            return Err(format!(
+                "State has {} buses but the graph has {bus_count}",
+                self.buses.len()
+            ));
+        }
+        let node_count = graph.travel_times.nrows();
+        for (i, team) in self.teams.iter().enumerate() {
+            if team.index as usize >= node_count {
+                return Err(format!(
+                    "Team {i} is at node {} but the team graph has {node_count} nodes",
+                    team.index
+                ));
+            }
+            let max_time = graph.get_max_dispatch_time();
+            if team.time > max_time {
+                return Err(format!(
+                    "Team {i} has {} time units remaining, which exceeds the maximum dispatch time {max_time}",
+                    team.time
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Cost function: the count of unenergized (damaged or unknown) buses, or the sum of their
+    /// weights if [`Graph::weights`] is given.
+    pub fn get_cost(&self, graph: &Graph) -> Cost {
+        self.weighted_bus_count(graph, |bus| bus != BusState::Energized)
+    }
+
+    /// Get the number of buses whose states satisfy the given predicate, or the sum of their
+    /// weights if [`Graph::weights`] is given.
+    pub fn weighted_bus_count(&self, graph: &Graph, predicate: impl Fn(BusState) -> bool) -> Cost {
+        let buses = self
+            .buses
             .iter()
-            .filter(|&b| *b != BusState::Energized)
-            .count() as Cost
+            .enumerate()
+            .filter(|(_, &bus)| predicate(bus));
+        match &graph.weights {
+            Some(weights) => buses.map(|(i, _)| weights[i]).sum(),
+            None => buses.count() as Cost,
+        }
     }
 
     /// Compute the transition probability from this state to another based on given
@@ -90,7 +145,7 @@ impl State {
             if *bus != BusState::Unknown {
                 return false;
             }
-            if graph.connected[i] {
+            if graph.is_connected(i) {
                 return true;
             }
             for &j in graph.branches[i].iter() {
@@ -122,7 +177,7 @@ impl State {
                 if bus != &BusState::Unknown {
                     return 0;
                 }
-                if graph.connected[i] {
+                if graph.is_connected(i) {
                     return 1;
                 }
                 for &j in graph.branches[i].iter() {
@@ -152,6 +207,93 @@ impl State {
         }
         minbeta
     }
+
+    /// Compute the minbeta array incrementally from the minbeta array of a parent state, which
+    /// differs from this state only in the given buses that were unknown in the parent.
+    ///
+    /// Only the entries that may depend on the changed buses are recomputed instead of running a
+    /// BFS over the whole graph. The result is identical to [`State::compute_minbeta`].
+    pub fn update_minbeta(
+        &self,
+        graph: &Graph,
+        parent_minbeta: &[BusIndex],
+        changed_buses: &[BusIndex],
+    ) -> Vec<BusIndex> {
+        let mut minbeta = parent_minbeta.to_vec();
+        // Find the buses whose minbeta may increase, i.e., the unknown buses that can be reached
+        // from a changed bus by following the shortest paths in the parent state.
+        let mut affected = vec![false; minbeta.len()];
+        let mut deque: VecDeque<BusIndex> = VecDeque::new();
+        for &i in changed_buses {
+            minbeta[i as usize] = 0;
+            deque.push_back(i);
+        }
+        while let Some(i) = deque.pop_front() {
+            let next_beta = parent_minbeta[i as usize].saturating_add(1);
+            for &j in graph.branches[i as usize].iter() {
+                let j = j as usize;
+                if !affected[j]
+                    && self.buses[j] == BusState::Unknown
+                    && parent_minbeta[j] != BusIndex::MAX
+                    && parent_minbeta[j] == next_beta
+                {
+                    affected[j] = true;
+                    minbeta[j] = BusIndex::MAX;
+                    deque.push_back(j as BusIndex);
+                }
+            }
+        }
+
+        // Recompute the affected buses from their unaffected neighbors.
+        let mut heap: BinaryHeap<Reverse<(BusIndex, BusIndex)>> = BinaryHeap::new();
+        for i in (0..minbeta.len()).filter(|&i| affected[i]) {
+            let mut beta = if graph.is_connected(i) {
+                1
+            } else {
+                BusIndex::MAX
+            };
+            for &j in graph.branches[i].iter() {
+                let j = j as usize;
+                let candidate = match self.buses[j] {
+                    BusState::Energized => 1,
+                    BusState::Damaged => BusIndex::MAX,
+                    BusState::Unknown if affected[j] => BusIndex::MAX,
+                    BusState::Unknown => minbeta[j].saturating_add(1),
+                };
+                beta = beta.min(candidate);
+            }
+            if beta != BusIndex::MAX {
+                minbeta[i] = beta;
+                heap.push(Reverse((beta, i as BusIndex)));
+            }
+        }
+        // Newly energized buses may decrease the minbeta of their neighbors.
+        for &i in changed_buses {
+            if self.buses[i as usize] != BusState::Energized {
+                continue;
+            }
+            for &j in graph.branches[i as usize].iter() {
+                if self.buses[j as usize] == BusState::Unknown && minbeta[j as usize] > 1 {
+                    minbeta[j as usize] = 1;
+                    heap.push(Reverse((1, j)));
+                }
+            }
+        }
+        // Propagate the decreased values.
+        while let Some(Reverse((beta, i))) = heap.pop() {
+            if beta > minbeta[i as usize] {
+                continue;
+            }
+            let next_beta: BusIndex = beta + 1;
+            for &j in graph.branches[i as usize].iter() {
+                if next_beta < minbeta[j as usize] {
+                    minbeta[j as usize] = next_beta;
+                    heap.push(Reverse((next_beta, j)));
+                }
+            }
+        }
+        minbeta
+    }
 }
 
 impl PartialEq for State {
@@ -235,6 +377,103 @@ impl std::hash::Hash for State {
     }
 }
 
+impl State {
+    /// Get a 64-bit hash of this state that is stable across processes, e.g., for keying the
+    /// states in caches or comparing the solutions of separate runs.
+    ///
+    /// Unlike the [`Hash`](std::hash::Hash) implementation, the result doesn't depend on the
+    /// hasher or the integer types of the enabled features. It's the FNV-1a hash (see
+    /// [`fnv1a_hash`](crate::utils::fnv1a_hash)) of the number of buses, the discriminant of each
+    /// [`BusState`] as a byte, the number of teams, and the time and index of each team, where the
+    /// numbers are encoded as 64-bit little-endian integers.
+    pub fn stable_hash(&self) -> u64 {
+        let mut bytes = Vec::with_capacity(16 + self.buses.len() + 16 * self.teams.len());
+        bytes.extend_from_slice(&(self.buses.len() as u64).to_le_bytes());
+        bytes.extend(self.buses.iter().map(|&bus| bus as u8));
+        bytes.extend_from_slice(&(self.teams.len() as u64).to_le_bytes());
+        for team in self.teams.iter() {
+            bytes.extend_from_slice(&(team.time as u64).to_le_bytes());
+            bytes.extend_from_slice(&(team.index as u64).to_le_bytes());
+        }
+        crate::utils::fnv1a_hash(&bytes)
+    }
+}
+
+/// Compact representation for debugging, e.g., `[TG U D] [0:0 2:1]` where each team is
+/// represented as `index:time`.
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, bus) in self.buses.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            let s = match bus {
+                BusState::Damaged => "D",
+                BusState::Unknown => "U",
+                BusState::Energized => "TG",
+            };
+            write!(f, "{s}")?;
+        }
+        write!(f, "] [")?;
+        for (i, team) in self.teams.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}:{}", team.index, team.time)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Parses the compact representation produced by [`std::fmt::Display`], e.g.,
+/// `[TG U D] [0:0 2:1]`.
+impl std::str::FromStr for State {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(']')
+            .ok_or_else(|| format!("Invalid state \"{s}\": expected two lists"))?;
+        let (buses, teams) = s.split_at(split + 1);
+        let list = |part: &str| -> Result<Vec<String>, String> {
+            let part = part.trim();
+            let inner = part
+                .strip_prefix('[')
+                .and_then(|part| part.strip_suffix(']'))
+                .ok_or_else(|| format!("Invalid list \"{part}\" in state"))?;
+            Ok(inner.split_whitespace().map(str::to_string).collect())
+        };
+        let buses = list(buses)?
+            .into_iter()
+            .map(|bus| match bus.as_str() {
+                "D" => Ok(BusState::Damaged),
+                "U" => Ok(BusState::Unknown),
+                "TG" => Ok(BusState::Energized),
+                _ => Err(format!("Invalid bus state \"{bus}\"")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let teams = list(teams)?
+            .into_iter()
+            .map(|team| {
+                let (index, time) = team
+                    .split_once(':')
+                    .ok_or_else(|| format!("Invalid team state \"{team}\", expected index:time"))?;
+                Ok(TeamState {
+                    index: index
+                        .parse()
+                        .map_err(|e| format!("Invalid team index \"{index}\": {e}"))?,
+                    time: time
+                        .parse()
+                        .map_err(|e| format!("Invalid team time \"{time}\": {e}"))?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(State { buses, teams })
+    }
+}
+
 impl Serialize for BusState {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -248,6 +487,51 @@ impl Serialize for BusState {
     }
 }
 
+/// Visitor for [`BusState`] deserialization.
+struct BusStateVisitor;
+
+impl<'de> serde::de::Visitor<'de> for BusStateVisitor {
+    type Value = BusState;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("\"D\", \"U\", \"TG\", or the index of a bus state")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        match s {
+            "D" => Ok(BusState::Damaged),
+            "U" => Ok(BusState::Unknown),
+            "TG" => Ok(BusState::Energized),
+            _ => Err(E::unknown_variant(s, &["D", "U", "TG"])),
+        }
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, i: u64) -> Result<Self::Value, E> {
+        BusState::from_u64(i)
+            .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Unsigned(i), &"0, 1, or 2"))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, i: i64) -> Result<Self::Value, E> {
+        BusState::from_i64(i)
+            .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Signed(i), &"0, 1, or 2"))
+    }
+}
+
+/// Accepts the strings emitted by the [`Serialize`] implementation and, in human-readable
+/// formats, the numeric values of the variants, e.g., `1` for [`BusState::Damaged`].
+impl<'de> Deserialize<'de> for BusState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BusStateVisitor)
+        } else {
+            deserializer.deserialize_str(BusStateVisitor)
+        }
+    }
+}
+
 mod indexers;
 pub use indexers::*;
 
@@ -255,6 +539,74 @@ pub use indexers::*;
 mod tests {
     use super::*;
 
+    #[test]
+    fn bus_state_serde_round_trip() {
+        use bincode::Options;
+        for bus in [BusState::Unknown, BusState::Damaged, BusState::Energized] {
+            let json = serde_json::to_string(&bus).unwrap();
+            assert_eq!(serde_json::from_str::<BusState>(&json).unwrap(), bus);
+            let numeric = (bus as u8).to_string();
+            assert_eq!(serde_json::from_str::<BusState>(&numeric).unwrap(), bus);
+
+            let options = bincode::DefaultOptions::new();
+            let encoded = options.serialize(&bus).unwrap();
+            assert_eq!(options.deserialize::<BusState>(&encoded).unwrap(), bus);
+        }
+        for invalid in ["\"E\"", "3", "-1", "null"] {
+            assert!(
+                serde_json::from_str::<BusState>(invalid).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn stable_hash() {
+        let state = State {
+            buses: vec![BusState::Unknown, BusState::Damaged, BusState::Energized],
+            teams: vec![
+                TeamState { time: 0, index: 1 },
+                TeamState { time: 3, index: 2 },
+            ],
+        };
+        // Golden values that must not change across runs, processes, or features.
+        assert_eq!(state.stable_hash(), 0xe776491efbddb4b3);
+        assert_eq!(state.clone().stable_hash(), state.stable_hash());
+        let swapped = State {
+            teams: vec![state.teams[1].clone(), state.teams[0].clone()],
+            ..state
+        };
+        assert_eq!(swapped.stable_hash(), 0x7b667add535e8073);
+        let empty = State {
+            buses: Vec::new(),
+            teams: Vec::new(),
+        };
+        assert_eq!(empty.stable_hash(), 0x88201fb960ff6465);
+    }
+
+    #[test]
+    fn state_string_round_trip() {
+        let state = State {
+            buses: vec![BusState::Energized, BusState::Unknown, BusState::Damaged],
+            teams: vec![
+                TeamState { index: 0, time: 0 },
+                TeamState { index: 3, time: 2 },
+            ],
+        };
+        let s = state.to_string();
+        assert_eq!(s, "[TG U D] [0:0 3:2]");
+        assert_eq!(s.parse::<State>().unwrap(), state);
+        assert_eq!(" [TG  U D ] [ 0:0 3:2]".parse::<State>().unwrap(), state);
+
+        let no_teams: State = "[U] []".parse().unwrap();
+        assert_eq!(no_teams.buses, vec![BusState::Unknown]);
+        assert!(no_teams.teams.is_empty());
+
+        assert!("[U X] []".parse::<State>().is_err());
+        assert!("[U] [0]".parse::<State>().is_err());
+        assert!("[U]".parse::<State>().is_err());
+    }
+
     #[test]
     fn team_state_ord_test() {
         let ordered_teams = vec![
@@ -545,6 +897,45 @@ mod tests {
         assert_eq!(shuffled, ordered_states);
     }
 
+    #[test]
+    fn validate_test() {
+        let graph = Graph {
+            travel_times: ndarray::arr2(&[[0, 1, 2], [1, 0, 3], [2, 3, 0]]),
+            branches: vec![vec![1], vec![0]],
+            connected: vec![true, false],
+            pfs: ndarray::arr1(&[0.5, 0.5]),
+            team_nodes: Array2::default((0, 0)),
+            ..Default::default()
+        };
+        let state = State::start_state(
+            &graph,
+            vec![
+                TeamState { time: 0, index: 2 },
+                TeamState { time: 3, index: 1 },
+            ],
+        );
+        assert_eq!(state.validate(&graph), Ok(()));
+
+        // Bus count
+        let mut invalid = state.clone();
+        invalid.buses.push(BusState::Unknown);
+        assert!(invalid.validate(&graph).is_err());
+
+        // Team index
+        let mut invalid = state.clone();
+        invalid.teams[0].index = 3;
+        assert!(invalid.validate(&graph).is_err());
+
+        // Team time
+        let mut invalid = state.clone();
+        invalid.teams[1].time = 4;
+        assert!(invalid.validate(&graph).is_err());
+
+        // Solving with an invalid initial state fails gracefully
+        let result = solve_naive(&graph, invalid.teams, &Config::default());
+        assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+    }
+
     #[test]
     fn start_state_test() {
         let mut graph = Graph {
@@ -560,6 +951,7 @@ mod tests {
             connected: vec![true, false, false, true, false, false],
             pfs: ndarray::arr1(&[0.5, 0.5, 0.25, 0.25, 0.25, 0.25]),
             team_nodes: Array2::default((0, 0)),
+            ..Default::default()
         };
         assert_eq!(
             State::start_state(&graph, vec![]),
@@ -130,6 +130,76 @@ impl<T: StateIndexer> StateIndexer for SortedStateIndexer<T> {
     }
 }
 
+/// State indexer that sorts the states of interchangeable teams, as given by
+/// [`Graph::team_classes`], to eliminate permutations of equivalent team states.
+///
+/// Unlike [`SortedStateIndexer`], which assumes that all teams are interchangeable, teams are
+/// only sorted among the other teams in the same class. Since actions are generated for the
+/// indexed states, team indices in actions always refer to the canonical team order.
+pub struct SymmetricStateIndexer<T: StateIndexer> {
+    indexer: T,
+    /// Indices of the teams in each class that contains more than one team.
+    classes: Vec<Vec<usize>>,
+}
+
+impl<T: StateIndexer> Iterator for SymmetricStateIndexer<T> {
+    type Item = (usize, State);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indexer.next()
+    }
+}
+
+impl<T: StateIndexer> StateIndexer for SymmetricStateIndexer<T> {
+    fn new(graph: &Graph, teams: &[TeamState]) -> Self {
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        if let Some(team_classes) = &graph.team_classes {
+            assert_eq!(
+                team_classes.len(),
+                teams.len(),
+                "Number of team classes must be equal to the number of teams"
+            );
+            let mut class_to_teams: HashMap<usize, Vec<usize>> = HashMap::new();
+            for (i, &class) in team_classes.iter().enumerate() {
+                class_to_teams.entry(class).or_default().push(i);
+            }
+            classes = class_to_teams
+                .into_values()
+                .filter(|class| class.len() > 1)
+                .collect();
+            classes.sort_unstable();
+        }
+        Self {
+            indexer: T::new(graph, teams),
+            classes,
+        }
+    }
+
+    #[inline]
+    fn get_state_count(&self) -> usize {
+        self.indexer.get_state_count()
+    }
+
+    #[inline]
+    fn index_state(&mut self, mut s: State) -> usize {
+        for class in self.classes.iter() {
+            let mut team_states: Vec<TeamState> =
+                class.iter().map(|&i| s.teams[i].clone()).collect();
+            team_states.sort_unstable();
+            for (&i, team_state) in class.iter().zip(team_states) {
+                s.teams[i] = team_state;
+            }
+        }
+        self.indexer.index_state(s)
+    }
+
+    #[inline]
+    fn deconstruct(self) -> (Array2<BusState>, Array2<TeamState>) {
+        self.indexer.deconstruct()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
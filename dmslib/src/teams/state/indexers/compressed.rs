@@ -192,15 +192,12 @@ impl StateIndexer for BitStackStateIndexer {
         let max_index = teams
             .iter()
             .map(|team| team.index)
+            .chain(graph.depots.iter().cloned())
             .max()
             .expect("No teams in StateIndexer") as usize;
         let max_index = max_index.max(bus_count - 1);
-        let max_time = graph
-            .travel_times
-            .iter()
-            .max()
-            .expect("Cannot get max travel time");
-        BitStackStateIndexer::new(bus_count, team_count, max_index, *max_time as usize)
+        let max_time = graph.get_max_dispatch_time();
+        BitStackStateIndexer::new(bus_count, team_count, max_index, max_time as usize)
     }
 
     fn get_state_count(&self) -> usize {
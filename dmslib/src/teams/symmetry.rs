@@ -0,0 +1,149 @@
+//! Detection of structurally equivalent buses in the distribution graph.
+use super::*;
+
+/// Refine the colors of the vertices until they are stable, i.e., vertices with the same color
+/// have the same number of neighbors of each color.
+///
+/// Colors are relabeled in the order of their signatures, so the colors of disjoint copies of a
+/// graph that are refined together remain comparable.
+fn refine_colors(adjacency: &[Vec<usize>], colors: &mut [usize]) {
+    let mut color_count = colors.iter().unique().count();
+    loop {
+        let signatures: Vec<(usize, Vec<usize>)> = adjacency
+            .iter()
+            .zip(colors.iter())
+            .map(|(neighbors, &color)| {
+                let mut neighbor_colors: Vec<usize> =
+                    neighbors.iter().map(|&j| colors[j]).collect();
+                neighbor_colors.sort_unstable();
+                (color, neighbor_colors)
+            })
+            .collect();
+        let unique: Vec<&(usize, Vec<usize>)> = signatures.iter().sorted().dedup().collect();
+        for (color, signature) in colors.iter_mut().zip(signatures.iter()) {
+            *color = unique.binary_search(&signature).unwrap();
+        }
+        // The new colors refine the old ones, so they are stable if their number is the same.
+        if unique.len() == color_count {
+            break;
+        }
+        color_count = unique.len();
+    }
+}
+
+/// Search for an isomorphism from the first copy of a graph to the second one that preserves the
+/// given colors, using individualization and refinement.
+///
+/// `adjacency` contains two disjoint copies of the graph with `n` vertices each, and the mapping
+/// of each vertex in the first copy is returned if found.
+fn find_isomorphism(
+    adjacency: &[Vec<usize>],
+    mut colors: Vec<usize>,
+    n: usize,
+) -> Option<Vec<usize>> {
+    refine_colors(adjacency, &mut colors);
+    let color_count = colors.iter().max().map_or(0, |&c| c + 1);
+    let mut counts = vec![(0, 0); color_count];
+    for (i, &color) in colors.iter().enumerate() {
+        if i < n {
+            counts[color].0 += 1;
+        } else {
+            counts[color].1 += 1;
+        }
+    }
+    if counts.iter().any(|(a, b)| a != b) {
+        return None;
+    }
+    match (0..n).find(|&i| counts[colors[i]].0 > 1) {
+        None => {
+            // Each color has a single vertex in each copy. Since the colors are stable, mapping
+            // the vertices with the same color preserves the branches.
+            let mut by_color = vec![0; color_count];
+            for (j, &color) in colors[n..].iter().enumerate() {
+                by_color[color] = j;
+            }
+            Some(colors[..n].iter().map(|&color| by_color[color]).collect())
+        }
+        Some(a) => (n..2 * n)
+            .filter(|&b| colors[b] == colors[a])
+            .find_map(|b| {
+                let mut individualized = colors.clone();
+                individualized[a] = color_count;
+                individualized[b] = color_count;
+                find_isomorphism(adjacency, individualized, n)
+            }),
+    }
+}
+
+/// Find the root of the set that contains `i` in the given disjoint-set forest.
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+impl Graph {
+    /// Find the groups of structurally interchangeable buses, i.e., the orbits of the
+    /// automorphisms of the distribution graph that preserve the failure probabilities and the
+    /// direct connections to energy sources.
+    ///
+    /// Only the groups with at least 2 buses are returned, in ascending order.
+    /// Team graph, repair times and deadlines are not considered.
+    pub fn find_symmetries(&self) -> Vec<Vec<BusIndex>> {
+        let n = self.branches.len();
+        let adjacency: Vec<Vec<usize>> = (0..2)
+            .flat_map(|copy| {
+                self.branches.iter().map(move |neighbors| {
+                    neighbors.iter().map(|&j| j as usize + copy * n).collect()
+                })
+            })
+            .collect();
+
+        let attributes: Vec<_> = (0..n)
+            .map(|i| (self.pfs[i].to_bits(), self.is_connected(i)))
+            .collect();
+        let unique: Vec<_> = attributes.iter().sorted().dedup().collect();
+        let mut colors: Vec<usize> = attributes
+            .iter()
+            .map(|a| unique.binary_search(&a).unwrap())
+            .collect();
+        refine_colors(&adjacency[..n], &mut colors);
+
+        let mut parents: Vec<usize> = (0..n).collect();
+        for i in 0..n {
+            let mut tried: Vec<usize> = Vec::new();
+            for j in 0..i {
+                if colors[i] != colors[j] {
+                    continue;
+                }
+                let root = find_root(&mut parents, j);
+                if root == find_root(&mut parents, i) || tried.contains(&root) {
+                    continue;
+                }
+                tried.push(root);
+                let mut joint_colors = colors.repeat(2);
+                let fresh = n;
+                joint_colors[j] = fresh;
+                joint_colors[n + i] = fresh;
+                if let Some(mapping) = find_isomorphism(&adjacency, joint_colors, n) {
+                    for (k, l) in mapping.into_iter().enumerate() {
+                        let (a, b) = (find_root(&mut parents, k), find_root(&mut parents, l));
+                        parents[a.max(b)] = a.min(b);
+                    }
+                }
+            }
+        }
+
+        let mut groups: BTreeMap<usize, Vec<BusIndex>> = BTreeMap::new();
+        for i in 0..n {
+            let root = find_root(&mut parents, i);
+            groups.entry(root).or_default().push(i as BusIndex);
+        }
+        groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect()
+    }
+}
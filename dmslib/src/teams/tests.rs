@@ -14,6 +14,7 @@ fn get_paper_example_graph() -> Graph {
         connected: vec![true, false, false, true, false, false],
         pfs: ndarray::arr1(&[0.5, 0.5, 0.25, 0.25, 0.25, 0.25]),
         team_nodes: Array2::default((0, 0)),
+        ..Default::default()
     }
 }
 
@@ -41,7 +42,7 @@ fn paper_example_4_1_1() {
     ];
     let state = State { buses, teams };
 
-    let cost = state.get_cost();
+    let cost = state.get_cost(&graph);
     assert_eq!(cost, 4 as Cost);
 
     let iter = NaiveActions::setup(&graph);
@@ -99,6 +100,7 @@ fn paper_example_4_1_1() {
     ];
     let outcomes: Vec<(Probability, State)> =
         NaiveActionApplier::apply_state(&state, cost, &graph, &actions[0])
+            .unwrap()
             .into_iter()
             .map(|(transition, state)| {
                 assert_eq!(transition.cost, cost);
@@ -126,7 +128,7 @@ fn test_timed_action_applier() {
     ];
     let state = State { buses, teams };
 
-    let cost = state.get_cost();
+    let cost = state.get_cost(&graph);
     assert_eq!(cost, 4 as Cost);
 
     let action: Vec<TeamAction> = vec![1, 2];
@@ -152,6 +154,7 @@ fn test_timed_action_applier() {
     )];
     let outcomes: Vec<(Probability, State)> =
         NaiveActionApplier::apply_state(&state, cost, &graph, &action)
+            .unwrap()
             .into_iter()
             .map(|(transition, state)| {
                 assert_eq!(transition.cost, cost);
@@ -211,6 +214,7 @@ fn test_timed_action_applier() {
     ];
     let outcomes: Vec<(Probability, State)> =
         TimedActionApplier::<TimeUntilArrival>::apply_state(&state, cost, &graph, &action)
+            .unwrap()
             .into_iter()
             .map(|(transition, state)| {
                 assert_eq!(transition.cost, cost);
@@ -238,7 +242,7 @@ fn on_energized_bus_actions() {
     ];
     let state = State { buses, teams };
 
-    assert_eq!(state.get_cost(), 4 as Cost);
+    assert_eq!(state.get_cost(&graph), 4 as Cost);
 
     let iter = NaiveActions::setup(&graph);
     let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
@@ -328,7 +332,7 @@ fn wait_moving_elimination() {
     ];
     let state = State { buses, teams };
 
-    assert_eq!(state.get_cost(), 3 as Cost);
+    assert_eq!(state.get_cost(&graph), 3 as Cost);
 
     let expected_actions: Vec<Vec<TeamAction>> = vec![vec![2, 0], vec![0, 0], vec![1, 0]];
 
@@ -400,6 +404,159 @@ fn beta_values_on_paper_example() {
     );
 }
 
+/// Differential test of [`State::update_minbeta`] against [`State::compute_minbeta`] on random
+/// graphs and states.
+#[test]
+fn incremental_minbeta_test() {
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+    for _ in 0..200 {
+        let bus_count: usize = rng.gen_range(1..16);
+        let mut branches: Vec<Vec<BusIndex>> = vec![Vec::new(); bus_count];
+        for i in 0..bus_count {
+            for j in (i + 1)..bus_count {
+                if rng.gen_bool(0.2) {
+                    branches[i].push(j as BusIndex);
+                    branches[j].push(i as BusIndex);
+                }
+            }
+        }
+        let graph = Graph {
+            travel_times: Array2::default((0, 0)),
+            branches,
+            connected: (0..bus_count).map(|_| rng.gen_bool(0.2)).collect(),
+            pfs: Array1::zeros(bus_count),
+            team_nodes: Array2::default((0, 0)),
+            ..Default::default()
+        };
+
+        let random_bus_state = |rng: &mut rand::rngs::StdRng| match rng.gen_range(0..3) {
+            0 => BusState::Unknown,
+            1 => BusState::Damaged,
+            _ => BusState::Energized,
+        };
+        let parent = State {
+            buses: (0..bus_count).map(|_| random_bus_state(&mut rng)).collect(),
+            teams: Vec::new(),
+        };
+        let parent_minbeta = parent.compute_minbeta(&graph);
+
+        let mut state = parent.clone();
+        let mut changed_buses: Vec<BusIndex> = Vec::new();
+        for i in 0..bus_count {
+            if parent.buses[i] == BusState::Unknown && rng.gen_bool(0.3) {
+                state.buses[i] = if rng.gen_bool(0.5) {
+                    BusState::Energized
+                } else {
+                    BusState::Damaged
+                };
+                changed_buses.push(i as BusIndex);
+            }
+        }
+
+        assert_eq!(
+            state.update_minbeta(&graph, &parent_minbeta, &changed_buses),
+            state.compute_minbeta(&graph),
+            "Incremental minbeta differs for {state:?} from {parent:?} on {:?}",
+            graph.branches,
+        );
+    }
+}
+
+/// Build a ring of 6 buses where source 0 feeds bus 0 and source 1 feeds bus 3.
+fn two_source_ring(source_outages: Vec<bool>) -> Graph {
+    Graph {
+        travel_times: Array2::from_shape_fn((6, 6), |(i, j)| {
+            let d = (i as Time).abs_diff(j as Time);
+            std::cmp::min(d, 6 - d)
+        }),
+        branches: (0..6).map(|i| vec![(i + 5) % 6, (i + 1) % 6]).collect(),
+        connected: vec![true, false, false, true, false, false],
+        pfs: ndarray::arr1(&[0.5; 6]),
+        team_nodes: Array2::default((0, 0)),
+        bus_sources: Some(vec![vec![0], vec![], vec![], vec![1], vec![], vec![]]),
+        source_outages,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn two_source_ring_test() {
+    let unknown = State {
+        buses: vec![BusState::Unknown; 6],
+        teams: vec![],
+    };
+
+    // A bus is directly energizable if any of its sources is in service.
+    let graph = two_source_ring(vec![false, false]);
+    assert!(graph.is_connected(0) && graph.is_connected(3));
+    assert_eq!(unknown.compute_minbeta(&graph), vec![1, 2, 2, 1, 2, 2]);
+    // Sources without an entry are in service.
+    assert_eq!(
+        unknown.compute_minbeta(&two_source_ring(Vec::new())),
+        unknown.compute_minbeta(&graph)
+    );
+    // Equivalent to the old boolean representation.
+    let legacy = Graph {
+        bus_sources: None,
+        source_outages: Vec::new(),
+        ..graph.clone()
+    };
+    assert_eq!(
+        unknown.compute_minbeta(&legacy),
+        unknown.compute_minbeta(&graph)
+    );
+
+    // The other side of the ring is still reachable via bus 0 if source 1 is out of service.
+    let graph = two_source_ring(vec![false, true]);
+    assert!(graph.is_connected(0) && !graph.is_connected(3));
+    assert_eq!(unknown.compute_minbeta(&graph), vec![1, 2, 3, 4, 3, 2]);
+    assert!(!unknown.is_terminal(&graph));
+
+    // Bus 3 cannot be energized while its only source is out of service.
+    let state = State {
+        buses: vec![BusState::Unknown; 6],
+        teams: vec![TeamState { time: 0, index: 3 }],
+    };
+    assert_eq!(state.energize(&graph), None);
+    let outcomes = state
+        .energize(&two_source_ring(vec![false, false]))
+        .unwrap();
+    assert_eq!(outcomes.len(), 2);
+
+    // If bus 0 is damaged, nothing can be energized without source 1.
+    let mut buses = vec![BusState::Unknown; 6];
+    buses[0] = BusState::Damaged;
+    let state = State {
+        buses,
+        teams: vec![],
+    };
+    assert_eq!(
+        state.compute_minbeta(&graph),
+        vec![
+            0,
+            BusIndex::MAX,
+            BusIndex::MAX,
+            BusIndex::MAX,
+            BusIndex::MAX,
+            BusIndex::MAX
+        ]
+    );
+    assert!(state.is_terminal(&graph));
+    let graph = two_source_ring(vec![false, false]);
+    assert_eq!(state.compute_minbeta(&graph), vec![0, 3, 2, 1, 2, 3]);
+    assert!(!state.is_terminal(&graph));
+
+    // Both sources out of service.
+    let graph = two_source_ring(vec![true, true]);
+    assert!(unknown
+        .compute_minbeta(&graph)
+        .iter()
+        .all(|&beta| beta == BusIndex::MAX));
+    assert!(unknown.is_terminal(&graph));
+}
+
 #[test]
 fn minimal_nonopt_permutations() {
     let graph = Graph {
@@ -408,6 +565,7 @@ fn minimal_nonopt_permutations() {
         connected: vec![true, true],
         pfs: ndarray::arr1(&[0.5, 0.5]),
         team_nodes: Array2::default((0, 0)),
+        ..Default::default()
     };
 
     let state = State {
@@ -458,6 +616,122 @@ fn minimal_nonopt_permutations() {
     check_sets(&actions, &expected_actions);
 }
 
+/// When two permutations take the same amount of time, the one with the lexicographically
+/// smallest assignment must be kept.
+#[test]
+fn equal_time_permutations() {
+    let graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 1, 1, 1], [1, 0, 1, 1], [1, 1, 0, 2], [1, 1, 2, 0]]),
+        branches: vec![vec![], vec![]],
+        connected: vec![true, true],
+        pfs: ndarray::arr1(&[0.5, 0.5]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+
+    let state = State {
+        buses: vec![BusState::Unknown, BusState::Unknown],
+        teams: vec![
+            TeamState { time: 0, index: 3 },
+            TeamState { time: 0, index: 2 },
+        ],
+    };
+
+    let expected_actions: Vec<Vec<TeamAction>> = vec![vec![0, 0], vec![0, 1], vec![1, 1]];
+    let iter = PermutationalActions::setup(&graph);
+    for _ in 0..3 {
+        let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+        assert_eq!(actions, expected_actions);
+    }
+}
+
+#[test]
+fn team_skill_actions() {
+    let mut graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 1, 1, 1], [1, 0, 1, 1], [1, 1, 0, 2], [1, 1, 2, 0]]),
+        branches: vec![vec![], vec![]],
+        connected: vec![true, true],
+        pfs: ndarray::arr1(&[0.5, 0.5]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+
+    let state = State {
+        buses: vec![BusState::Unknown, BusState::Unknown],
+        teams: vec![
+            TeamState { time: 0, index: 3 },
+            TeamState { time: 0, index: 2 },
+        ],
+    };
+
+    // First team cannot be dispatched to bus 0.
+    graph.team_bus_allowed = Some(ndarray::arr2(&[[false, true], [true, true]]));
+    assert!(!graph.is_team_allowed(0, 0));
+    assert!(graph.is_team_allowed(0, 3));
+
+    let expected_actions: Vec<Vec<TeamAction>> = vec![vec![1, 0], vec![1, 1]];
+
+    let iter = NaiveActions::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    check_sets(&actions, &expected_actions);
+
+    // [0, 1] dominates [1, 0] when unrestricted, but the latter must be kept now.
+    let iter = PermutationalActions::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    check_sets(&actions, &expected_actions);
+
+    let iter = FilterTeamSkill::<FilterOnWay<NaiveActions>>::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    check_sets(&actions, &expected_actions);
+
+    // Only bus 0 remains and the second team is on its way to it, the first team must wait.
+    let state = State {
+        buses: vec![BusState::Unknown, BusState::Energized],
+        teams: vec![
+            TeamState { time: 0, index: 3 },
+            TeamState { time: 1, index: 0 },
+        ],
+    };
+    let expected_actions: Vec<Vec<TeamAction>> = vec![vec![3, 0]];
+
+    let iter = NaiveActions::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    check_sets(&actions, &expected_actions);
+
+    let iter = PermutationalActions::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    check_sets(&actions, &expected_actions);
+}
+
+#[test]
+fn infeasible_team_skills() {
+    // Bus 1 can only be energized after bus 0.
+    let mut graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 1, 1], [1, 0, 1], [1, 1, 0]]),
+        branches: vec![vec![1], vec![0]],
+        connected: vec![true, false],
+        pfs: ndarray::arr1(&[0.5, 0.5]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    graph.team_bus_allowed = Some(ndarray::arr2(&[[false, true], [false, true]]));
+    let mut problem = Problem {
+        graph,
+        initial_teams: vec![TeamState { time: 0, index: 2 }; 2],
+        start_distribution: None,
+    };
+    match problem.check_feasibility() {
+        Err(SolveFailure::BadInput(message)) => assert_eq!(
+            message,
+            "Infeasible problem: no team is allowed on any of the energizable buses [0]"
+        ),
+        _ => panic!("Infeasible problem is accepted"),
+    }
+
+    problem.graph.team_bus_allowed = Some(ndarray::arr2(&[[false, true], [true, false]]));
+    assert!(problem.check_feasibility().is_ok());
+}
+
 #[test]
 fn eliminating_cycle_permutations() {
     let graph = get_paper_example_graph();
@@ -547,3 +821,406 @@ fn all_enroute_actions() {
     let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
     assert_eq!(actions, expected_actions);
 }
+
+/// Checks that moving to a depot is a feasible action when depots are enabled.
+#[test]
+fn depot_actions() {
+    let mut graph = get_paper_example_graph();
+    graph.travel_times = ndarray::arr2(&[
+        [0, 1, 2, 1, 2, 2, 2],
+        [1, 0, 1, 2, 2, 2, 2],
+        [2, 1, 0, 2, 2, 1, 3],
+        [1, 2, 2, 0, 1, 2, 1],
+        [2, 2, 2, 1, 0, 1, 2],
+        [2, 2, 1, 2, 1, 0, 3],
+        [2, 2, 3, 1, 2, 3, 0],
+    ]);
+    graph.depots = vec![6];
+    let buses: Vec<BusState> = vec![
+        BusState::Energized,
+        BusState::Unknown,
+        BusState::Unknown,
+        BusState::Energized,
+        BusState::Damaged,
+        BusState::Unknown,
+    ];
+    // Progress condition is satisfied by the en-route team.
+    let teams: Vec<TeamState> = vec![
+        TeamState { time: 0, index: 0 },
+        TeamState { index: 1, time: 1 },
+    ];
+    let state = State {
+        buses: buses.clone(),
+        teams,
+    };
+
+    let expected_actions: Vec<Vec<TeamAction>> = vec![vec![1, 1], vec![2, 1], vec![6, 1]];
+
+    let iter = NaiveActions::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    check_sets(&actions, &expected_actions);
+
+    let iter = PermutationalActions::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    check_sets(&actions, &expected_actions);
+
+    // Moving to the depot has no energization effect.
+    let outcomes =
+        NaiveActionApplier::apply_state(&state, state.get_cost(&graph), &graph, &[6, 1]).unwrap();
+    assert_eq!(outcomes.len(), 2);
+    for (_, successor) in outcomes {
+        assert_eq!(successor.teams[0], TeamState { time: 1, index: 6 });
+        assert_eq!(successor.teams[1], TeamState { time: 0, index: 1 });
+    }
+
+    // En-route teams cannot be reassigned.
+    let result = NaiveActionApplier::apply_state(&state, state.get_cost(&graph), &graph, &[1, 2]);
+    assert!(matches!(result, Err(SolveFailure::BadInput(message)) if message.contains("en route")));
+    let result = TimedActionApplier::<TimeUntilArrival>::apply_state(
+        &state,
+        state.get_cost(&graph),
+        &graph,
+        &[1, 2],
+    );
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+    let result = NaiveActionApplier::apply_state(&state, state.get_cost(&graph), &graph, &[1]);
+    assert!(matches!(result, Err(SolveFailure::BadInput(_))));
+
+    // Depot moves alone don't satisfy the progress condition.
+    let state = State {
+        buses,
+        teams: vec![TeamState { time: 0, index: 0 }],
+    };
+    let expected_actions: Vec<Vec<TeamAction>> = vec![vec![1]];
+
+    let iter = NaiveActions::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    assert_eq!(actions, expected_actions);
+
+    let iter = PermutationalActions::setup(&graph);
+    let actions: Vec<_> = iter.all_actions_in_state(&state, &graph);
+    assert_eq!(actions, expected_actions);
+}
+
+#[test]
+fn actions_in_state_by_name() {
+    let graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 1, 2, 1], [1, 0, 1, 2], [2, 1, 0, 1], [1, 2, 1, 0]]),
+        branches: vec![vec![1], vec![0, 2], vec![1]],
+        connected: vec![true, false, true],
+        pfs: ndarray::arr1(&[0.5, 0.5, 0.5]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    let state: State = "[U U U] [3:0 3:0]".parse().unwrap();
+    let naive = actions_in_state(&graph, &state, "NaiveActions").unwrap();
+    assert_eq!(
+        naive,
+        NaiveActions::setup(&graph).all_actions_in_state(&state, &graph)
+    );
+    let permutational = actions_in_state(&graph, &state, "PermutationalActions").unwrap();
+    assert!(permutational.len() < naive.len());
+    assert!(permutational.iter().all(|action| naive.contains(action)));
+
+    assert!(matches!(
+        actions_in_state(&graph, &state, "UnknownActions"),
+        Err(SolveFailure::BadInput(_))
+    ));
+    let invalid: State = "[U U] [3:0 3:0]".parse().unwrap();
+    assert!(matches!(
+        actions_in_state(&graph, &invalid, "NaiveActions"),
+        Err(SolveFailure::BadInput(_))
+    ));
+}
+
+#[test]
+fn graph_symmetries() {
+    // Two identical feeders 1-3 and 2-4 from the connected bus 0.
+    let mut graph = Graph {
+        travel_times: Array2::default((5, 5)),
+        branches: vec![vec![1, 2], vec![0, 3], vec![0, 4], vec![1], vec![2]],
+        connected: vec![true, false, false, false, false],
+        pfs: ndarray::arr1(&[0.5, 0.25, 0.25, 0.5, 0.5]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    assert_eq!(graph.find_symmetries(), vec![vec![1, 2], vec![3, 4]]);
+
+    // Buses 1 and 2 are no longer interchangeable if their feeders differ.
+    graph.pfs[4] = 0.75;
+    assert!(graph.find_symmetries().is_empty());
+
+    // A ring of 6 buses and two rings of 3 buses have the same colors after refinement, but only
+    // the buses in the same kind of ring are interchangeable.
+    let graph = Graph {
+        travel_times: Array2::default((12, 12)),
+        branches: vec![
+            vec![1, 5],
+            vec![0, 2],
+            vec![1, 3],
+            vec![2, 4],
+            vec![3, 5],
+            vec![4, 0],
+            vec![7, 8],
+            vec![6, 8],
+            vec![6, 7],
+            vec![10, 11],
+            vec![9, 11],
+            vec![9, 10],
+        ],
+        connected: vec![false; 12],
+        pfs: ndarray::arr1(&[0.5; 12]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    assert_eq!(
+        graph.find_symmetries(),
+        vec![vec![0, 1, 2, 3, 4, 5], vec![6, 7, 8, 9, 10, 11]]
+    );
+
+    // The buses at the ends of a line are interchangeable, the one in the middle is not.
+    let graph = Graph {
+        travel_times: Array2::default((3, 3)),
+        branches: vec![vec![1], vec![0, 2], vec![1]],
+        connected: vec![false, true, false],
+        pfs: ndarray::arr1(&[0.5; 3]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    assert_eq!(graph.find_symmetries(), vec![vec![0, 2]]);
+}
+
+/// Action sets that only eliminate suboptimal actions, so the optimal value must stay the same.
+const EXACT_ACTION_SETS: [&str; 5] = [
+    "PermutationalActions",
+    "FilterOnWay<NaiveActions>",
+    "FilterOnWay<PermutationalActions>",
+    "FilterEnergizedOnWay<NaiveActions>",
+    "FilterEnergizedOnWay<PermutationalActions>",
+];
+
+/// Action sets that may eliminate optimal actions, but must still return a subset of
+/// [`NaiveActions`].
+const HEURISTIC_ACTION_SETS: [&str; 4] = [
+    "WaitMovingActions<NaiveActions>",
+    "WaitMovingActions<PermutationalActions>",
+    "FilterTeamSkill<NaiveActions>",
+    "FilterTeamSkill<PermutationalActions>",
+];
+
+/// Generate a random connected graph with the given number of buses and an extra team node.
+///
+/// Team nodes are random points in the plane and travel times are the rounded up distances
+/// between them, which satisfy the triangle inequality.
+fn random_graph(rng: &mut rand::rngs::StdRng, bus_count: usize) -> Graph {
+    use rand::Rng;
+    let mut branches: Vec<Vec<BusIndex>> = vec![Vec::new(); bus_count];
+    for i in 1..bus_count {
+        let j = rng.gen_range(0..i);
+        branches[i].push(j as BusIndex);
+        branches[j].push(i as BusIndex);
+        for j in (j + 1)..i {
+            if rng.gen_bool(0.2) {
+                branches[i].push(j as BusIndex);
+                branches[j].push(i as BusIndex);
+            }
+        }
+    }
+    let mut connected: Vec<bool> = (0..bus_count).map(|_| rng.gen_bool(0.2)).collect();
+    connected[0] = true;
+    let pfs: Array1<Probability> = (0..bus_count)
+        .map(|_| [0.1, 0.25, 0.5, 0.75][rng.gen_range(0..4)])
+        .collect();
+
+    let node_count = bus_count + 1;
+    let points: Vec<(f64, f64)> = (0..node_count)
+        .map(|_| (rng.gen_range(0.0..3.0), rng.gen_range(0.0..3.0)))
+        .collect();
+    let travel_times = Array2::from_shape_fn((node_count, node_count), |(i, j)| {
+        if i == j {
+            0
+        } else {
+            let (dx, dy) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+            ((dx * dx + dy * dy).sqrt().ceil() as Time).max(1)
+        }
+    });
+
+    Graph {
+        travel_times,
+        branches,
+        connected,
+        pfs,
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    }
+}
+
+/// Sample non-terminal states by random walks from the start state with [`NaiveActions`].
+fn random_states(
+    rng: &mut rand::rngs::StdRng,
+    graph: &Graph,
+    teams: &[TeamState],
+    count: usize,
+) -> Vec<State> {
+    use rand::Rng;
+    let mut states = Vec::new();
+    while states.len() < count {
+        let mut state = State::start_state(graph, teams.to_vec());
+        while !state.is_terminal(graph) && states.len() < count {
+            let actions = NaiveActions::setup(graph).all_actions_in_state(&state, graph);
+            let action = &actions[rng.gen_range(0..actions.len())];
+            let successors = NaiveActionApplier::apply(
+                &state.clone().to_action_state(graph),
+                state.get_cost(graph),
+                graph,
+                action,
+            )
+            .unwrap();
+            states.push(state);
+            state = successors[rng.gen_range(0..successors.len())].1.clone();
+        }
+    }
+    states
+}
+
+/// Differential test harness for action sets.
+///
+/// Generates random graphs and random reachable states, checks that each of the given action
+/// sets returns a nonempty subset of [`NaiveActions`] in every state, and if `exact` is true,
+/// checks that the optimal value of the whole MDP is the same as with [`NaiveActions`] for the
+/// same horizon.
+fn action_set_differential_test(action_sets: &[&str], exact: bool, seed: u64) {
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let config = Config::unlimited();
+
+    for _ in 0..20 {
+        let bus_count: usize = rng.gen_range(2..=5);
+        let graph = random_graph(&mut rng, bus_count);
+        let team_count = rng.gen_range(1..=2);
+        let teams: Vec<TeamState> = (0..team_count)
+            .map(|_| TeamState {
+                time: 0,
+                index: rng.gen_range(0..=bus_count) as BusIndex,
+            })
+            .collect();
+
+        for state in random_states(&mut rng, &graph, &teams, 20) {
+            let naive = actions_in_state(&graph, &state, "NaiveActions").unwrap();
+            for &action_set in action_sets {
+                let actions = actions_in_state(&graph, &state, action_set).unwrap();
+                assert!(
+                    !actions.is_empty(),
+                    "{action_set} has no actions in {state}"
+                );
+                for action in &actions {
+                    assert!(
+                        naive.contains(action),
+                        "{action_set} returned {action:?} in {state}, which is not in NaiveActions"
+                    );
+                }
+            }
+        }
+
+        if exact {
+            let solve = |action_set, config: &Config| {
+                solve_custom_regular(
+                    &graph,
+                    teams.clone(),
+                    config,
+                    "NaiveStateIndexer",
+                    action_set,
+                    "NaivePolicySynthesizer",
+                )
+                .unwrap()
+            };
+            let naive = solve("NaiveActions", &config);
+            let expected = naive.get_min_value();
+            // The automatic horizon is the longest path, which may be shorter with an action set
+            // that eliminates some actions. Terminal states keep incurring the cost of the
+            // damaged buses, so the values are only comparable for the same horizon.
+            let config = Config {
+                horizon: HorizonSpec::Absolute(naive.horizon),
+                ..config.clone()
+            };
+            for &action_set in action_sets {
+                let value = solve(action_set, &config).get_min_value();
+                assert!(
+                    (value - expected).abs() <= 1e-4 * expected.abs().max(1.0),
+                    "{action_set}: {value}, NaiveActions: {expected}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn exact_action_sets_differential() {
+    action_set_differential_test(&EXACT_ACTION_SETS, true, 0);
+}
+
+#[test]
+fn heuristic_action_sets_differential() {
+    action_set_differential_test(&HEURISTIC_ACTION_SETS, false, 1);
+}
+
+#[test]
+fn triangle_inequality_check() {
+    let graph = get_paper_example_graph();
+    assert!(graph.check_triangle_inequality().is_empty());
+
+    // Going from 0 to 2 through 1 is shorter than going directly.
+    let mut graph = Graph {
+        travel_times: ndarray::arr2(&[[0, 1, 5], [1, 0, 1], [5, 1, 0]]),
+        branches: vec![vec![1], vec![0, 2], vec![1]],
+        connected: vec![true, false, false],
+        pfs: ndarray::arr1(&[0.5, 0.5, 0.5]),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    assert_eq!(
+        graph.check_triangle_inequality(),
+        vec![(0, 1, 2), (2, 1, 0)]
+    );
+
+    graph.repair_triangle_inequality();
+    assert_eq!(
+        graph.travel_times,
+        ndarray::arr2(&[[0, 1, 2], [1, 0, 1], [2, 1, 0]])
+    );
+    assert!(graph.check_triangle_inequality().is_empty());
+}
+
+#[test]
+fn sorted_actions_test() {
+    let graph = get_paper_example_graph();
+    let state: State = "[TG U U TG U U] [0:0 3:0]".parse().unwrap();
+    let minbeta = state.compute_minbeta(&graph);
+
+    let mut naive = NaiveActions::setup(&graph).all_actions_in_state(&state, &graph);
+    let sorted = SortedActions::<NaiveActions>::setup(&graph).all_actions_in_state(&state, &graph);
+    let keys = sorted
+        .iter()
+        .map(|action| {
+            let idle_teams = action
+                .iter()
+                .filter(|&&target| minbeta[target as usize] != 1)
+                .count();
+            let total_time: Time = state
+                .teams
+                .iter()
+                .zip(action.iter())
+                .map(|(team, &target)| graph.travel_times[(team.index as usize, target as usize)])
+                .sum();
+            (idle_teams, total_time)
+        })
+        .collect::<Vec<_>>();
+    assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+    // Both teams go to the nearest energizable buses first.
+    assert_eq!(sorted[0], vec![1, 4]);
+
+    let mut sorted = sorted;
+    naive.sort();
+    sorted.sort();
+    assert_eq!(naive, sorted);
+}
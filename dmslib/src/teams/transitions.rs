@@ -19,7 +19,7 @@ fn min_time_until_arrival(
                 if action == team.index {
                     None
                 } else {
-                    Some(graph.travel_times[(team.index as usize, action as usize)])
+                    Some(graph.dispatch_time(team.index, action))
                 }
             } else {
                 debug_assert_eq!(action, team.index);
@@ -81,8 +81,7 @@ impl DetermineActionTime for TimeUntilEnergization {
             .zip(actions.iter())
             .filter_map(|(team, &action)| {
                 // Only consider buses that are energizable.
-                let beta = action_state.minbeta[action as usize];
-                if beta != 1 {
+                if action_state.minbeta.get(action as usize) != Some(&1) {
                     return None;
                 }
                 if team.time == 0 {
@@ -90,14 +89,22 @@ impl DetermineActionTime for TimeUntilEnergization {
                         action, team.index,
                         "A team cannot reach & wait on a bus without energizing it."
                     );
-                    let travel_time = graph.travel_times[(team.index as usize, action as usize)];
-                    Some(travel_time)
+                    Some(graph.dispatch_time(team.index, action))
                 } else {
                     debug_assert_eq!(action, team.index);
                     Some(team.time)
                 }
             })
             .min()
+            .or_else(|| {
+                // The progress condition is waived while the ready teams cannot satisfy it due to
+                // team_bus_allowed, wait for the next arrival in that case.
+                if action_state.progress_required {
+                    None
+                } else {
+                    min_time_until_arrival(graph, &action_state.state.teams, actions)
+                }
+            })
             .expect("Cannot get time until energization: progress condition is not satisfied")
     }
 }
@@ -115,7 +122,7 @@ fn advance_time_for_teams(
         .zip(actions.iter())
         .map(|(team, &action)| {
             let travel_time = if team.time == 0 {
-                graph.travel_times[(team.index as usize, action as usize)]
+                graph.dispatch_time(team.index, action)
             } else {
                 debug_assert_eq!(action, team.index);
                 team.time
@@ -132,14 +139,115 @@ fn advance_time_for_teams(
         .collect()
 }
 
+/// Minimum size of the initial alpha set for which the energization outcomes are expanded in
+/// parallel, see [`recursive_energization_with_threshold`].
+#[cfg(feature = "parallel")]
+const PARALLEL_ENERGIZATION_THRESHOLD: usize = 10;
+
+/// Compute alpha as defined in paper, i.e., the buses with a team that can be energized in the
+/// given state.
+#[inline]
+fn get_alpha(graph: &Graph, team_buses: &[BusIndex], state: &[BusState]) -> Vec<BusIndex> {
+    team_buses
+        .iter()
+        .cloned()
+        .filter(|i| {
+            let i = *i as usize;
+            state[i] == BusState::Unknown && {
+                graph.is_connected(i)
+                    || graph.branches[i]
+                        .iter()
+                        .any(|j| state[*j as usize] == BusState::Energized)
+            }
+        })
+        .collect()
+}
+
+/// Add each combination of energization outcomes of the buses in alpha to the queue.
+#[inline]
+fn add_permutations(
+    graph: &Graph,
+    p: Probability,
+    mut state: Vec<BusState>,
+    alpha: &[BusIndex],
+    queue: &mut Vec<(Probability, Vec<BusState>)>,
+) {
+    for &i in alpha {
+        state[i as usize] = BusState::Damaged;
+    }
+    'permutations: loop {
+        let p = alpha.iter().fold(p, |acc, &i| {
+            let pf = graph.pfs[i as usize];
+            acc * if state[i as usize] == BusState::Damaged {
+                pf
+            } else {
+                1.0 - pf
+            }
+        });
+        queue.push((p, state.clone()));
+        for i in alpha {
+            let i = *i as usize;
+            if state[i] == BusState::Damaged {
+                state[i] = BusState::Energized;
+                continue 'permutations;
+            } else {
+                state[i] = BusState::Damaged;
+            }
+        }
+        break 'permutations;
+    }
+}
+
+/// Energize the states in the queue recursively until no bus can be energized, and return the
+/// final outcomes.
+fn expand_outcomes(
+    graph: &Graph,
+    team_buses: &[BusIndex],
+    mut queue: Vec<(Probability, Vec<BusState>)>,
+) -> Vec<(Probability, Vec<BusState>)> {
+    let mut outcomes: Vec<(Probability, Vec<BusState>)> = Vec::new();
+    while let Some((p, state)) = queue.pop() {
+        let alpha = get_alpha(graph, team_buses, &state);
+        if alpha.is_empty() {
+            // Discard transitions with p = 0
+            if p != 0.0 {
+                outcomes.push((p, state));
+            }
+            continue;
+        }
+        add_permutations(graph, p, state, &alpha, &mut queue);
+    }
+    outcomes
+}
+
 /// Performs recursive energization with given team and bus state on the given graph.
 /// Returns a pair of bool and outcomes.
 /// The bool determines whether at least one energization happened.
 /// Outcomes are a list of probability and bus state pairs.
+#[inline]
 fn recursive_energization(
     graph: &Graph,
     teams: &[TeamState],
     buses: Vec<BusState>,
+) -> (bool, Vec<(Probability, Vec<BusState>)>) {
+    #[cfg(feature = "parallel")]
+    let parallel_threshold = PARALLEL_ENERGIZATION_THRESHOLD;
+    #[cfg(not(feature = "parallel"))]
+    let parallel_threshold = usize::MAX;
+    recursive_energization_with_threshold(graph, teams, buses, parallel_threshold)
+}
+
+/// Performs [`recursive_energization`], expanding the outcomes in parallel if the `parallel`
+/// feature is enabled and the initial alpha set has at least `parallel_threshold` buses.
+///
+/// There are `2^|alpha|` outcome combinations of the initial alpha set, and each is energized
+/// recursively in a separate task. The outcomes are in the same order as the serial version.
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn recursive_energization_with_threshold(
+    graph: &Graph,
+    teams: &[TeamState],
+    buses: Vec<BusState>,
+    parallel_threshold: usize,
 ) -> (bool, Vec<(Probability, Vec<BusState>)>) {
     // Buses on which a team is present
     let team_buses: Vec<BusIndex> = teams
@@ -153,88 +261,130 @@ fn recursive_energization(
         })
         .unique()
         .collect();
-    // All energization outcomes with probability.
-    let mut outcomes: Vec<(Probability, Vec<BusState>)> = Vec::new();
-    // Recursive energization process
+
+    // Handle initial state
+    let alpha = get_alpha(graph, &team_buses, &buses);
+    if alpha.is_empty() {
+        return (false, vec![(1.0, buses)]);
+    }
     let mut queue: Vec<(Probability, Vec<BusState>)> = Vec::new();
+    add_permutations(graph, 1.0, buses, &alpha, &mut queue);
 
-    /// Compute alpha as defined in paper
-    macro_rules! get_alpha {
-        ($state:expr) => {{
-            team_buses
-                .clone()
-                .into_iter()
-                .filter(|i| {
-                    let i = *i as usize;
-                    $state[i] == BusState::Unknown && {
-                        graph.connected[i]
-                            || graph.branches[i]
-                                .iter()
-                                .any(|j| $state[*j as usize] == BusState::Energized)
-                    }
-                })
-                .collect()
-        }};
+    #[cfg(feature = "parallel")]
+    if alpha.len() >= parallel_threshold {
+        use rayon::prelude::*;
+        // The serial version pops the last combination first and expands it completely before
+        // the others since the queue is a stack.
+        let outcomes = queue
+            .into_par_iter()
+            .rev()
+            .flat_map_iter(|next| expand_outcomes(graph, &team_buses, vec![next]))
+            .collect();
+        return (true, outcomes);
     }
-    /// Add permutations to the queue
-    macro_rules! add_permutations {
-        ($p:expr, $state:expr, $alpha:expr) => {{
-            for &i in &$alpha {
-                $state[i as usize] = BusState::Damaged;
-            }
-            'permutations: loop {
-                let p = $alpha.iter().fold($p, |acc, &i| {
-                    let pf = graph.pfs[i as usize];
-                    acc * if $state[i as usize] == BusState::Damaged {
-                        pf
-                    } else {
-                        1.0 - pf
-                    }
-                });
-                queue.push((p, $state.clone()));
-                for i in &$alpha {
-                    let i = *i as usize;
-                    if $state[i] == BusState::Damaged {
-                        $state[i] = BusState::Energized;
-                        continue 'permutations;
-                    } else {
-                        $state[i] = BusState::Damaged;
-                    }
-                }
-                break 'permutations;
-            }
-        }};
+
+    (true, expand_outcomes(graph, &team_buses, queue))
+}
+
+/// Approximate the outcomes of an action by drawing `max_outcomes` samples with replacement
+/// according to their probabilities, see [`Config::max_outcomes_per_action`].
+///
+/// The probability of each sampled outcome is its frequency in the samples, so the
+/// probabilities add up to 1. The outcomes are returned unchanged if there are at most
+/// `max_outcomes` of them. Otherwise, the sampled ones are returned in the original order.
+pub fn sample_outcomes<TT: Transition>(
+    outcomes: Vec<(TT, State)>,
+    max_outcomes: usize,
+    rng: &mut rand::rngs::StdRng,
+) -> Vec<(TT, State)> {
+    use rand::distributions::{Distribution, WeightedIndex};
+    if outcomes.len() <= max_outcomes {
+        return outcomes;
     }
+    let distribution = WeightedIndex::new(
+        outcomes
+            .iter()
+            .map(|(transition, _)| transition.get_probability() as f64),
+    )
+    .expect("Probabilities of outcomes must be valid weights");
+    let mut counts: Vec<usize> = vec![0; outcomes.len()];
+    for _ in 0..max_outcomes {
+        counts[distribution.sample(rng)] += 1;
+    }
+    outcomes
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|((transition, state), count)| {
+            let p = (count as f64 / max_outcomes as f64) as Probability;
+            let transition = TT::from_parts(
+                transition.get_successor(),
+                transition.get_cost(),
+                p,
+                transition.get_time(),
+            );
+            (transition, state)
+        })
+        .collect()
+}
 
-    // Handle initial state
-    {
-        let mut state = buses;
-        let alpha: Vec<BusIndex> = get_alpha!(state);
-        if alpha.is_empty() {
-            outcomes.push((1.0, state));
-            return (false, outcomes);
-        }
+/// Result of an energization attempt without moving the teams.
+///
+/// See [`State::energization_outcomes`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnergizationResult {
+    /// No bus can be energized by the teams in the state.
+    NotPossible,
+    /// Possible outcome states with their probabilities, which add up to 1.
+    /// Teams are the same as in the original state.
+    Outcomes(Vec<(Probability, State)>),
+}
 
-        add_permutations!(1.0, state, alpha);
+impl EnergizationResult {
+    /// Returns true if an energization can happen.
+    pub fn is_possible(&self) -> bool {
+        matches!(self, EnergizationResult::Outcomes(_))
     }
-    // Handle states in queue
-    while let Some(next) = queue.pop() {
-        let (p, mut state) = next;
-        let alpha: Vec<BusIndex> = get_alpha!(state);
-        if alpha.is_empty() {
-            // Discard transitions with p = 0
-            if p != 0.0 {
-                outcomes.push((p, state));
-            }
-            continue;
-        }
 
-        add_permutations!(p, state, alpha);
+    /// Get the outcomes, empty if no energization is possible.
+    pub fn outcomes(&self) -> &[(Probability, State)] {
+        match self {
+            EnergizationResult::NotPossible => &[],
+            EnergizationResult::Outcomes(outcomes) => outcomes,
+        }
     }
-    (true, outcomes)
 }
 
 impl State {
+    /// Attempt to energize without moving the teams and return the typed outcomes.
+    ///
+    /// This is a friendlier alternative to [`State::energize`] that builds the outcome states.
+    /// Panics if the probabilities of the outcomes don't add up to 1.
+    pub fn energization_outcomes(&self, graph: &Graph) -> EnergizationResult {
+        let Some(outcomes) = self.energize(graph) else {
+            return EnergizationResult::NotPossible;
+        };
+        let total: f64 = outcomes.iter().map(|(p, _)| *p as f64).sum();
+        assert!(
+            (total - 1.0).abs() < 1e-4,
+            "Probabilities of energization outcomes add up to {total}"
+        );
+        EnergizationResult::Outcomes(
+            outcomes
+                .into_iter()
+                .map(|(p, buses)| {
+                    (
+                        p,
+                        State {
+                            buses,
+                            teams: self.teams.clone(),
+                        },
+                    )
+                })
+                .collect(),
+        )
+    }
+
     /// Attempt to energize without moving the teams.
     pub fn energize(&self, graph: &Graph) -> Option<Vec<(Probability, Vec<BusState>)>> {
         let (success, outcomes) = recursive_energization(graph, &self.teams, self.buses.clone());
@@ -249,17 +399,53 @@ impl State {
     }
 }
 
+/// Get the number of teams that are busy when the given action is applied, i.e., the teams
+/// that are dispatched to another node or are already en route (including the repair time).
+#[inline]
+pub fn moving_team_count(graph: &Graph, teams: &[TeamState], actions: &[TeamAction]) -> usize {
+    teams
+        .iter()
+        .zip(actions.iter())
+        .filter(|(team, action)| team.time > 0 || graph.dispatch_time(team.index, **action) > 0)
+        .count()
+}
+
+/// Check whether the given action is valid for the given teams: there must be an action for each
+/// team, and the teams that are en route must continue to their current target.
+///
+/// Returns [`SolveFailure::BadInput`] otherwise.
+pub fn validate_action(teams: &[TeamState], actions: &[TeamAction]) -> Result<(), SolveFailure> {
+    if actions.len() != teams.len() {
+        return Err(SolveFailure::BadInput(format!(
+            "Action has {} entries but there are {} teams",
+            actions.len(),
+            teams.len()
+        )));
+    }
+    for (i, (team, &action)) in teams.iter().zip(actions.iter()).enumerate() {
+        if team.time > 0 && action != team.index {
+            return Err(SolveFailure::BadInput(format!(
+                "Team {i} is en route to bus {} but the action assigns it to bus {action}",
+                team.index
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Trait that contains methods to apply given actions at a given state.
 /// The resulting transitions will have TransitionType.
 pub trait ActionApplier<TransitionType: Transition> {
     /// Apply the action at given state, returning a list of transitions and the corresponding
     /// successor states.
+    ///
+    /// Returns [`SolveFailure::BadInput`] if the action is malformed, see [`validate_action`].
     fn apply(
         action_state: &ActionState,
         cost: Cost,
         graph: &Graph,
         actions: &[TeamAction],
-    ) -> Vec<(TransitionType, State)>;
+    ) -> Result<Vec<(TransitionType, State)>, SolveFailure>;
 
     /// Apply the action at given state, returning a list of transitions and the corresponding
     /// successor states.
@@ -271,7 +457,7 @@ pub trait ActionApplier<TransitionType: Transition> {
         cost: Cost,
         graph: &Graph,
         actions: &[TeamAction],
-    ) -> Vec<(TransitionType, State)> {
+    ) -> Result<Vec<(TransitionType, State)>, SolveFailure> {
         Self::apply(&state.clone().to_action_state(graph), cost, graph, actions)
     }
 }
@@ -287,25 +473,27 @@ impl ActionApplier<RegularTransition> for NaiveActionApplier {
         cost: Cost,
         graph: &Graph,
         actions: &[TeamAction],
-    ) -> Vec<(RegularTransition, State)> {
-        debug_assert_eq!(actions.len(), action_state.state.teams.len());
+    ) -> Result<Vec<(RegularTransition, State)>, SolveFailure> {
+        validate_action(&action_state.state.teams, actions)?;
         let teams = advance_time_for_teams(graph, &action_state.state.teams, actions, 1);
-        recursive_energization(graph, &teams, action_state.state.buses.clone())
-            .1
-            .into_iter()
-            .map(|(p, bus_state)| {
-                let transition = RegularTransition {
-                    successor: StateIndex::MAX,
-                    p,
-                    cost,
-                };
-                let successor_state = State {
-                    teams: teams.clone(),
-                    buses: bus_state,
-                };
-                (transition, successor_state)
-            })
-            .collect()
+        Ok(
+            recursive_energization(graph, &teams, action_state.state.buses.clone())
+                .1
+                .into_iter()
+                .map(|(p, bus_state)| {
+                    let transition = RegularTransition {
+                        successor: StateIndex::MAX,
+                        p,
+                        cost,
+                    };
+                    let successor_state = State {
+                        teams: teams.clone(),
+                        buses: bus_state,
+                    };
+                    (transition, successor_state)
+                })
+                .collect(),
+        )
     }
 }
 
@@ -325,27 +513,29 @@ impl<F: DetermineActionTime> ActionApplier<TimedTransition> for TimedActionAppli
         cost: Cost,
         graph: &Graph,
         actions: &[TeamAction],
-    ) -> Vec<(TimedTransition, State)> {
-        debug_assert_eq!(actions.len(), action_state.state.teams.len());
+    ) -> Result<Vec<(TimedTransition, State)>, SolveFailure> {
+        validate_action(&action_state.state.teams, actions)?;
         // Get minimum time until a team reaches its destination.
         let time: Time = F::get_time(graph, action_state, actions);
         let teams = advance_time_for_teams(graph, &action_state.state.teams, actions, time);
-        recursive_energization(graph, &teams, action_state.state.buses.clone())
-            .1
-            .into_iter()
-            .map(|(p, bus_state)| {
-                let transition = TimedTransition {
-                    successor: StateIndex::MAX,
-                    p,
-                    cost,
-                    time,
-                };
-                let successor_state = State {
-                    teams: teams.clone(),
-                    buses: bus_state,
-                };
-                (transition, successor_state)
-            })
-            .collect()
+        Ok(
+            recursive_energization(graph, &teams, action_state.state.buses.clone())
+                .1
+                .into_iter()
+                .map(|(p, bus_state)| {
+                    let transition = TimedTransition {
+                        successor: StateIndex::MAX,
+                        p,
+                        cost,
+                        time,
+                    };
+                    let successor_state = State {
+                        teams: teams.clone(),
+                        buses: bus_state,
+                    };
+                    (transition, successor_state)
+                })
+                .collect(),
+        )
     }
 }
@@ -16,6 +16,7 @@ fn test_min_time_until_arrival() {
         connected: vec![],
         pfs: ndarray::arr1(&[]),
         team_nodes: Array2::default((0, 0)),
+        ..Default::default()
     };
 
     assert_eq!(
@@ -342,6 +343,7 @@ fn ten_bus_linear_system() -> (Graph, Vec<BusState>) {
         ],
         pfs: ndarray::arr1(&[0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5]),
         team_nodes: Array2::default((0, 0)),
+        ..Default::default()
     };
 
     let bus_state = vec![
@@ -480,3 +482,71 @@ fn test_time_until_energization_progress() {
         &[1, 2, 6],
     );
 }
+
+#[test]
+fn test_energization_outcomes() {
+    let (graph, _) = ten_bus_linear_system();
+    let buses = vec![BusState::Unknown; 10];
+    // Bus 0 is connected to the source, bus 1 is not energizable before bus 0.
+    let state = State {
+        buses: buses.clone(),
+        teams: vec![TeamState { time: 0, index: 1 }],
+    };
+    assert_eq!(
+        state.energization_outcomes(&graph),
+        EnergizationResult::NotPossible
+    );
+    assert!(state.energization_outcomes(&graph).outcomes().is_empty());
+
+    let state = State {
+        buses,
+        teams: vec![
+            TeamState { time: 0, index: 0 },
+            TeamState { time: 3, index: 1 },
+        ],
+    };
+    let result = state.energization_outcomes(&graph);
+    assert!(result.is_possible());
+    let outcomes = result.outcomes();
+    assert_eq!(outcomes.len(), 2);
+    let total: f64 = outcomes.iter().map(|(p, _)| *p as f64).sum();
+    assert!((total - 1.0).abs() < 1e-6);
+    for (p, outcome) in outcomes {
+        assert_eq!(outcome.teams, state.teams);
+        let expected = match outcome.buses[0] {
+            BusState::Energized => 1.0 - graph.pfs[0],
+            BusState::Damaged => graph.pfs[0],
+            BusState::Unknown => panic!("Bus 0 is not energized or damaged"),
+        };
+        assert_eq!(*p, expected);
+        assert!(outcome.buses[1..].iter().all(|&b| b == BusState::Unknown));
+    }
+}
+
+#[test]
+fn test_parallel_recursive_energization() {
+    // 10 buses connected to the source, the first 5 of which have another bus behind them.
+    let mut branches: Vec<Vec<BusIndex>> = vec![Vec::new(); 15];
+    for i in 0..5 {
+        branches[i].push(10 + i as BusIndex);
+        branches[10 + i].push(i as BusIndex);
+    }
+    let graph = Graph {
+        travel_times: get_distance_matrix(15),
+        branches,
+        connected: (0..15).map(|i| i < 10).collect(),
+        pfs: (0..15).map(|i| [0.1, 0.25, 0.5, 0.75][i % 4]).collect(),
+        team_nodes: Array2::default((0, 0)),
+        ..Default::default()
+    };
+    let teams: Vec<TeamState> = (0..15).map(|index| TeamState { time: 0, index }).collect();
+    let buses = vec![BusState::Unknown; 15];
+
+    let serial = recursive_energization_with_threshold(&graph, &teams, buses.clone(), usize::MAX);
+    let parallel = recursive_energization_with_threshold(&graph, &teams, buses, 0);
+    assert!(serial.0);
+    assert_eq!(serial.1.len(), 2usize.pow(5) * 3usize.pow(5));
+    assert_eq!(serial, parallel);
+    let total: f64 = serial.1.iter().map(|(p, _)| *p as f64).sum();
+    assert!((total - 1.0).abs() < 1e-4);
+}
@@ -19,15 +19,17 @@ pub type Time = usize;
 pub type Time = u8;
 
 /// Data type for measuring transition costs.
-#[cfg(not(feature = "minmem"))]
+#[cfg(not(any(feature = "minmem", feature = "f32-transitions")))]
 pub type Cost = f64;
+#[cfg(all(not(feature = "minmem"), feature = "f32-transitions"))]
+pub type Cost = f32;
 #[cfg(feature = "minmem")]
 pub type Cost = BusIndex;
 
 /// Data type for probability.
-#[cfg(not(feature = "minmem"))]
+#[cfg(not(any(feature = "minmem", feature = "f32-transitions")))]
 pub type Probability = f64;
-#[cfg(feature = "minmem")]
+#[cfg(any(feature = "minmem", feature = "f32-transitions"))]
 pub type Probability = f32;
 
 /// Data type for the value function.
@@ -2,6 +2,7 @@
 
 use ndarray::Array2;
 use num_traits::{ToPrimitive, Unsigned};
+use rand::SeedableRng;
 
 /// Given 2 sorted iterators, returns true if at least one element is common.
 pub fn sorted_intersects<'a, T, IT>(mut a: IT, mut b: IT) -> bool
@@ -223,6 +224,25 @@ where
     out
 }
 
+/// Compute the 64-bit FNV-1a hash of the given bytes.
+///
+/// Unlike the default hasher, the result is stable across processes and Rust versions.
+pub fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+/// Create a random number generator with the given seed.
+///
+/// Used wherever randomness is needed in the solution process so that the results are
+/// reproducible.
+pub fn create_rng(seed: u64) -> rand::rngs::StdRng {
+    rand::rngs::StdRng::seed_from_u64(seed)
+}
+
 #[cfg(test)]
 #[allow(clippy::bool_assert_comparison)]
 mod tests {
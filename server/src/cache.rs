@@ -0,0 +1,125 @@
+//! In-memory LRU cache for solutions.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of solutions in the cache by default.
+pub const DEFAULT_MAX_ENTRIES: usize = 32;
+/// Maximum total size of solutions in the cache by default, in bytes.
+pub const DEFAULT_MAX_BYTES: usize = 256 * 1024 * 1024;
+
+/// A cached solution.
+struct Entry {
+    /// Serialized solution.
+    body: Arc<String>,
+    /// Value of the access counter when this entry was last used.
+    last_used: u64,
+}
+
+/// Mutable state of [`SolutionCache`].
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<u64, Entry>,
+    /// Incremented on each access to determine the least recently used entry.
+    access_counter: u64,
+    /// Total size of the cached solutions in bytes.
+    total_bytes: usize,
+}
+
+/// Thread-safe LRU cache that maps problem hashes to serialized solutions.
+///
+/// The cache is bounded by both the number of entries and their total size.
+/// Solutions are stored in serialized form so that their size is known exactly and they don't
+/// need to be serialized again on a hit.
+pub struct SolutionCache {
+    inner: Mutex<Inner>,
+    max_entries: usize,
+    max_bytes: usize,
+}
+
+impl SolutionCache {
+    /// Create an empty cache with the given limits.
+    pub fn new(max_entries: usize, max_bytes: usize) -> SolutionCache {
+        SolutionCache {
+            inner: Mutex::new(Inner::default()),
+            max_entries,
+            max_bytes,
+        }
+    }
+
+    /// Get the solution with the given key and mark it as recently used.
+    pub fn get(&self, key: u64) -> Option<Arc<String>> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.access_counter += 1;
+        let access_counter = inner.access_counter;
+        let entry = inner.entries.get_mut(&key)?;
+        entry.last_used = access_counter;
+        Some(entry.body.clone())
+    }
+
+    /// Insert a solution with the given key, evicting the least recently used entries if the
+    /// limits are exceeded.
+    ///
+    /// Solutions larger than the total size limit are not cached.
+    pub fn insert(&self, key: u64, body: String) {
+        if body.len() > self.max_bytes || self.max_entries == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        inner.access_counter += 1;
+        let entry = Entry {
+            body: Arc::new(body),
+            last_used: inner.access_counter,
+        };
+        inner.total_bytes += entry.body.len();
+        if let Some(old) = inner.entries.insert(key, entry) {
+            inner.total_bytes -= old.body.len();
+        }
+        while inner.entries.len() > self.max_entries || inner.total_bytes > self.max_bytes {
+            let lru_key = *inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key)
+                .unwrap();
+            let removed = inner.entries.remove(&lru_key).unwrap();
+            inner.total_bytes -= removed.body.len();
+        }
+    }
+
+    /// Get the number of cached solutions.
+    #[cfg(test)]
+    pub fn entry_count(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+}
+
+impl Default for SolutionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ENTRIES, DEFAULT_MAX_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lru_eviction() {
+        let cache = SolutionCache::new(2, 10);
+        cache.insert(0, "aaa".to_string());
+        cache.insert(1, "bbb".to_string());
+        // Access 0 so that 1 is the least recently used.
+        assert_eq!(cache.get(0).as_deref().map(String::as_str), Some("aaa"));
+        cache.insert(2, "ccc".to_string());
+        assert_eq!(cache.entry_count(), 2);
+        assert!(cache.get(1).is_none());
+        assert!(cache.get(0).is_some());
+
+        // Size limit
+        cache.insert(3, "dddddddd".to_string());
+        assert_eq!(cache.entry_count(), 1);
+        assert!(cache.get(3).is_some());
+        cache.insert(4, "too long to cache".to_string());
+        assert!(cache.get(4).is_none());
+    }
+}
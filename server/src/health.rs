@@ -0,0 +1,62 @@
+//! Liveness and readiness checks for orchestration, e.g., Kubernetes probes.
+use std::path::PathBuf;
+
+/// Trivial problem that is solved once at startup to check that solving works: a single bus with
+/// a team on it, directly connected to an energy source.
+const SELF_TEST_PROBLEM: &str = r#"{
+    "graph": {
+        "name": "Self-test",
+        "branches": [],
+        "externalBranches": [{ "node": 0, "source": 0 }],
+        "nodes": [{ "pf": 0.5, "latlng": [0.0, 0.0] }],
+        "resources": [{ "latlng": [0.0, 0.0], "type": null }]
+    },
+    "teams": [{ "index": 0 }]
+}"#;
+
+/// Solve [`SELF_TEST_PROBLEM`] and return the reason on failure.
+fn self_test() -> Result<(), String> {
+    let problem: dmslib::io::TeamProblem = serde_json::from_str(SELF_TEST_PROBLEM)
+        .map_err(|e| format!("Cannot parse the self-test problem: {e}"))?;
+    let solution = problem
+        .solve_naive()
+        .map_err(|e| format!("Self-test solve failed: {e}"))?;
+    if solution.transitions.is_empty() {
+        return Err("Self-test solve returned an empty solution".to_string());
+    }
+    Ok(())
+}
+
+/// Readiness state of the server.
+pub struct Health {
+    /// Directories that must exist and be readable, e.g., static files and graphs.
+    paths: Vec<PathBuf>,
+    /// Result of the self-test solve at startup, see [`SELF_TEST_PROBLEM`].
+    self_test: Result<(), String>,
+}
+
+impl Health {
+    /// Run the self-test solve and create the readiness state with the given required
+    /// directories.
+    pub fn new(paths: Vec<PathBuf>) -> Health {
+        let self_test = self_test();
+        if let Err(e) = &self_test {
+            log::error!("{e}");
+        }
+        Health { paths, self_test }
+    }
+
+    /// Check whether the server is ready to serve requests: the self-test solve succeeded and
+    /// the required directories are readable. The directories are checked on each call.
+    ///
+    /// Returns the reason on failure.
+    pub fn check(&self) -> Result<(), String> {
+        self.self_test.clone()?;
+        for path in self.paths.iter() {
+            if let Err(e) = std::fs::read_dir(path) {
+                return Err(format!("Cannot read {}: {e}", path.display()));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
 use std::net::SocketAddr;
+use std::sync::Arc;
 
+mod cache;
+mod health;
+mod metrics;
 mod routes;
 
 #[tokio::main]
@@ -14,7 +18,10 @@ async fn main() {
             return;
         }
     };
-    let api = routes::api();
+    let metrics = Arc::new(metrics::Metrics::new());
+    let cache = Arc::new(cache::SolutionCache::default());
+    let health = Arc::new(health::Health::new(routes::required_paths()));
+    let api = routes::api(metrics, cache, health);
 
     let server = warp::serve(api).run(addr);
     server.await;
@@ -0,0 +1,121 @@
+//! Prometheus metrics for monitoring the server.
+use std::sync::Arc;
+
+use dmslib::teams::ExplorationGauge;
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
+
+/// Metrics collected by the server, registered in their own [`Registry`].
+pub struct Metrics {
+    registry: Registry,
+    /// Number of solve requests that are started.
+    pub solves_started: IntCounter,
+    /// Number of solve requests that completed successfully.
+    pub solves_completed: IntCounter,
+    /// Number of solve requests that failed.
+    pub solves_failed: IntCounter,
+    /// Number of solve requests answered from the solution cache.
+    pub cache_hits: IntCounter,
+    /// Duration of successful solves in seconds.
+    pub solve_duration: Histogram,
+    /// Number of states in the solutions.
+    pub state_count: Histogram,
+    /// Number of bytes currently allocated, updated on each scrape.
+    pub memory: IntGauge,
+    /// Progress of the latest exploration, passed to the solves in
+    /// [`dmslib::teams::Config::exploration_gauge`].
+    pub exploration: Arc<ExplorationGauge>,
+    /// Number of states indexed in the latest exploration, updated on each scrape.
+    pub indexed_states: IntGauge,
+    /// Number of states waiting to be explored in the latest exploration, updated on each
+    /// scrape.
+    pub frontier_size: IntGauge,
+}
+
+impl Metrics {
+    /// Create the metrics and register them in a new registry.
+    pub fn new() -> Metrics {
+        let registry = Registry::new();
+        let solves_started =
+            IntCounter::new("solves_started_total", "Number of solves started").unwrap();
+        let solves_completed =
+            IntCounter::new("solves_completed_total", "Number of solves completed").unwrap();
+        let solves_failed =
+            IntCounter::new("solves_failed_total", "Number of solves failed").unwrap();
+        let cache_hits = IntCounter::new(
+            "solution_cache_hits_total",
+            "Number of solves answered from cache",
+        )
+        .unwrap();
+        let solve_duration = Histogram::with_opts(HistogramOpts::new(
+            "solve_duration_seconds",
+            "Duration of successful solves in seconds",
+        ))
+        .unwrap();
+        let state_count = Histogram::with_opts(
+            HistogramOpts::new("solve_state_count", "Number of states in solutions")
+                .buckets(prometheus::exponential_buckets(10.0, 10.0, 7).unwrap()),
+        )
+        .unwrap();
+        let memory = IntGauge::new(
+            "allocated_memory_bytes",
+            "Number of bytes currently allocated",
+        )
+        .unwrap();
+        let indexed_states = IntGauge::new(
+            "exploration_indexed_states",
+            "Number of states indexed in the latest exploration",
+        )
+        .unwrap();
+        let frontier_size = IntGauge::new(
+            "exploration_frontier_size",
+            "Number of states waiting to be explored in the latest exploration",
+        )
+        .unwrap();
+
+        registry.register(Box::new(solves_started.clone())).unwrap();
+        registry
+            .register(Box::new(solves_completed.clone()))
+            .unwrap();
+        registry.register(Box::new(solves_failed.clone())).unwrap();
+        registry.register(Box::new(cache_hits.clone())).unwrap();
+        registry.register(Box::new(solve_duration.clone())).unwrap();
+        registry.register(Box::new(state_count.clone())).unwrap();
+        registry.register(Box::new(memory.clone())).unwrap();
+        registry.register(Box::new(indexed_states.clone())).unwrap();
+        registry.register(Box::new(frontier_size.clone())).unwrap();
+
+        Metrics {
+            registry,
+            solves_started,
+            solves_completed,
+            solves_failed,
+            cache_hits,
+            solve_duration,
+            state_count,
+            memory,
+            exploration: Arc::new(ExplorationGauge::new()),
+            indexed_states,
+            frontier_size,
+        }
+    }
+
+    /// Encode the current values of the metrics in Prometheus text format.
+    pub fn encode(&self) -> String {
+        self.memory.set(dmslib::allocated_memory() as i64);
+        self.indexed_states
+            .set(self.exploration.indexed_states() as i64);
+        self.frontier_size
+            .set(self.exploration.frontier_size() as i64);
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
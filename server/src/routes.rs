@@ -1,47 +1,207 @@
 //! Server routes module.
 use dmslib::io::fs::*;
-use dmslib::GRAPHS_PATH;
+use dmslib::{EXPERIMENTS_PATH, GRAPHS_PATH};
 
-use std::path::Path;
-use warp::{filters::BoxedFilter, Filter, Reply};
+use crate::cache::SolutionCache;
+use crate::health::Health;
+use crate::metrics::Metrics;
+use flate2::read::GzDecoder;
+use serde::de::DeserializeOwned;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 use warp::{http::StatusCode, reply};
 
 /// Path to static files for the client.
 pub const STATIC_PATH: &str = "../client";
 
+/// Get the directories that must be readable for the server to be ready, see [`Health`].
+pub fn required_paths() -> Vec<PathBuf> {
+    [STATIC_PATH, GRAPHS_PATH, EXPERIMENTS_PATH]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
 /// Content length limit for JSON requests.
 const JSON_CONTENT_LIMIT: u64 = 8 * 1024 * 1024;
 
+/// Size limit for the decompressed JSON requests, which prevents decompression bombs.
+const JSON_DECOMPRESSED_LIMIT: u64 = 64 * 1024 * 1024;
+
+/// Rejection for request bodies that cannot be decoded.
+#[derive(Debug)]
+enum BodyError {
+    /// The decompressed body exceeds [`JSON_DECOMPRESSED_LIMIT`].
+    TooLarge,
+    /// The body has an unsupported encoding, or it is not valid gzip or JSON.
+    Invalid(String),
+}
+
+impl warp::reject::Reject for BodyError {}
+
+/// Decode the request body as JSON, decompressing it first if it has `Content-Encoding: gzip`.
+fn json_body<T: DeserializeOwned + Send>() -> impl Filter<Extract = (T,), Error = Rejection> + Clone
+{
+    warp::body::content_length_limit(JSON_CONTENT_LIMIT)
+        .and(warp::header::optional::<String>("content-encoding"))
+        .and(warp::body::bytes())
+        .and_then(
+            |encoding: Option<String>, body: warp::hyper::body::Bytes| async move {
+                decode_json_body(encoding.as_deref(), &body).map_err(warp::reject::custom)
+            },
+        )
+}
+
+/// Decode the given request body with the given content encoding as JSON.
+fn decode_json_body<T: DeserializeOwned>(
+    encoding: Option<&str>,
+    body: &[u8],
+) -> Result<T, BodyError> {
+    match encoding.map(str::trim) {
+        None | Some("identity") => {
+            serde_json::from_slice(body).map_err(|e| BodyError::Invalid(e.to_string()))
+        }
+        Some(encoding) if encoding.eq_ignore_ascii_case("gzip") => {
+            let mut decompressed = Vec::new();
+            // Read one more byte than the limit to detect oversized bodies.
+            GzDecoder::new(body)
+                .take(JSON_DECOMPRESSED_LIMIT + 1)
+                .read_to_end(&mut decompressed)
+                .map_err(|e| BodyError::Invalid(format!("Invalid gzip body: {e}")))?;
+            if decompressed.len() as u64 > JSON_DECOMPRESSED_LIMIT {
+                return Err(BodyError::TooLarge);
+            }
+            serde_json::from_slice(&decompressed).map_err(|e| BodyError::Invalid(e.to_string()))
+        }
+        Some(encoding) => Err(BodyError::Invalid(format!(
+            "Unsupported content encoding: {encoding}"
+        ))),
+    }
+}
+
+/// Convert [`BodyError`] rejections to replies with the corresponding status codes.
+async fn recover_body_error(rejection: Rejection) -> Result<reply::Response, Rejection> {
+    match rejection.find::<BodyError>() {
+        Some(BodyError::TooLarge) => Ok(reply::with_status(
+            reply::json(&"The decompressed request body is too large."),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        )
+        .into_response()),
+        Some(BodyError::Invalid(error)) => Ok(reply::with_status(
+            reply::json(&format!("Error while decoding the request body: {error}")),
+            StatusCode::BAD_REQUEST,
+        )
+        .into_response()),
+        None => Err(rejection),
+    }
+}
+
 /// Every route combined for a single network
-pub fn api() -> BoxedFilter<(impl Reply,)> {
+pub fn api(
+    metrics: Arc<Metrics>,
+    cache: Arc<SolutionCache>,
+    health: Arc<Health>,
+) -> BoxedFilter<(impl Reply,)> {
     let static_files = warp::any().and(warp::fs::dir(STATIC_PATH));
     let graph_files = warp::path("graphs").and(warp::fs::dir(GRAPHS_PATH));
+    let with_metrics = warp::any().map(move || metrics.clone());
+    let with_cache = warp::any().map(move || cache.clone());
 
     graph_files
         .or(static_files)
         .or(warp::path!("policy")
             .and(warp::post())
-            .and(warp::body::content_length_limit(JSON_CONTENT_LIMIT))
-            .and(warp::body::json())
-            .map(|req: dmslib::io::TeamProblem| {
-                // TODO: Make optimization selection configurable from UI
-                // Use optimizations by default
-                let solution = req.solve_custom_timed(
-                    // NOTE: The client cannot handle sorted teams yet.
-                    "BitStackStateIndexer",
-                    "FilterEnergizedOnWay<PermutationalActions>",
-                    "TimedActionApplier<TimeUntilEnergization>",
-                );
-                // Naive solution:
-                // let solution = req.solve_naive();
-                let solution = match solution {
-                    Ok(x) => x,
-                    Err(e) => {
-                        let error = format!("Error while generating a solution: {e}");
-                        return reply::with_status(reply::json(&error), StatusCode::BAD_REQUEST);
+            .and(json_body())
+            .and(with_metrics.clone())
+            .and(with_cache)
+            .map(
+                |req: dmslib::io::TeamProblem, metrics: Arc<Metrics>, cache: Arc<SolutionCache>| {
+                    let key = req.canonical_hash();
+                    if let Some(body) = cache.get(key) {
+                        metrics.cache_hits.inc();
+                        return reply::with_header(
+                            body.as_ref().clone(),
+                            "content-type",
+                            "application/json",
+                        )
+                        .into_response();
                     }
-                };
-                reply::with_status(reply::json(&solution), StatusCode::OK)
+                    metrics.solves_started.inc();
+                    let start_time = Instant::now();
+                    // TODO: Make optimization selection configurable from UI
+                    // Use optimizations by default
+                    let (team_names, node_names) = req.get_names();
+                    let solution = req.prepare().and_then(|(problem, config)| {
+                        let config = dmslib::teams::Config {
+                            exploration_gauge: Some(metrics.exploration.clone()),
+                            ..config
+                        };
+                        let solution = dmslib::teams::solve_custom_timed(
+                            &problem.graph,
+                            problem.initial_teams,
+                            &config,
+                            // NOTE: The client cannot handle sorted teams yet.
+                            "BitStackStateIndexer",
+                            "FilterEnergizedOnWay<PermutationalActions>",
+                            "TimedActionApplier<TimeUntilEnergization>",
+                            "NaiveTimedPolicySynthesizer",
+                        )?;
+                        Ok(solution
+                            .into_io(problem.graph)
+                            .with_names(team_names, node_names))
+                    });
+                    // Naive solution:
+                    // let solution = req.solve_naive();
+                    let solution = match solution {
+                        Ok(x) => x,
+                        Err(e) => {
+                            metrics.solves_failed.inc();
+                            let error = format!("Error while generating a solution: {e}");
+                            return reply::with_status(
+                                reply::json(&error),
+                                StatusCode::BAD_REQUEST,
+                            )
+                            .into_response();
+                        }
+                    };
+                    metrics.solves_completed.inc();
+                    metrics
+                        .solve_duration
+                        .observe(start_time.elapsed().as_secs_f64());
+                    metrics.state_count.observe(solution.states.nrows() as f64);
+                    let body = match serde_json::to_string(&solution) {
+                        Ok(body) => body,
+                        Err(e) => {
+                            let error = format!("Error while serializing the solution: {e}");
+                            return reply::with_status(
+                                reply::json(&error),
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                            )
+                            .into_response();
+                        }
+                    };
+                    cache.insert(key, body.clone());
+                    reply::with_header(body, "content-type", "application/json").into_response()
+                },
+            ))
+        .or(warp::path!("metrics")
+            .and(warp::get())
+            .and(with_metrics)
+            .map(|metrics: Arc<Metrics>| metrics.encode()))
+        .or(warp::path!("healthz")
+            .and(warp::get())
+            .map(|| reply::with_status("OK".to_string(), StatusCode::OK)))
+        .or(warp::path!("readyz")
+            .and(warp::get())
+            .map(move || match health.check() {
+                Ok(()) => reply::with_status("OK".to_string(), StatusCode::OK),
+                Err(reason) => {
+                    log::warn!("Server is not ready: {reason}");
+                    reply::with_status(reason, StatusCode::SERVICE_UNAVAILABLE)
+                }
             }))
         .or(warp::path!("get-graphs").and(warp::get()).map(|| {
             match list_graphs(Path::new(GRAPHS_PATH)) {
@@ -55,8 +215,7 @@ pub fn api() -> BoxedFilter<(impl Reply,)> {
         }))
         .or(warp::path!("save-problem")
             .and(warp::post())
-            .and(warp::body::content_length_limit(JSON_CONTENT_LIMIT))
-            .and(warp::body::json())
+            .and(json_body())
             .map(|mut req: serde_json::Value| {
                 match req.as_object_mut() {
                     Some(map) => {
@@ -73,7 +232,7 @@ pub fn api() -> BoxedFilter<(impl Reply,)> {
                     Ok(_) => reply::with_status("OK".to_string(), StatusCode::OK),
                     Err(e) => reply::with_status(
                         e.to_string(),
-                        if e.kind() == std::io::ErrorKind::Other {
+                        if matches!(e, dmslib::DmsError::Validation(_)) {
                             StatusCode::BAD_REQUEST
                         } else {
                             StatusCode::INTERNAL_SERVER_ERROR
@@ -81,5 +240,205 @@ pub fn api() -> BoxedFilter<(impl Reply,)> {
                     ),
                 }
             }))
+        .recover(recover_body_error)
         .boxed()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_GRAPH: &str = include_str!("../../graphs/FieldTeams/paperE0.json");
+
+    /// Get the routes with the default required paths.
+    fn test_api(metrics: Arc<Metrics>, cache: Arc<SolutionCache>) -> BoxedFilter<(impl Reply,)> {
+        api(metrics, cache, Arc::new(Health::new(required_paths())))
+    }
+
+    /// Get the value of a metric without labels from the text encoding.
+    fn get_metric(text: &str, name: &str) -> f64 {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn metrics_test() {
+        let api = test_api(Arc::new(Metrics::new()), Arc::new(SolutionCache::default()));
+
+        let graph: serde_json::Value = serde_json::from_str(TEST_GRAPH).unwrap();
+        let problem = serde_json::json!({
+            "graph": graph,
+            "teams": [{ "index": 0 }],
+        });
+        let response = warp::test::request()
+            .method("POST")
+            .path("/policy")
+            .json(&problem)
+            .reply(&api)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = warp::test::request()
+            .method("GET")
+            .path("/metrics")
+            .reply(&api)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let text = String::from_utf8(response.body().to_vec()).unwrap();
+        assert_eq!(get_metric(&text, "solves_started_total"), 1.0);
+        assert_eq!(get_metric(&text, "solves_completed_total"), 1.0);
+        assert_eq!(get_metric(&text, "solves_failed_total"), 0.0);
+        assert_eq!(get_metric(&text, "solve_duration_seconds_count"), 1.0);
+        assert_eq!(get_metric(&text, "solve_state_count_count"), 1.0);
+        assert!(get_metric(&text, "allocated_memory_bytes") > 0.0);
+        assert!(get_metric(&text, "exploration_indexed_states") > 0.0);
+        assert_eq!(get_metric(&text, "exploration_frontier_size"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn solution_cache_test() {
+        let metrics = Arc::new(Metrics::new());
+        let cache = Arc::new(SolutionCache::default());
+        let api = test_api(metrics.clone(), cache.clone());
+
+        let graph: serde_json::Value = serde_json::from_str(TEST_GRAPH).unwrap();
+        let mut problem = serde_json::json!({
+            "graph": graph,
+            "teams": [{ "index": 0 }],
+        });
+        let mut bodies = Vec::new();
+        for _ in 0..2 {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/policy")
+                .json(&problem)
+                .reply(&api)
+                .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            bodies.push(response.body().clone());
+        }
+        assert_eq!(bodies[0], bodies[1]);
+        assert_eq!(metrics.solves_started.get(), 1);
+        assert_eq!(metrics.cache_hits.get(), 1);
+        assert_eq!(cache.entry_count(), 1);
+
+        // Changing horizon results in a cache miss.
+        problem["horizon"] = serde_json::json!(30);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/policy")
+            .json(&problem)
+            .reply(&api)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(metrics.solves_started.get(), 2);
+        assert_eq!(metrics.cache_hits.get(), 1);
+        assert_eq!(cache.entry_count(), 2);
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn gzip_body_test() {
+        let graph: serde_json::Value = serde_json::from_str(TEST_GRAPH).unwrap();
+        let problem = serde_json::json!({
+            "graph": graph,
+            "teams": [{ "index": 0 }],
+        });
+        let body = serde_json::to_vec(&problem).unwrap();
+
+        let mut solutions = Vec::new();
+        for encoding in [None, Some("gzip")] {
+            // Separate caches so that both requests are solved.
+            let api = test_api(Arc::new(Metrics::new()), Arc::new(SolutionCache::default()));
+            let mut request = warp::test::request().method("POST").path("/policy");
+            request = match encoding {
+                Some(encoding) => request
+                    .header("content-encoding", encoding)
+                    .body(gzip(&body)),
+                None => request.body(body.clone()),
+            };
+            let response = request.reply(&api).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let solution: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+            solutions.push(solution);
+        }
+        for field in [
+            "states",
+            "teams",
+            "transitions",
+            "values",
+            "policy",
+            "horizon",
+        ] {
+            assert_eq!(solutions[0][field], solutions[1][field]);
+        }
+
+        let api = test_api(Arc::new(Metrics::new()), Arc::new(SolutionCache::default()));
+        // Decompression bomb.
+        let bomb = gzip(&vec![b' '; JSON_DECOMPRESSED_LIMIT as usize + 1]);
+        assert!((bomb.len() as u64) < JSON_CONTENT_LIMIT);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/policy")
+            .header("content-encoding", "gzip")
+            .body(bomb)
+            .reply(&api)
+            .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Invalid gzip and unsupported encodings.
+        for encoding in ["gzip", "br"] {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/policy")
+                .header("content-encoding", encoding)
+                .body(body.clone())
+                .reply(&api)
+                .await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn health_test() {
+        let metrics = Arc::new(Metrics::new());
+        let cache = Arc::new(SolutionCache::default());
+        let routes = test_api(metrics.clone(), cache.clone());
+        for path in ["/healthz", "/readyz"] {
+            let response = warp::test::request()
+                .method("GET")
+                .path(path)
+                .reply(&routes)
+                .await;
+            assert_eq!(response.status(), StatusCode::OK, "{path}");
+        }
+
+        let mut paths = required_paths();
+        paths.push(PathBuf::from("../this-path-does-not-exist"));
+        let routes = api(metrics, cache, Arc::new(Health::new(paths)));
+        let response = warp::test::request()
+            .method("GET")
+            .path("/healthz")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = warp::test::request()
+            .method("GET")
+            .path("/readyz")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = String::from_utf8(response.body().to_vec()).unwrap();
+        assert!(body.contains("this-path-does-not-exist"), "{body}");
+    }
+}